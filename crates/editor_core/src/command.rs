@@ -0,0 +1,49 @@
+/**
+ * path: /crates/editor_core/src/command.rs
+ * description: The `Command` trait: one undoable edit of the authoring world.
+ */
+use std::any::Any;
+
+use bevy_ecs::world::World;
+use engine_ecs::ReflectError;
+use engine_scene::{
+    EntityUid,
+    SceneError,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("no entity {0} in the world")]
+    MissingEntity(EntityUid),
+    #[error("entity {0} already exists")]
+    DuplicateEntity(EntityUid),
+    #[error("can't attach {entity} to {parent}, which is below it")]
+    ParentCycle {
+        entity: EntityUid,
+        parent: EntityUid,
+    },
+    #[error("scene: {0}")]
+    Scene(#[from] SceneError),
+    #[error("reflect: {0}")]
+    Reflect(#[from] ReflectError),
+}
+
+/// An edit that can be undone. `apply` runs when the command is executed and again on redo,
+/// `undo` after it was applied; a command records whatever it needs to undo (previous values,
+/// removed entities) while applying. Scene entities are addressed by `EntityUid`, which stays
+/// the same when undo/redo respawns them.
+pub trait Command: Any + Send + Sync {
+    /// What the command does, for menus ("Undo Rename").
+    fn name(&self) -> &str;
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError>;
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError>;
+
+    /// Fold the command applied right after this one into it, so both undo as one step (e.g.
+    /// every frame of a drag setting the same field). Returns whether it did.
+    fn merge(&mut self, _next: &dyn Command) -> bool {
+        false
+    }
+}
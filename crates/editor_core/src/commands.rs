@@ -0,0 +1,623 @@
+/**
+ * path: /crates/editor_core/src/commands.rs
+ * description: Commands on the scene entities of the authoring world: spawn, despawn, set a
+ * component field by reflection path, reparent and rename. Entities are found through the
+ * `EntityMap` and changed with scene patches (`apply_scene_patch`), so the hierarchy, the map
+ * and global transforms stay consistent.
+ */
+use bevy_ecs::entity::Entity as EcsEntity;
+use bevy_ecs::world::World;
+use bevy_reflect::Reflect;
+use engine_scene::{
+    apply_scene_patch,
+    extract_entity,
+    Children,
+    Entity,
+    EntityMap,
+    EntityUid,
+    Name,
+    Parent,
+    SceneChange,
+    SceneEntityId,
+    SceneInstance,
+    ScenePatch,
+};
+
+use crate::command::{
+    Command,
+    CommandError,
+};
+
+/// Spawn a scene entity; with a `parent` it becomes that entity's last child. New entities
+/// need a fresh id (`EntityUid::new()`).
+pub struct SpawnEntity {
+    entity: Entity,
+}
+
+impl SpawnEntity {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+impl Command for SpawnEntity {
+    fn name(&self) -> &str {
+        "Spawn"
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        if resolve(world, self.entity.id).is_ok() {
+            return Err(CommandError::DuplicateEntity(self.entity.id));
+        }
+        if let Some(parent) = self.entity.parent {
+            resolve(world, parent)?;
+        }
+        apply_changes(world, vec![SceneChange::AddEntity(self.entity.clone())])
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        resolve(world, self.entity.id)?;
+        apply_changes(world, vec![SceneChange::RemoveEntity(self.entity.clone())])
+    }
+}
+
+/// Despawn an entity with the scene entities attached below it. Undo restores them as they
+/// were, at the same place among their siblings. Entities below it that aren't scene entities
+/// (no `SceneEntityId`) are only detached.
+pub struct DespawnEntity {
+    entity: EntityUid,
+    /// Parents before children.
+    removed: Vec<Entity>,
+    index: Option<usize>,
+}
+
+impl DespawnEntity {
+    pub fn new(entity: EntityUid) -> Self {
+        Self {
+            entity,
+            removed: Vec::new(),
+            index: None,
+        }
+    }
+}
+
+impl Command for DespawnEntity {
+    fn name(&self) -> &str {
+        "Despawn"
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let root = resolve(world, self.entity)?;
+        let mut removed = Vec::new();
+        let mut stack = vec![root];
+        while let Some(next) = stack.pop() {
+            if let Some(entity) = extract_entity(world, next) {
+                removed.push(entity?);
+            }
+            if let Some(children) = world.get::<Children>(next) {
+                stack.extend(children.0.iter().rev());
+            }
+        }
+        self.index = child_index(world, root);
+        let changes = removed
+            .iter()
+            .rev()
+            .map(|entity| SceneChange::RemoveEntity(entity.clone()))
+            .collect();
+        apply_changes(world, changes)?;
+        self.removed = removed;
+        Ok(())
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let changes = self
+            .removed
+            .iter()
+            .map(|entity| SceneChange::AddEntity(entity.clone()))
+            .collect();
+        apply_changes(world, changes)?;
+        if let Some(index) = self.index {
+            set_child_index(world, resolve(world, self.entity)?, index);
+        }
+        Ok(())
+    }
+}
+
+/// Set a field of a reflected component by path, e.g. `("Transform2D", "position.0")`; `""`
+/// sets the whole component. The value must have the field's type. Consecutive sets of the
+/// same field merge.
+pub struct SetField {
+    entity: EntityUid,
+    component: String,
+    path: String,
+    label: String,
+    value: Box<dyn Reflect>,
+    old: Option<Box<dyn Reflect>>,
+}
+
+impl SetField {
+    pub fn new(
+        entity: EntityUid,
+        component: impl Into<String>,
+        path: impl Into<String>,
+        value: impl Reflect,
+    ) -> Self {
+        Self::from_reflect(entity, component, path, Box::new(value))
+    }
+
+    pub fn from_reflect(
+        entity: EntityUid,
+        component: impl Into<String>,
+        path: impl Into<String>,
+        value: Box<dyn Reflect>,
+    ) -> Self {
+        let (component, path) = (component.into(), path.into());
+        let label = match path.is_empty() {
+            true => format!("Set {component}"),
+            false => format!("Set {component}.{path}"),
+        };
+        Self {
+            entity,
+            component,
+            path,
+            label,
+            value,
+            old: None,
+        }
+    }
+
+    /// The value as RON text, parsed as the field's current type (e.g. from an inspector).
+    pub fn from_ron(
+        world: &World,
+        entity: EntityUid,
+        component: impl Into<String>,
+        path: impl Into<String>,
+        text: &str,
+    ) -> Result<Self, CommandError> {
+        let (component, path) = (component.into(), path.into());
+        let target = resolve(world, entity)?;
+        let value = engine_ecs::component_path_from_ron(world, target, &component, &path, text)?;
+        Ok(Self::from_reflect(entity, component, path, value))
+    }
+}
+
+impl Command for SetField {
+    fn name(&self) -> &str {
+        &self.label
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let target = resolve(world, self.entity)?;
+        let old = engine_ecs::get_component_path(world, target, &self.component, &self.path)?;
+        engine_ecs::set_component_path(
+            world,
+            target,
+            &self.component,
+            &self.path,
+            self.value.as_ref(),
+        )?;
+        self.old = Some(old);
+        Ok(())
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let target = resolve(world, self.entity)?;
+        if let Some(old) = &self.old {
+            engine_ecs::set_component_path(
+                world,
+                target,
+                &self.component,
+                &self.path,
+                old.as_ref(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = (next as &dyn std::any::Any).downcast_ref::<SetField>() else {
+            return false;
+        };
+        if (next.entity, &next.component, &next.path) != (self.entity, &self.component, &self.path)
+        {
+            return false;
+        }
+        self.value = next.value.clone_value();
+        true
+    }
+}
+
+/// Attach an entity to `parent` (`None` makes it a root) as child `index` (the last if
+/// `None`); its local transform is kept. Also reorders an entity within its parent.
+pub struct Reparent {
+    entity: EntityUid,
+    parent: Option<EntityUid>,
+    index: Option<usize>,
+    old_parent: Option<EntityUid>,
+    old_index: Option<usize>,
+}
+
+impl Reparent {
+    pub fn new(entity: EntityUid, parent: Option<EntityUid>, index: Option<usize>) -> Self {
+        Self {
+            entity,
+            parent,
+            index,
+            old_parent: None,
+            old_index: None,
+        }
+    }
+}
+
+impl Command for Reparent {
+    fn name(&self) -> &str {
+        "Reparent"
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let target = resolve(world, self.entity)?;
+        if let Some(parent) = self.parent {
+            let mut ancestor = Some(resolve(world, parent)?);
+            while let Some(next) = ancestor {
+                if next == target {
+                    return Err(CommandError::ParentCycle {
+                        entity: self.entity,
+                        parent,
+                    });
+                }
+                ancestor = world.get::<Parent>(next).map(|p| p.0);
+            }
+        }
+        self.old_parent = world
+            .get::<Parent>(target)
+            .and_then(|parent| world.get::<SceneEntityId>(parent.0))
+            .map(|id| id.0);
+        self.old_index = child_index(world, target);
+        set_parent(world, self.entity, self.old_parent, self.parent)?;
+        if let (Some(_), Some(index)) = (self.parent, self.index) {
+            set_child_index(world, target, index);
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let target = resolve(world, self.entity)?;
+        set_parent(world, self.entity, self.parent, self.old_parent)?;
+        if let Some(index) = self.old_index {
+            set_child_index(world, target, index);
+        }
+        Ok(())
+    }
+}
+
+/// Change an entity's `Name`. Consecutive renames of the same entity merge (typing).
+pub struct Rename {
+    entity: EntityUid,
+    name: String,
+    old: String,
+}
+
+impl Rename {
+    pub fn new(entity: EntityUid, name: impl Into<String>) -> Self {
+        Self {
+            entity,
+            name: name.into(),
+            old: String::new(),
+        }
+    }
+}
+
+impl Command for Rename {
+    fn name(&self) -> &str {
+        "Rename"
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        let target = resolve(world, self.entity)?;
+        self.old = world
+            .get::<Name>(target)
+            .map(|name| name.0.clone())
+            .unwrap_or_default();
+        apply_changes(
+            world,
+            vec![SceneChange::SetName {
+                entity: self.entity,
+                old: self.old.clone(),
+                new: self.name.clone(),
+            }],
+        )
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        resolve(world, self.entity)?;
+        apply_changes(
+            world,
+            vec![SceneChange::SetName {
+                entity: self.entity,
+                old: self.name.clone(),
+                new: self.old.clone(),
+            }],
+        )
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        match (next as &dyn std::any::Any).downcast_ref::<Rename>() {
+            Some(next) if next.entity == self.entity => {
+                self.name = next.name.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The live entity of a scene entity id.
+fn resolve(world: &World, id: EntityUid) -> Result<EcsEntity, CommandError> {
+    world
+        .get_resource::<EntityMap>()
+        .and_then(|map| map.get(id))
+        .filter(|entity| world.get_entity(*entity).is_some())
+        .ok_or(CommandError::MissingEntity(id))
+}
+
+/// Apply changes to the scene entities in the world's `EntityMap`.
+fn apply_changes(world: &mut World, changes: Vec<SceneChange>) -> Result<(), CommandError> {
+    let mut instance = SceneInstance {
+        entities: world
+            .get_resource::<EntityMap>()
+            .map(|map| map.iter().collect())
+            .unwrap_or_default(),
+    };
+    apply_scene_patch(world, &mut instance, &ScenePatch { changes })?;
+    Ok(())
+}
+
+fn set_parent(
+    world: &mut World,
+    entity: EntityUid,
+    old: Option<EntityUid>,
+    new: Option<EntityUid>,
+) -> Result<(), CommandError> {
+    apply_changes(world, vec![SceneChange::SetParent { entity, old, new }])
+}
+
+/// Position of `child` among its parent's children.
+fn child_index(world: &World, child: EcsEntity) -> Option<usize> {
+    let parent = world.get::<Parent>(child)?.0;
+    world
+        .get::<Children>(parent)?
+        .0
+        .iter()
+        .position(|c| *c == child)
+}
+
+/// Move `child` to `index` (clamped) among its parent's children.
+fn set_child_index(world: &mut World, child: EcsEntity, index: usize) {
+    let Some(parent) = world.get::<Parent>(child).map(|p| p.0) else {
+        return;
+    };
+    if let Some(mut children) = world.get_mut::<Children>(parent) {
+        children.0.retain(|c| *c != child);
+        let index = index.min(children.0.len());
+        children.0.insert(index, child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_core::EngineApp;
+    use engine_scene::{
+        ScenePlugin,
+        Transform2D,
+    };
+
+    use super::*;
+    use crate::{
+        EditorAppExt,
+        EditorPlugin,
+        UndoStack,
+    };
+
+    fn app() -> EngineApp {
+        let mut app = EngineApp::new();
+        app.add_plugin(ScenePlugin).add_plugin(EditorPlugin);
+        app
+    }
+
+    fn transform(x: f32) -> Transform2D {
+        Transform2D {
+            position: (x, 0.0),
+            rotation: 0.0,
+            scale: (1.0, 1.0),
+        }
+    }
+
+    fn spawn(app: &mut EngineApp, id: u32, name: &str, parent: Option<u32>) {
+        let mut entity = Entity::new(id, name).with_component(&transform(id as f32));
+        entity.parent = parent.map(EntityUid::from);
+        app.execute_command(SpawnEntity::new(entity)).unwrap();
+    }
+
+    /// root(1) with children a(2), b(3), c(4); b has child d(5), which has child e(6).
+    fn tree() -> EngineApp {
+        let mut app = app();
+        spawn(&mut app, 1, "root", None);
+        spawn(&mut app, 2, "a", Some(1));
+        spawn(&mut app, 3, "b", Some(1));
+        spawn(&mut app, 4, "c", Some(1));
+        spawn(&mut app, 5, "d", Some(3));
+        spawn(&mut app, 6, "e", Some(5));
+        app.world.resource_mut::<UndoStack>().clear();
+        app
+    }
+
+    fn get(app: &EngineApp, id: u32) -> Option<EcsEntity> {
+        resolve(&app.world, id.into()).ok()
+    }
+
+    fn name(app: &EngineApp, id: u32) -> String {
+        app.world
+            .get::<Name>(get(app, id).unwrap())
+            .unwrap()
+            .0
+            .clone()
+    }
+
+    fn children(app: &EngineApp, id: u32) -> Vec<String> {
+        let Some(children) = app.world.get::<Children>(get(app, id).unwrap()) else {
+            return Vec::new();
+        };
+        children
+            .0
+            .iter()
+            .map(|child| app.world.get::<Name>(*child).unwrap().0.clone())
+            .collect()
+    }
+
+    fn position(app: &EngineApp, id: u32) -> (f32, f32) {
+        app.world
+            .get::<Transform2D>(get(app, id).unwrap())
+            .unwrap()
+            .position
+    }
+
+    #[test]
+    fn spawn_and_undo() {
+        let mut app = tree();
+        spawn(&mut app, 7, "f", Some(3));
+        assert_eq!(children(&app, 3), ["d", "f"]);
+        assert_eq!(position(&app, 7), (7.0, 0.0));
+
+        app.undo().unwrap();
+        assert!(get(&app, 7).is_none());
+        assert_eq!(children(&app, 3), ["d"]);
+        app.redo().unwrap();
+        assert_eq!(children(&app, 3), ["d", "f"]);
+
+        let duplicate = app.execute_command(SpawnEntity::new(Entity::new(1u32, "again")));
+        assert!(matches!(duplicate, Err(CommandError::DuplicateEntity(_))));
+        let mut orphan = Entity::new(8u32, "orphan");
+        orphan.parent = Some(99u32.into());
+        let missing = app.execute_command(SpawnEntity::new(orphan));
+        assert!(matches!(missing, Err(CommandError::MissingEntity(_))));
+        assert!(get(&app, 8).is_none());
+    }
+
+    #[test]
+    fn despawn_restores_subtree() {
+        let mut app = tree();
+        app.execute_command(DespawnEntity::new(3u32.into()))
+            .unwrap();
+        assert_eq!(children(&app, 1), ["a", "c"]);
+        assert!(get(&app, 3).is_none() && get(&app, 5).is_none() && get(&app, 6).is_none());
+
+        app.undo().unwrap();
+        assert_eq!(children(&app, 1), ["a", "b", "c"]);
+        assert_eq!(children(&app, 3), ["d"]);
+        assert_eq!(children(&app, 5), ["e"]);
+        assert_eq!(position(&app, 6), (6.0, 0.0));
+        assert_eq!(
+            app.world
+                .get::<SceneEntityId>(get(&app, 6).unwrap())
+                .unwrap()
+                .0,
+            EntityUid::from(6u32)
+        );
+
+        app.redo().unwrap();
+        assert_eq!(children(&app, 1), ["a", "c"]);
+        assert!(get(&app, 6).is_none());
+        assert!(matches!(
+            app.execute_command(DespawnEntity::new(3u32.into())),
+            Err(CommandError::MissingEntity(_))
+        ));
+    }
+
+    #[test]
+    fn set_field_merges() {
+        let mut app = tree();
+        for x in 1..=5 {
+            let command = SetField::new(2u32.into(), "Transform2D", "position", (x as f32, 1.0f32));
+            app.execute_command(command).unwrap();
+        }
+        assert_eq!(position(&app, 2), (5.0, 1.0));
+        app.world.resource_mut::<UndoStack>().seal();
+        let command =
+            SetField::from_ron(&app.world, 2u32.into(), "Transform2D", "position.0", "42.0")
+                .unwrap();
+        app.execute_command(command).unwrap();
+        assert_eq!(position(&app, 2), (42.0, 1.0));
+        assert_eq!(
+            app.world.resource::<UndoStack>().undo_name(),
+            Some("Set Transform2D.position.0")
+        );
+
+        app.undo().unwrap();
+        assert_eq!(position(&app, 2), (5.0, 1.0));
+        app.undo().unwrap();
+        assert_eq!(position(&app, 2), (2.0, 0.0));
+        assert!(!app.world.resource::<UndoStack>().can_undo());
+
+        // A whole component, and a value of the wrong type
+        let command = SetField::new(2u32.into(), "Transform2D", "", transform(-1.0));
+        app.execute_command(command).unwrap();
+        assert_eq!(position(&app, 2), (-1.0, 0.0));
+        let command = SetField::new(2u32.into(), "Transform2D", "rotation", 1.0f64);
+        assert!(matches!(
+            app.execute_command(command),
+            Err(CommandError::Reflect(_))
+        ));
+    }
+
+    #[test]
+    fn reparent_with_index() {
+        let mut app = tree();
+        app.execute_command(Reparent::new(4u32.into(), Some(1u32.into()), Some(0)))
+            .unwrap();
+        assert_eq!(children(&app, 1), ["c", "a", "b"]);
+        app.undo().unwrap();
+        assert_eq!(children(&app, 1), ["a", "b", "c"]);
+
+        app.execute_command(Reparent::new(2u32.into(), Some(5u32.into()), Some(0)))
+            .unwrap();
+        assert_eq!(children(&app, 1), ["b", "c"]);
+        assert_eq!(children(&app, 5), ["a", "e"]);
+        app.undo().unwrap();
+        assert_eq!(children(&app, 1), ["a", "b", "c"]);
+        assert_eq!(children(&app, 5), ["e"]);
+
+        app.execute_command(Reparent::new(3u32.into(), None, None))
+            .unwrap();
+        assert_eq!(children(&app, 1), ["a", "c"]);
+        assert!(app.world.get::<Parent>(get(&app, 3).unwrap()).is_none());
+        app.undo().unwrap();
+        assert_eq!(children(&app, 1), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn reparent_rejects_cycle() {
+        let mut app = tree();
+        for parent in [3, 6] {
+            let result = app.execute_command(Reparent::new(3u32.into(), Some(parent.into()), None));
+            assert!(matches!(result, Err(CommandError::ParentCycle { .. })));
+        }
+        assert_eq!(children(&app, 1), ["a", "b", "c"]);
+        assert!(!app.world.resource::<UndoStack>().can_undo());
+    }
+
+    #[test]
+    fn rename_merges() {
+        let mut app = tree();
+        for name in ["x", "xy", "xyz"] {
+            app.execute_command(Rename::new(2u32.into(), name)).unwrap();
+        }
+        app.execute_command(Rename::new(4u32.into(), "z")).unwrap();
+        assert_eq!(children(&app, 1), ["xyz", "b", "z"]);
+        assert_eq!(app.world.resource::<UndoStack>().len(), 2);
+
+        app.undo().unwrap();
+        app.undo().unwrap();
+        assert_eq!(name(&app, 2), "a");
+        assert_eq!(name(&app, 4), "c");
+    }
+}
@@ -1,6 +1,68 @@
-// /**
-//  * path: /crates/editor_core/src/lib.rs
-//  * description: Core editor functionality for Ironhold.
-//  */
+/**
+ * path: /crates/editor_core/src/lib.rs
+ * description: Core editor functionality for Ironhold: undoable commands on the authoring world
+ * (`Command`, `UndoStack`) and the scene edits the editor makes with them. Has no UI or web
+ * dependencies, so it runs natively and in tests.
+ */
+use bevy_ecs::prelude::Mut;
+use engine_core::{
+    EngineApp,
+    Plugin,
+};
 
-// Selection, commands, undo/redo (stub)
+mod command;
+mod commands;
+mod undo;
+pub use command::{
+    Command,
+    CommandError,
+};
+pub use commands::{
+    DespawnEntity,
+    Rename,
+    Reparent,
+    SetField,
+    SpawnEntity,
+};
+pub use undo::{
+    UndoStack,
+    DEFAULT_UNDO_LIMIT,
+};
+
+/// Adds the `UndoStack`. The commands need the scene setup (`ScenePlugin`) in the world.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.init_resource::<UndoStack>();
+    }
+}
+
+/// Runs commands on the app's world through its `UndoStack` resource (added if missing).
+pub trait EditorAppExt {
+    fn execute_command(&mut self, command: impl Command) -> Result<(), CommandError>;
+
+    fn undo(&mut self) -> Result<bool, CommandError>;
+
+    fn redo(&mut self) -> Result<bool, CommandError>;
+}
+
+impl EditorAppExt for EngineApp {
+    fn execute_command(&mut self, command: impl Command) -> Result<(), CommandError> {
+        self.world.get_resource_or_insert_with(UndoStack::default);
+        self.world
+            .resource_scope(|world, mut stack: Mut<UndoStack>| stack.execute(world, command))
+    }
+
+    fn undo(&mut self) -> Result<bool, CommandError> {
+        self.world.get_resource_or_insert_with(UndoStack::default);
+        self.world
+            .resource_scope(|world, mut stack: Mut<UndoStack>| stack.undo(world))
+    }
+
+    fn redo(&mut self) -> Result<bool, CommandError> {
+        self.world.get_resource_or_insert_with(UndoStack::default);
+        self.world
+            .resource_scope(|world, mut stack: Mut<UndoStack>| stack.redo(world))
+    }
+}
@@ -0,0 +1,454 @@
+/**
+ * path: /crates/editor_core/src/undo.rs
+ * description: Undo/redo history of executed commands, with transactions that group several
+ * commands into one step and merging of consecutive commands (drags, typing).
+ */
+use bevy_ecs::prelude::Resource;
+use bevy_ecs::world::World;
+
+use crate::command::{
+    Command,
+    CommandError,
+};
+
+/// Undo steps kept by default; older ones are dropped.
+pub const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// Commands applied as one undo step.
+struct Transaction {
+    name: String,
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl Command for Transaction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+        for command in &mut self.commands {
+            command.apply(world)?;
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+        for command in self.commands.iter_mut().rev() {
+            command.undo(world)?;
+        }
+        Ok(())
+    }
+}
+
+/// History of executed commands. `execute` applies a command and records it; a command that
+/// merges with the previous one (see `Command::merge`) extends that step instead, until `seal`
+/// is called or anything is undone or redone. Commands executed between `begin_transaction`
+/// and `commit_transaction` form one step. A command that fails to apply, undo or redo is
+/// dropped, as the world no longer matches it.
+#[derive(Resource)]
+pub struct UndoStack {
+    undo: Vec<Box<dyn Command>>,
+    redo: Vec<Box<dyn Command>>,
+    limit: usize,
+    transaction: Option<Transaction>,
+    /// Transactions begun inside the open one; they join it.
+    nested: usize,
+    sealed: bool,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::with_limit(DEFAULT_UNDO_LIMIT)
+    }
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A history keeping at most `limit` steps.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit,
+            transaction: None,
+            nested: 0,
+            sealed: false,
+        }
+    }
+
+    /// Apply `command` and record it; clears the redo history.
+    pub fn execute(
+        &mut self,
+        world: &mut World,
+        command: impl Command,
+    ) -> Result<(), CommandError> {
+        self.execute_boxed(world, Box::new(command))
+    }
+
+    pub fn execute_boxed(
+        &mut self,
+        world: &mut World,
+        mut command: Box<dyn Command>,
+    ) -> Result<(), CommandError> {
+        command.apply(world)?;
+        self.redo.clear();
+        let steps = match &mut self.transaction {
+            Some(transaction) => &mut transaction.commands,
+            None => &mut self.undo,
+        };
+        let sealed = std::mem::take(&mut self.sealed);
+        if !sealed
+            && steps
+                .last_mut()
+                .is_some_and(|last| last.merge(command.as_ref()))
+        {
+            return Ok(());
+        }
+        steps.push(command);
+        self.trim();
+        Ok(())
+    }
+
+    /// Start a new step for the next command, even if it could merge with the last one (e.g.
+    /// when a drag ends).
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Group the following commands into one step named `name`, until `commit_transaction`.
+    /// A transaction begun while one is open joins it.
+    pub fn begin_transaction(&mut self, name: impl Into<String>) {
+        match self.transaction {
+            Some(_) => self.nested += 1,
+            None => {
+                self.transaction = Some(Transaction {
+                    name: name.into(),
+                    commands: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// Close the innermost transaction; closing the outermost records its commands as one step
+    /// (nothing if it is empty).
+    pub fn commit_transaction(&mut self) {
+        if self.nested > 0 {
+            self.nested -= 1;
+            return;
+        }
+        let Some(transaction) = self.transaction.take() else {
+            return;
+        };
+        if !transaction.commands.is_empty() {
+            self.undo.push(Box::new(transaction));
+            self.sealed = true;
+            self.trim();
+        }
+    }
+
+    /// Undo the commands of the open transaction (all levels) and discard it.
+    pub fn cancel_transaction(&mut self, world: &mut World) -> Result<(), CommandError> {
+        self.nested = 0;
+        match self.transaction.take() {
+            Some(mut transaction) => transaction.undo(world),
+            None => Ok(()),
+        }
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Undo the last step; an open transaction is committed first. Returns whether there was
+    /// one.
+    pub fn undo(&mut self, world: &mut World) -> Result<bool, CommandError> {
+        self.close_transaction();
+        let Some(mut command) = self.undo.pop() else {
+            return Ok(false);
+        };
+        self.sealed = true;
+        command.undo(world)?;
+        self.redo.push(command);
+        Ok(true)
+    }
+
+    /// Apply the last undone step again. Returns whether there was one.
+    pub fn redo(&mut self, world: &mut World) -> Result<bool, CommandError> {
+        self.close_transaction();
+        let Some(mut command) = self.redo.pop() else {
+            return Ok(false);
+        };
+        self.sealed = true;
+        command.apply(world)?;
+        self.undo.push(command);
+        Ok(true)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+            || self
+                .transaction
+                .as_ref()
+                .is_some_and(|t| !t.commands.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Name of the step `undo` would undo.
+    pub fn undo_name(&self) -> Option<&str> {
+        match &self.transaction {
+            Some(transaction) if !transaction.commands.is_empty() => Some(&transaction.name),
+            _ => self.undo.last().map(|command| command.name()),
+        }
+    }
+
+    /// Name of the step `redo` would apply.
+    pub fn redo_name(&self) -> Option<&str> {
+        self.redo.last().map(|command| command.name())
+    }
+
+    /// Number of steps that can be undone.
+    pub fn len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty()
+    }
+
+    /// Forget the history (e.g. after loading another scene); an open transaction is dropped
+    /// without undoing it.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.transaction = None;
+        self.nested = 0;
+        self.sealed = false;
+    }
+
+    fn close_transaction(&mut self) {
+        self.nested = 0;
+        self.commit_transaction();
+    }
+
+    fn trim(&mut self) {
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::prelude::Resource;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Total(i32);
+
+    /// Adds to `Total`; consecutive adds merge.
+    struct Add(i32);
+
+    impl Command for Add {
+        fn name(&self) -> &str {
+            "Add"
+        }
+
+        fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+            world.resource_mut::<Total>().0 += self.0;
+            Ok(())
+        }
+
+        fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+            world.resource_mut::<Total>().0 -= self.0;
+            Ok(())
+        }
+
+        fn merge(&mut self, next: &dyn Command) -> bool {
+            match (next as &dyn std::any::Any).downcast_ref::<Add>() {
+                Some(next) => {
+                    self.0 += next.0;
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Multiplies `Total`; never merges.
+    struct Mul(i32);
+
+    impl Command for Mul {
+        fn name(&self) -> &str {
+            "Mul"
+        }
+
+        fn apply(&mut self, world: &mut World) -> Result<(), CommandError> {
+            world.resource_mut::<Total>().0 *= self.0;
+            Ok(())
+        }
+
+        fn undo(&mut self, world: &mut World) -> Result<(), CommandError> {
+            world.resource_mut::<Total>().0 /= self.0;
+            Ok(())
+        }
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Total>();
+        world
+    }
+
+    fn total(world: &World) -> i32 {
+        world.resource::<Total>().0
+    }
+
+    #[test]
+    fn apply_undo_redo() {
+        let mut world = world();
+        let mut stack = UndoStack::new();
+        assert!(!stack.undo(&mut world).unwrap());
+        stack.execute(&mut world, Mul(3)).unwrap();
+        stack.execute(&mut world, Add(2)).unwrap();
+        stack.execute(&mut world, Mul(2)).unwrap();
+        assert_eq!(total(&world), 4);
+        assert_eq!(stack.len(), 3);
+
+        assert!(stack.undo(&mut world).unwrap());
+        assert_eq!(total(&world), 2);
+        assert!(stack.undo(&mut world).unwrap());
+        assert_eq!(total(&world), 0);
+        assert_eq!(stack.redo_name(), Some("Add"));
+        assert!(stack.redo(&mut world).unwrap());
+        assert_eq!(total(&world), 2);
+        assert!(stack.can_redo());
+
+        // A new command drops what could be redone
+        stack.execute(&mut world, Mul(5)).unwrap();
+        assert_eq!(total(&world), 10);
+        assert!(!stack.can_redo());
+        assert!(!stack.redo(&mut world).unwrap());
+        assert_eq!(stack.undo_name(), Some("Mul"));
+    }
+
+    #[test]
+    fn merge_until_sealed() {
+        let mut world = world();
+        let mut stack = UndoStack::new();
+        for _ in 0..4 {
+            stack.execute(&mut world, Add(1)).unwrap();
+        }
+        assert_eq!(stack.len(), 1);
+        stack.seal();
+        stack.execute(&mut world, Add(10)).unwrap();
+        stack.execute(&mut world, Add(10)).unwrap();
+        assert_eq!(stack.len(), 2);
+        assert_eq!(total(&world), 24);
+
+        stack.undo(&mut world).unwrap();
+        assert_eq!(total(&world), 4);
+        // Undo seals, so this doesn't merge into the remaining step
+        stack.execute(&mut world, Add(1)).unwrap();
+        assert_eq!(stack.len(), 2);
+        stack.undo(&mut world).unwrap();
+        stack.undo(&mut world).unwrap();
+        assert_eq!(total(&world), 0);
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn transactions() {
+        let mut world = world();
+        let mut stack = UndoStack::new();
+        stack.execute(&mut world, Add(1)).unwrap();
+        stack.begin_transaction("Group");
+        stack.execute(&mut world, Mul(3)).unwrap();
+        stack.begin_transaction("Inner");
+        stack.execute(&mut world, Add(1)).unwrap();
+        stack.commit_transaction();
+        assert!(stack.in_transaction());
+        assert_eq!(stack.undo_name(), Some("Group"));
+        stack.execute(&mut world, Mul(2)).unwrap();
+        stack.commit_transaction();
+        assert!(!stack.in_transaction());
+        assert_eq!(stack.len(), 2);
+        assert_eq!(total(&world), 8);
+
+        // The whole transaction undoes as one step, in reverse
+        stack.undo(&mut world).unwrap();
+        assert_eq!(total(&world), 1);
+        stack.redo(&mut world).unwrap();
+        assert_eq!(total(&world), 8);
+
+        // Committed transactions don't merge with what follows
+        stack.execute(&mut world, Add(1)).unwrap();
+        assert_eq!(stack.len(), 3);
+
+        // Empty transactions leave no step
+        stack.begin_transaction("Empty");
+        stack.commit_transaction();
+        assert_eq!(stack.len(), 3);
+    }
+
+    #[test]
+    fn cancel_transaction() {
+        let mut world = world();
+        let mut stack = UndoStack::new();
+        stack.execute(&mut world, Add(2)).unwrap();
+        stack.begin_transaction("Group");
+        stack.execute(&mut world, Mul(3)).unwrap();
+        stack.begin_transaction("Inner");
+        stack.execute(&mut world, Add(4)).unwrap();
+        stack.cancel_transaction(&mut world).unwrap();
+        assert!(!stack.in_transaction());
+        assert_eq!(total(&world), 2);
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.undo_name(), Some("Add"));
+    }
+
+    #[test]
+    fn undo_commits_open_transaction() {
+        let mut world = world();
+        let mut stack = UndoStack::new();
+        stack.begin_transaction("Group");
+        stack.execute(&mut world, Add(2)).unwrap();
+        stack.execute(&mut world, Mul(3)).unwrap();
+        stack.undo(&mut world).unwrap();
+        assert!(!stack.in_transaction());
+        assert_eq!(total(&world), 0);
+        assert_eq!(stack.redo_name(), Some("Group"));
+    }
+
+    #[test]
+    fn trims_to_limit() {
+        let mut world = world();
+        let mut stack = UndoStack::with_limit(3);
+        for _ in 0..5 {
+            stack.execute(&mut world, Mul(2)).unwrap();
+        }
+        assert_eq!(stack.len(), 3);
+        assert_eq!(total(&world), 0);
+        world.resource_mut::<Total>().0 = 32;
+        while stack.undo(&mut world).unwrap() {}
+        assert_eq!(total(&world), 4);
+
+        // Committing a transaction trims too
+        stack.clear();
+        for _ in 0..3 {
+            stack.execute(&mut world, Mul(2)).unwrap();
+        }
+        stack.begin_transaction("Group");
+        stack.execute(&mut world, Add(1)).unwrap();
+        stack.commit_transaction();
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.undo_name(), Some("Group"));
+    }
+}
@@ -0,0 +1,13 @@
+/**
+ * path: /crates/engine_assets/src/font_loader.rs
+ * description: Loading TrueType/OpenType fonts (`.ttf`, `.otf`) for `engine_text`.
+ */
+use engine_text::Font;
+
+use crate::AssetError;
+
+/// Parse a font file under `name` (what styles and `Text` components refer to); `path` only
+/// labels errors.
+pub fn load_font_bytes(name: &str, path: &str, bytes: Vec<u8>) -> Result<Font, AssetError> {
+    Font::from_bytes(name, bytes).map_err(|e| AssetError::Format(format!("{path}: {e}")))
+}
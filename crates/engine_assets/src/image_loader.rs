@@ -0,0 +1,25 @@
+/**
+ * path: /crates/engine_assets/src/image_loader.rs
+ * description: Decoding images (PNG) into RGBA8 pixels for GPU upload.
+ */
+use crate::AssetError;
+
+/// Decoded image, tightly packed sRGB RGBA8 rows from the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageData {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Decode a PNG; `path` only labels errors.
+pub fn load_image_bytes(path: &str, bytes: &[u8]) -> Result<ImageData, AssetError> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+        .map_err(|e| AssetError::Format(format!("{path}: {e}")))?
+        .into_rgba8();
+    Ok(ImageData {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    })
+}
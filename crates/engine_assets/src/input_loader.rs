@@ -0,0 +1,14 @@
+/**
+ * path: /crates/engine_assets/src/input_loader.rs
+ * description: Loading action/axis bindings (`.input.ron`, see `engine_input::InputMap`).
+ */
+use engine_input::InputMap;
+
+use crate::AssetError;
+
+/// Decode a `.input.ron` file; `path` only labels errors.
+pub fn load_input_map_bytes(path: &str, bytes: &[u8]) -> Result<InputMap, AssetError> {
+    let text =
+        std::str::from_utf8(bytes).map_err(|e| AssetError::Format(format!("{path}: {e}")))?;
+    InputMap::from_ron_str(text).map_err(|e| AssetError::Format(format!("{path}: {e}")))
+}
@@ -3,8 +3,16 @@
  * description: Asset management for the engine.
  */
 use thiserror::Error;
+mod font_loader;
+mod image_loader;
+mod input_loader;
 mod mesh_loader;
-pub use mesh_loader::{load_mesh_stub};
+mod ui_loader;
+pub use font_loader::load_font_bytes;
+pub use image_loader::{load_image_bytes, ImageData};
+pub use input_loader::load_input_map_bytes;
+pub use mesh_loader::{load_mesh_bytes, load_mesh_gltf, load_mesh_stub, mesh_from_gltf};
+pub use ui_loader::load_ui_document_bytes;
 pub use engine_types::MeshData;
 
 #[derive(Error, Debug)]
@@ -1,4 +1,8 @@
-use engine_scene::Entity;
+use engine_scene::{
+    Entity,
+    Mesh,
+    SceneComponent,
+};
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen::JsCast;
@@ -6,13 +10,16 @@ use wasm_bindgen::JsCast;
 use engine_types::{
     MeshVertex,
     MeshData,
+    MESH_BINARY_EXTENSION,
 };
 
+use crate::AssetError;
+
 
 /// Stub function to load mesh data from an entity
-/// In future: parse GLTF or other formats based on entity.mesh path
+/// In future: parse GLTF or other formats based on the entity's `Mesh` file
 pub fn load_mesh_stub(entity: &Entity) -> Option<MeshData> {
-    if entity.mesh.is_none() {
+    if !entity.has_component(Mesh::TYPE_NAME) {
         return None;
     }
 
@@ -40,23 +47,49 @@ pub async fn load_mesh_gltf(url: &str) -> Result<MeshData, JsValue> {
     let buf: js_sys::ArrayBuffer = buf_value.dyn_into().unwrap();
 
     let bytes = js_sys::Uint8Array::new(&buf).to_vec();
+    load_mesh_bytes(url, &bytes).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Decode mesh bytes by file name: a cooked `.mesh.bin` (see `MeshData::to_packed`) or glTF.
+pub fn load_mesh_bytes(path: &str, bytes: &[u8]) -> Result<MeshData, AssetError> {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    if path.ends_with(MESH_BINARY_EXTENSION) {
+        MeshData::from_packed(bytes).map_err(|e| AssetError::Format(e.to_string()))
+    } else {
+        mesh_from_gltf(bytes)
+    }
+}
 
+/// First primitive of the first mesh in a glTF file. Only the `.glb` binary chunk is read;
+/// external and data-URI buffers are not supported.
+pub fn mesh_from_gltf(bytes: &[u8]) -> Result<MeshData, AssetError> {
     // Parse GLTF
-    let gltf = gltf::Gltf::from_slice(&bytes).map_err(|e| JsValue::from_str(&format!("GLTF parse error: {:?}", e)))?;
-    let mesh = gltf.meshes().next().ok_or(JsValue::from_str("No mesh found"))?;
-    let primitive = mesh.primitives().next().ok_or(JsValue::from_str("No primitive found"))?;
+    let gltf = gltf::Gltf::from_slice(bytes).map_err(|e| AssetError::Format(format!("GLTF parse error: {e}")))?;
+    let mesh = gltf.meshes().next().ok_or(AssetError::Format("No mesh found".into()))?;
+    let primitive = mesh.primitives().next().ok_or(AssetError::Format("No primitive found".into()))?;
 
     // Extract positions, normals, UVs
-    let reader = primitive.reader(|buffer| Some(&bytes[buffer.index()..]));
-    let positions: Vec<[f32; 3]> = reader.read_positions().unwrap().collect();
+    let blob = gltf.blob.as_deref();
+    let reader = primitive.reader(|buffer| match buffer.source() {
+        gltf::buffer::Source::Bin => blob,
+        gltf::buffer::Source::Uri(_) => None,
+    });
+    let positions: Vec<[f32; 3]> = reader
+        .read_positions()
+        .ok_or(AssetError::Format("No positions found".into()))?
+        .collect();
     let normals: Vec<[f32; 3]> = reader
         .read_normals()
-        .map(|iter| iter.collect()).expect("No normals found!");;
+        .ok_or(AssetError::Format("No normals found".into()))?
+        .collect();
     let uvs: Vec<[f32; 2]> = reader
         .read_tex_coords(0)
         .map(|tc| tc.into_f32().collect())
         .unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
-    let indices: Vec<u32> = reader.read_indices().unwrap().into_u32().collect();
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
 
     // Convert to MeshVertex
     let vertices: Vec<MeshVertex> = positions.iter().enumerate().map(|(i, pos)| MeshVertex {
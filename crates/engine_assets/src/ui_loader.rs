@@ -0,0 +1,14 @@
+/**
+ * path: /crates/engine_assets/src/ui_loader.rs
+ * description: Loading UI documents (`.ui.ron`, see `engine_ui::UiDocument`).
+ */
+use engine_ui::UiDocument;
+
+use crate::AssetError;
+
+/// Decode a `.ui.ron` file; `path` only labels errors.
+pub fn load_ui_document_bytes(path: &str, bytes: &[u8]) -> Result<UiDocument, AssetError> {
+    let text =
+        std::str::from_utf8(bytes).map_err(|e| AssetError::Format(format!("{path}: {e}")))?;
+    UiDocument::from_ron_str(text).map_err(|e| AssetError::Format(format!("{path}: {e}")))
+}
@@ -3,43 +3,173 @@
  * description: Core engine application logic and structures.
  */
 use bevy_ecs::{
-    prelude::World,
-    schedule::Schedule,
+    prelude::{
+        ReflectResource,
+        Resource,
+        World,
+    },
+    reflect::AppTypeRegistry,
+    schedule::{
+        IntoSystemConfigs,
+        Schedule,
+        ScheduleLabel,
+        Schedules,
+    },
+    world::FromWorld,
+};
+use bevy_reflect::{
+    GetTypeRegistration,
+    Reflect,
+};
+use std::collections::HashSet;
+
+mod plugin;
+pub mod schedule;
+mod time;
+pub use plugin::Plugin;
+pub use schedule::{
+    in_edit_mode,
+    in_mode,
+    in_play_mode,
+    FixedUpdate,
+    PostUpdate,
+    PreUpdate,
+    RenderExtract,
+    Startup,
+    Update,
+};
+pub use time::{
+    FixedTime,
+    Time,
+    DEFAULT_FIXED_HZ,
 };
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+/// Current editor/runtime mode, stored as a resource so run conditions can read it.
+#[derive(Resource, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Resource)]
 pub enum Mode {
     Edit,
     Play,
 }
 
+/// ECS world plus the named schedules that drive it (see `schedule` for the frame order).
+/// Games and engine subsystems extend it through `Plugin`s or `add_systems`.
 pub struct EngineApp {
     pub world: World,
-    pub edit_schedule: Schedule,
-    pub play_schedule: Schedule,
-    pub mode: Mode,
+    plugins: HashSet<String>,
+    started: bool,
 }
 
 impl Default for EngineApp {
     fn default() -> Self {
+        let mut world = World::new();
+        world.insert_resource(AppTypeRegistry::default());
+        engine_ecs::register_reflect::<Mode>(&mut world);
+        engine_ecs::register_reflect::<Time>(&mut world);
+        engine_ecs::register_reflect::<FixedTime>(&mut world);
+        world.insert_resource(Mode::Edit);
+        world.insert_resource(Time::default());
+        world.insert_resource(FixedTime::default());
+        for schedule in [
+            Schedule::new(Startup),
+            Schedule::new(PreUpdate),
+            Schedule::new(FixedUpdate),
+            Schedule::new(Update),
+            Schedule::new(PostUpdate),
+            Schedule::new(RenderExtract),
+        ] {
+            world.add_schedule(schedule);
+        }
         Self {
-            world: World::new(),
-            edit_schedule: Schedule::default(),
-            play_schedule: Schedule::default(),
-            mode: Mode::Edit,
+            world,
+            plugins: HashSet::new(),
+            started: false,
         }
     }
 }
 
 impl EngineApp {
-    pub fn update(&mut self) {
-        match self.mode {
-            Mode::Edit => self.edit_schedule.run(&mut self.world),
-            Mode::Play => self.play_schedule.run(&mut self.world),
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a plugin into the app. Adding the same plugin (by `Plugin::name`) again is a no-op.
+    pub fn add_plugin(&mut self, plugin: impl Plugin) -> &mut Self {
+        if self.plugins.insert(plugin.name().to_string()) {
+            plugin.build(self);
+        }
+        self
+    }
+
+    pub fn is_plugin_added(&self, name: &str) -> bool {
+        self.plugins.contains(name)
+    }
+
+    /// Add systems to a schedule, e.g. `app.add_systems(Update, move_player.run_if(in_play_mode))`.
+    pub fn add_systems<M>(
+        &mut self,
+        label: impl ScheduleLabel,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        self.world
+            .resource_mut::<Schedules>()
+            .add_systems(label, systems);
+        self
+    }
+
+    /// Add a custom schedule; run it from a system or `World::run_schedule`.
+    pub fn add_schedule(&mut self, schedule: Schedule) -> &mut Self {
+        self.world.add_schedule(schedule);
+        self
+    }
+
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    pub fn init_resource<R: Resource + FromWorld>(&mut self) -> &mut Self {
+        self.world.init_resource::<R>();
+        self
+    }
+
+    /// Make `T` available to reflection (inspector, undo, scripting); see `engine_ecs::reflection`.
+    pub fn register_type<T: GetTypeRegistration>(&mut self) -> &mut Self {
+        engine_ecs::register_reflect::<T>(&mut self.world);
+        self
+    }
+
+    pub fn mode(&self) -> Mode {
+        *self.world.resource::<Mode>()
+    }
+
+    /// Advance time by `dt_secs` (real seconds since the previous frame) and run the schedules.
+    pub fn update(&mut self, dt_secs: f32) {
+        let delta = self.world.resource_mut::<Time>().advance(dt_secs);
+
+        if !self.started {
+            self.started = true;
+            self.world.run_schedule(Startup);
         }
+
+        self.world.run_schedule(PreUpdate);
+        if self.mode() == Mode::Play {
+            let steps = self.world.resource_mut::<FixedTime>().accumulate(delta);
+            for _ in 0..steps {
+                self.world.run_schedule(FixedUpdate);
+                self.world.resource_mut::<FixedTime>().step();
+            }
+        }
+        self.world.run_schedule(Update);
+        self.world.run_schedule(PostUpdate);
+        self.world.run_schedule(RenderExtract);
     }
 }
 
 pub fn set_mode(app: &mut EngineApp, mode: Mode) {
-    app.mode = mode;
+    if app.mode() != mode {
+        // Don't replay time accumulated before the switch
+        app.world.resource_mut::<FixedTime>().reset_accumulator();
+    }
+    app.world.insert_resource(mode);
 }
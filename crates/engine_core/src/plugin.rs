@@ -0,0 +1,28 @@
+/**
+ * path: /crates/engine_core/src/plugin.rs
+ * description: Plugin trait used to bundle systems/resources for a subsystem (render extraction,
+ * input, assets, UI, or a game's own logic).
+ */
+use crate::EngineApp;
+
+/// A self-contained piece of engine setup.
+/// `build` adds resources and systems; it runs once when the plugin is added.
+pub trait Plugin: 'static {
+    fn build(&self, app: &mut EngineApp);
+
+    /// Identifies the plugin; adding a plugin with the same name twice is a no-op,
+    /// so plugins may add the plugins they depend on.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Plugins can also be plain functions: `app.add_plugin(my_game_plugin)`.
+impl<F> Plugin for F
+where
+    F: Fn(&mut EngineApp) + 'static,
+{
+    fn build(&self, app: &mut EngineApp) {
+        self(app)
+    }
+}
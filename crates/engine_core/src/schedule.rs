@@ -0,0 +1,50 @@
+/**
+ * path: /crates/engine_core/src/schedule.rs
+ * description: Named schedule labels and mode-specific run conditions.
+ *
+ * Per-frame order in `EngineApp::update`:
+ *   Startup (first update only) → PreUpdate → FixedUpdate ×N (Play only) → Update → PostUpdate → RenderExtract
+ */
+use bevy_ecs::prelude::Res;
+use bevy_ecs::schedule::ScheduleLabel;
+
+use crate::Mode;
+
+/// Runs once, before the first frame.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Startup;
+
+/// Runs every frame before gameplay (e.g. input collection, asset events).
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreUpdate;
+
+/// Runs zero or more times per frame at a fixed rate, in Play mode only (see `FixedTime`).
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedUpdate;
+
+/// Runs every frame in both modes; gate systems with `in_play_mode` / `in_edit_mode`.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update;
+
+/// Runs every frame after gameplay (e.g. transform propagation).
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PostUpdate;
+
+/// Runs last every frame; copies world state into render-side data.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderExtract;
+
+/// Run condition: true while the app is in `mode`.
+pub fn in_mode(mode: Mode) -> impl FnMut(Res<Mode>) -> bool + Clone {
+    move |current: Res<Mode>| *current == mode
+}
+
+/// Run condition: true in Edit mode.
+pub fn in_edit_mode(mode: Res<Mode>) -> bool {
+    *mode == Mode::Edit
+}
+
+/// Run condition: true in Play mode.
+pub fn in_play_mode(mode: Res<Mode>) -> bool {
+    *mode == Mode::Play
+}
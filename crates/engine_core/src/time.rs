@@ -0,0 +1,130 @@
+/**
+ * path: /crates/engine_core/src/time.rs
+ * description: Frame time and fixed-timestep bookkeeping, exposed to systems as ECS resources.
+ */
+use bevy_ecs::prelude::{
+    ReflectResource,
+    Resource,
+};
+use bevy_reflect::std_traits::ReflectDefault;
+use bevy_reflect::Reflect;
+
+/// Default fixed update rate (steps per second).
+pub const DEFAULT_FIXED_HZ: f32 = 60.0;
+
+/// Per-frame clock, advanced once per `EngineApp::update`.
+/// - `delta`: scaled seconds since the previous frame (0 while paused)
+/// - `raw_delta`: unscaled seconds since the previous frame (after `max_delta` clamping)
+/// - `elapsed`: scaled seconds since start
+/// - `time_scale`: multiplier for `delta` (1.0 = real time)
+/// - `max_delta`: upper bound for a single frame, e.g. after a backgrounded tab resumes
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource, Default)]
+pub struct Time {
+    pub delta: f32,
+    pub raw_delta: f32,
+    pub elapsed: f64,
+    pub frame_count: u64,
+    pub time_scale: f32,
+    pub paused: bool,
+    pub max_delta: f32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            delta: 0.0,
+            raw_delta: 0.0,
+            elapsed: 0.0,
+            frame_count: 0,
+            time_scale: 1.0,
+            paused: false,
+            max_delta: 0.25,
+        }
+    }
+}
+
+impl Time {
+    /// Advance the clock by `raw_dt` seconds; returns the scaled delta.
+    pub fn advance(&mut self, raw_dt: f32) -> f32 {
+        let raw_dt = if raw_dt.is_finite() {
+            raw_dt.clamp(0.0, self.max_delta)
+        } else {
+            0.0
+        };
+        self.raw_delta = raw_dt;
+        self.delta = if self.paused {
+            0.0
+        } else {
+            raw_dt * self.time_scale.max(0.0)
+        };
+        self.elapsed += self.delta as f64;
+        self.frame_count += 1;
+        self.delta
+    }
+}
+
+/// Fixed-timestep accumulator. Systems in the fixed schedule should use `timestep` as their dt.
+/// - `alpha`: leftover fraction of a step after this frame's fixed steps (0..1);
+///   renderers blend previous and current fixed state with it
+/// - `max_steps_per_frame`: caps catch-up work so a slow frame can't spiral
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource, Default)]
+pub struct FixedTime {
+    pub timestep: f32,
+    pub max_steps_per_frame: u32,
+    pub accumulator: f32,
+    pub alpha: f32,
+    pub steps_this_frame: u32,
+    pub step_count: u64,
+    pub elapsed: f64,
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::from_hz(DEFAULT_FIXED_HZ)
+    }
+}
+
+impl FixedTime {
+    pub fn from_hz(hz: f32) -> Self {
+        Self {
+            timestep: 1.0 / hz.max(1.0),
+            max_steps_per_frame: 8,
+            accumulator: 0.0,
+            alpha: 0.0,
+            steps_this_frame: 0,
+            step_count: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Add `delta` seconds to the accumulator and return how many fixed steps to run now.
+    /// Time beyond `max_steps_per_frame` steps is dropped rather than carried over.
+    pub fn accumulate(&mut self, delta: f32) -> u32 {
+        let timestep = self.timestep.max(f32::EPSILON);
+        self.accumulator += delta.max(0.0);
+        let due = (self.accumulator / timestep).floor() as u32;
+        let steps = due.min(self.max_steps_per_frame);
+        self.accumulator -= steps as f32 * timestep;
+        if due > steps {
+            self.accumulator %= timestep;
+        }
+        self.alpha = (self.accumulator / timestep).clamp(0.0, 1.0);
+        self.steps_this_frame = steps;
+        steps
+    }
+
+    /// Record one executed fixed step.
+    pub fn step(&mut self) {
+        self.step_count += 1;
+        self.elapsed += self.timestep as f64;
+    }
+
+    /// Drop pending time, e.g. when entering Play mode.
+    pub fn reset_accumulator(&mut self) {
+        self.accumulator = 0.0;
+        self.alpha = 0.0;
+        self.steps_this_frame = 0;
+    }
+}
@@ -1,8 +1,32 @@
 /**
  * path: /crates/engine_ecs/src/lib.rs
  * description: Entity-Component-System (ECS) module
+ * Re-exports bevy_ecs and bevy_reflect, plus name/path based reflection helpers (see `reflection`).
  */
 pub use bevy_ecs as ecs;
-// pub use bevy_reflect as reflect;
-// Reflection will be added later when the inspector needs it.
-// For now we keep ECS minimal to avoid feature/version friction.
+pub use bevy_reflect as reflect;
+
+pub mod reflection;
+pub use reflection::{
+    component_path_from_ron,
+    default_value,
+    describe,
+    describe_type,
+    find_type,
+    from_ron,
+    get_component_path,
+    get_resource_path,
+    insert_default_component,
+    reflected_components,
+    reflected_resources,
+    register_reflect,
+    set_component_path,
+    set_component_path_ron,
+    set_resource_path,
+    to_ron,
+    type_registry,
+    visit_values_mut,
+    FieldDescription,
+    ReflectError,
+    TypeDescription,
+};
@@ -0,0 +1,425 @@
+/**
+ * path: /crates/engine_ecs/src/reflection.rs
+ * description: Generic access to components and resources through `bevy_reflect`.
+ * Types register into the world's `AppTypeRegistry` and are then addressed by name
+ * ("Transform2D" or the full type path) and a field path ("position.0"), so the inspector,
+ * scene tools, undo and the JS API don't need to know concrete types.
+ */
+use bevy_ecs::entity::Entity;
+use bevy_ecs::reflect::{
+    AppTypeRegistry,
+    ReflectComponent,
+    ReflectResource,
+};
+use bevy_ecs::world::World;
+use bevy_reflect::serde::{
+    TypedReflectDeserializer,
+    TypedReflectSerializer,
+};
+use bevy_reflect::std_traits::ReflectDefault;
+use bevy_reflect::{
+    GetPath,
+    GetTypeRegistration,
+    Reflect,
+    ReflectMut,
+    TypeInfo,
+    TypeRegistration,
+    TypeRegistry,
+};
+use serde::de::DeserializeSeed;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReflectError {
+    #[error("type '{0}' is not registered for reflection")]
+    UnknownType(String),
+    #[error("'{0}' is not a reflected component")]
+    NotAComponent(String),
+    #[error("'{0}' is not a reflected resource")]
+    NotAResource(String),
+    #[error("entity {entity:?} has no {type_name}")]
+    MissingComponent { entity: Entity, type_name: String },
+    #[error("resource {0} is not in the world")]
+    MissingResource(String),
+    #[error("{0} has no default value")]
+    NoDefault(String),
+    #[error("path '{path}': {reason}")]
+    Path { path: String, reason: String },
+    #[error("path '{path}': expected {expected}, got {found}")]
+    TypeMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    #[error("ron: {0}")]
+    Ron(String),
+}
+
+/// One field of a struct, tuple struct or tuple; tuple fields are named by index ("0", "1", ..).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_path: &'static str,
+}
+
+/// What the registry knows about a type.
+/// - `name`: short type path, e.g. "Transform2D"
+/// - `fields`: empty for values (numbers, strings), lists, maps and enums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDescription {
+    pub name: &'static str,
+    pub type_path: &'static str,
+    pub fields: Vec<FieldDescription>,
+    pub is_component: bool,
+    pub is_resource: bool,
+    pub has_default: bool,
+}
+
+/// Register `T` (and the types of its fields) in the world's `AppTypeRegistry`,
+/// inserting the registry if there is none yet.
+pub fn register_reflect<T: GetTypeRegistration>(world: &mut World) {
+    world
+        .get_resource_or_insert_with(AppTypeRegistry::default)
+        .write()
+        .register::<T>();
+}
+
+/// The world's type registry (a cheap handle; lock it with `.read()`).
+pub fn type_registry(world: &World) -> AppTypeRegistry {
+    world
+        .get_resource::<AppTypeRegistry>()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Look a type up by short name ("Sprite") or full type path ("engine_scene::Sprite").
+pub fn find_type<'r>(
+    registry: &'r TypeRegistry,
+    name: &str,
+) -> Result<&'r TypeRegistration, ReflectError> {
+    registry
+        .get_with_short_type_path(name)
+        .or_else(|| registry.get_with_type_path(name))
+        .ok_or_else(|| ReflectError::UnknownType(name.to_string()))
+}
+
+pub fn describe(registration: &TypeRegistration) -> TypeDescription {
+    let info = registration.type_info();
+    let field = |name: String, type_path| FieldDescription { name, type_path };
+    let fields = match info {
+        TypeInfo::Struct(s) => s
+            .iter()
+            .map(|f| field(f.name().to_string(), f.type_path()))
+            .collect(),
+        TypeInfo::TupleStruct(s) => s
+            .iter()
+            .map(|f| field(f.index().to_string(), f.type_path()))
+            .collect(),
+        TypeInfo::Tuple(t) => t
+            .iter()
+            .map(|f| field(f.index().to_string(), f.type_path()))
+            .collect(),
+        _ => Vec::new(),
+    };
+    TypeDescription {
+        name: info.type_path_table().short_path(),
+        type_path: info.type_path(),
+        fields,
+        is_component: registration.data::<ReflectComponent>().is_some(),
+        is_resource: registration.data::<ReflectResource>().is_some(),
+        has_default: registration.data::<ReflectDefault>().is_some(),
+    }
+}
+
+/// Describe a registered type by name.
+pub fn describe_type(world: &World, name: &str) -> Result<TypeDescription, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    find_type(&registry, name).map(describe)
+}
+
+/// Every registered component type, sorted by name.
+pub fn reflected_components(world: &World) -> Vec<TypeDescription> {
+    reflected(world, |d| d.is_component)
+}
+
+/// Every registered resource type, sorted by name.
+pub fn reflected_resources(world: &World) -> Vec<TypeDescription> {
+    reflected(world, |d| d.is_resource)
+}
+
+fn reflected(world: &World, filter: impl Fn(&TypeDescription) -> bool) -> Vec<TypeDescription> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let mut out: Vec<_> = registry.iter().map(describe).filter(filter).collect();
+    out.sort_by_key(|d| d.name);
+    out
+}
+
+/// A new value of the named type from its `#[reflect(Default)]`.
+pub fn default_value(world: &World, name: &str) -> Result<Box<dyn Reflect>, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let registration = find_type(&registry, name)?;
+    let default = registration
+        .data::<ReflectDefault>()
+        .ok_or_else(|| ReflectError::NoDefault(name.to_string()))?;
+    Ok(default.default())
+}
+
+/// Add the named component with its default value (replacing an existing one).
+pub fn insert_default_component(
+    world: &mut World,
+    entity: Entity,
+    name: &str,
+) -> Result<(), ReflectError> {
+    let value = default_value(world, name)?;
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let component = reflect_component(&registry, name)?;
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return Err(missing(entity, name));
+    };
+    component.insert(&mut entity_mut, value.as_ref(), &registry);
+    Ok(())
+}
+
+/// Copy of the field at `path` ("" for the whole component) of the named component.
+pub fn get_component_path(
+    world: &World,
+    entity: Entity,
+    name: &str,
+    path: &str,
+) -> Result<Box<dyn Reflect>, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let component = reflect_component(&registry, name)?;
+    let value = world
+        .get_entity(entity)
+        .and_then(|e| component.reflect(e))
+        .ok_or_else(|| missing(entity, name))?;
+    Ok(field(value, path)?.clone_value())
+}
+
+/// Set the field at `path` ("" for the whole component); `value` must have the field's type.
+/// Change detection sees the component as changed.
+pub fn set_component_path(
+    world: &mut World,
+    entity: Entity,
+    name: &str,
+    path: &str,
+    value: &dyn Reflect,
+) -> Result<(), ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let component = reflect_component(&registry, name)?;
+    let mut target = world
+        .get_entity_mut(entity)
+        .and_then(|e| component.reflect_mut(e))
+        .ok_or_else(|| missing(entity, name))?;
+    apply(field_mut(target.as_reflect_mut(), path)?, path, value)
+}
+
+/// Copy of the field at `path` ("" for the whole resource) of the named resource.
+pub fn get_resource_path(
+    world: &World,
+    name: &str,
+    path: &str,
+) -> Result<Box<dyn Reflect>, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let resource = reflect_resource(&registry, name)?;
+    let value = resource
+        .reflect(world)
+        .ok_or_else(|| ReflectError::MissingResource(name.to_string()))?;
+    Ok(field(value, path)?.clone_value())
+}
+
+/// Set the field at `path` ("" for the whole resource); `value` must have the field's type.
+pub fn set_resource_path(
+    world: &mut World,
+    name: &str,
+    path: &str,
+    value: &dyn Reflect,
+) -> Result<(), ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let resource = reflect_resource(&registry, name)?;
+    let mut target = resource
+        .reflect_mut(world)
+        .ok_or_else(|| ReflectError::MissingResource(name.to_string()))?;
+    apply(field_mut(target.as_reflect_mut(), path)?, path, value)
+}
+
+/// RON text of a reflected value, e.g. `(1.0, 2.0)` for a `position` field.
+pub fn to_ron(world: &World, value: &dyn Reflect) -> Result<String, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    ron::to_string(&TypedReflectSerializer::new(value, &registry))
+        .map_err(|e| ReflectError::Ron(e.to_string()))
+}
+
+/// Parse RON text as a value of the named (registered) type.
+pub fn from_ron(
+    world: &World,
+    type_name: &str,
+    text: &str,
+) -> Result<Box<dyn Reflect>, ReflectError> {
+    let registry = type_registry(world);
+    let registry = registry.read();
+    let registration = find_type(&registry, type_name)?;
+    let mut deserializer =
+        ron::Deserializer::from_str(text).map_err(|e| ReflectError::Ron(e.to_string()))?;
+    TypedReflectDeserializer::new(registration, &registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| ReflectError::Ron(e.to_string()))
+}
+
+/// Parse RON text as a value for a component field, of the field's current type.
+pub fn component_path_from_ron(
+    world: &World,
+    entity: Entity,
+    name: &str,
+    path: &str,
+    text: &str,
+) -> Result<Box<dyn Reflect>, ReflectError> {
+    let current = get_component_path(world, entity, name, path)?;
+    from_ron(world, represented_type_path(current.as_ref()), text)
+}
+
+/// Set a component field from RON text; the text is parsed as the field's current type.
+pub fn set_component_path_ron(
+    world: &mut World,
+    entity: Entity,
+    name: &str,
+    path: &str,
+    text: &str,
+) -> Result<(), ReflectError> {
+    let value = component_path_from_ron(world, entity, name, path, text)?;
+    set_component_path(world, entity, name, path, value.as_ref())
+}
+
+/// Call `f` on every value of type `T` inside `value` (fields, elements, map values, enum
+/// payloads), e.g. to rewrite entity references. Returns how many were visited.
+pub fn visit_values_mut<T: Reflect>(value: &mut dyn Reflect, f: &mut dyn FnMut(&mut T)) -> usize {
+    if let Some(target) = value.downcast_mut::<T>() {
+        f(target);
+        return 1;
+    }
+    let mut count = 0;
+    match value.reflect_mut() {
+        ReflectMut::Struct(s) => {
+            for i in 0..s.field_len() {
+                count += s.field_at_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::TupleStruct(s) => {
+            for i in 0..s.field_len() {
+                count += s.field_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::Tuple(t) => {
+            for i in 0..t.field_len() {
+                count += t.field_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::List(l) => {
+            for i in 0..l.len() {
+                count += l.get_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::Array(a) => {
+            for i in 0..a.len() {
+                count += a.get_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::Map(m) => {
+            for i in 0..m.len() {
+                count += m.get_at_mut(i).map_or(0, |(_, v)| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::Enum(e) => {
+            for i in 0..e.field_len() {
+                count += e.field_at_mut(i).map_or(0, |v| visit_values_mut(v, f));
+            }
+        }
+        ReflectMut::Value(_) => {}
+    }
+    count
+}
+
+fn reflect_component<'r>(
+    registry: &'r TypeRegistry,
+    name: &str,
+) -> Result<&'r ReflectComponent, ReflectError> {
+    find_type(registry, name)?
+        .data::<ReflectComponent>()
+        .ok_or_else(|| ReflectError::NotAComponent(name.to_string()))
+}
+
+fn reflect_resource<'r>(
+    registry: &'r TypeRegistry,
+    name: &str,
+) -> Result<&'r ReflectResource, ReflectError> {
+    find_type(registry, name)?
+        .data::<ReflectResource>()
+        .ok_or_else(|| ReflectError::NotAResource(name.to_string()))
+}
+
+fn missing(entity: Entity, name: &str) -> ReflectError {
+    ReflectError::MissingComponent {
+        entity,
+        type_name: name.to_string(),
+    }
+}
+
+fn field<'a>(value: &'a dyn Reflect, path: &str) -> Result<&'a dyn Reflect, ReflectError> {
+    if path.is_empty() {
+        return Ok(value);
+    }
+    value
+        .reflect_path(path)
+        .map_err(|e| path_error(path, e.to_string()))
+}
+
+fn field_mut<'a>(
+    value: &'a mut dyn Reflect,
+    path: &str,
+) -> Result<&'a mut dyn Reflect, ReflectError> {
+    if path.is_empty() {
+        return Ok(value);
+    }
+    value
+        .reflect_path_mut(path)
+        .map_err(|e| path_error(path, e.to_string()))
+}
+
+fn path_error(path: &str, reason: String) -> ReflectError {
+    ReflectError::Path {
+        path: path.to_string(),
+        reason,
+    }
+}
+
+/// Type path a value stands for; dynamic values (e.g. from deserialization) report the type they represent.
+fn represented_type_path(value: &dyn Reflect) -> &str {
+    match value.get_represented_type_info() {
+        Some(info) => info.type_path(),
+        None => value.reflect_type_path(),
+    }
+}
+
+/// `Reflect::apply` panics on mismatched types, so check first.
+fn apply(target: &mut dyn Reflect, path: &str, value: &dyn Reflect) -> Result<(), ReflectError> {
+    let expected = represented_type_path(target);
+    let found = represented_type_path(value);
+    if expected != found {
+        return Err(ReflectError::TypeMismatch {
+            path: path.to_string(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        });
+    }
+    target.apply(value);
+    Ok(())
+}
@@ -0,0 +1,347 @@
+/**
+ * path: /crates/engine_input/src/action.rs
+ * description: Named actions evaluated from the raw input state once per frame.
+ * `ActionMap` holds the loaded `InputMap`, the user's overrides and the active contexts;
+ * `update_actions` writes `ActionState`, which gameplay reads instead of raw keys.
+ */
+use bevy_ecs::prelude::{
+    Res,
+    ResMut,
+    Resource,
+};
+use glam::Vec2;
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use crate::{
+    ActionBinding,
+    Axis2dBinding,
+    AxisBinding,
+    ButtonBinding,
+    ButtonInput,
+    GamepadAxis,
+    Gamepads,
+    InputMap,
+    InputOverrides,
+    KeyCode,
+    MouseButton,
+    MouseWheel,
+    DEFAULT_DEAD_ZONE,
+};
+
+/// Bindings in effect: the authored map, user overrides on top, and the enabled contexts.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActionMap {
+    map: InputMap,
+    overrides: InputOverrides,
+    active: BTreeSet<String>,
+}
+
+impl ActionMap {
+    /// Start with `map`'s initial contexts.
+    pub fn new(map: InputMap) -> Self {
+        let mut actions = Self::default();
+        actions.set_map(map);
+        actions.active = if actions.map.active.is_empty() {
+            actions.map.contexts.keys().cloned().collect()
+        } else {
+            actions.map.active.iter().cloned().collect()
+        };
+        actions
+    }
+
+    pub fn map(&self) -> &InputMap {
+        &self.map
+    }
+
+    /// Replace the authored bindings (e.g. after a hot reload); overrides and the active
+    /// contexts are kept.
+    pub fn set_map(&mut self, map: InputMap) {
+        self.map = map;
+    }
+
+    pub fn enable_context(&mut self, context: &str) {
+        self.active.insert(context.to_string());
+    }
+
+    pub fn disable_context(&mut self, context: &str) {
+        self.active.remove(context);
+    }
+
+    /// Enable exactly these contexts, e.g. `["menu"]` while a menu is open.
+    pub fn set_contexts<'a>(&mut self, contexts: impl IntoIterator<Item = &'a str>) {
+        self.active = contexts.into_iter().map(str::to_string).collect();
+    }
+
+    pub fn is_context_active(&self, context: &str) -> bool {
+        self.active.contains(context)
+    }
+
+    /// Rebind an action for this user; `overrides()` has everything to save.
+    pub fn rebind(&mut self, context: &str, action: &str, binding: ActionBinding) {
+        self.overrides
+            .contexts
+            .entry(context.to_string())
+            .or_default()
+            .insert(action.to_string(), binding);
+    }
+
+    /// Back to the authored binding.
+    pub fn reset_binding(&mut self, context: &str, action: &str) {
+        if let Some(set) = self.overrides.contexts.get_mut(context) {
+            set.remove(action);
+        }
+    }
+
+    pub fn overrides(&self) -> &InputOverrides {
+        &self.overrides
+    }
+
+    /// Apply previously saved overrides, replacing the current ones.
+    pub fn set_overrides(&mut self, overrides: InputOverrides) {
+        self.overrides = overrides;
+    }
+
+    /// Binding in effect for an action of a context (override first).
+    pub fn binding(&self, context: &str, action: &str) -> Option<&ActionBinding> {
+        self.overrides
+            .binding(context, action)
+            .or_else(|| self.map.binding(context, action))
+    }
+
+    /// Actions of the active contexts with their effective bindings.
+    fn active_bindings(&self) -> Vec<(&str, &ActionBinding)> {
+        let mut bindings = Vec::new();
+        for context in &self.active {
+            let authored = self.map.contexts.get(context).into_iter().flatten();
+            let overridden = self.overrides.contexts.get(context).into_iter().flatten();
+            let actions: BTreeSet<&String> = authored
+                .chain(overridden)
+                .map(|(action, _)| action)
+                .collect();
+            for action in actions {
+                if let Some(binding) = self.binding(context, action) {
+                    bindings.push((action.as_str(), binding));
+                }
+            }
+        }
+        bindings
+    }
+}
+
+/// State of one action this frame.
+/// - `value`: strength in `0..=1` for buttons, the axis value for 1D axes, the length for 2D
+/// - `axis`: the 2D value (x right, y up); `(value, 0)` for 1D axes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionValue {
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+    pub value: f32,
+    pub axis: Vec2,
+}
+
+/// Per-action state for gameplay code; unknown or inactive actions read as released / zero.
+/// An axis counts as pressed while it is off center.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActionState {
+    actions: BTreeMap<String, ActionValue>,
+}
+
+impl ActionState {
+    pub fn get(&self, action: &str) -> ActionValue {
+        self.actions.get(action).copied().unwrap_or_default()
+    }
+
+    pub fn pressed(&self, action: &str) -> bool {
+        self.get(action).pressed
+    }
+
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.get(action).just_pressed
+    }
+
+    pub fn just_released(&self, action: &str) -> bool {
+        self.get(action).just_released
+    }
+
+    pub fn value(&self, action: &str) -> f32 {
+        self.get(action).value
+    }
+
+    pub fn axis_pair(&self, action: &str) -> Vec2 {
+        self.get(action).axis
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ActionValue)> {
+        self.actions
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+/// Evaluate the active bindings into `ActionState` (after `update_input`).
+pub fn update_actions(
+    map: Res<ActionMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    wheel: Res<MouseWheel>,
+    gamepads: Res<Gamepads>,
+    mut state: ResMut<ActionState>,
+) {
+    let sources = Sources {
+        keys: &keys,
+        mouse: &mouse,
+        wheel: &wheel,
+        gamepads: &gamepads,
+    };
+    // An action bound in several active contexts takes the strongest value
+    let mut current: BTreeMap<&str, (bool, f32, Vec2)> = BTreeMap::new();
+    for (action, binding) in map.active_bindings() {
+        let (pressed, value, axis) = sources.evaluate(binding);
+        let entry = current.entry(action).or_default();
+        entry.0 |= pressed;
+        if value.abs() > entry.1.abs() {
+            (entry.1, entry.2) = (value, axis);
+        }
+    }
+
+    let previous = std::mem::take(&mut state.actions);
+    let names: BTreeSet<&str> = previous
+        .keys()
+        .map(String::as_str)
+        .chain(current.keys().copied())
+        .collect();
+    for name in names {
+        let was_pressed = previous.get(name).is_some_and(|v| v.pressed);
+        let (pressed, value, axis) = current.get(name).copied().unwrap_or_default();
+        if !pressed && !was_pressed {
+            continue;
+        }
+        state.actions.insert(
+            name.to_string(),
+            ActionValue {
+                pressed,
+                just_pressed: pressed && !was_pressed,
+                just_released: !pressed && was_pressed,
+                value,
+                axis,
+            },
+        );
+    }
+}
+
+struct Sources<'a> {
+    keys: &'a ButtonInput<KeyCode>,
+    mouse: &'a ButtonInput<MouseButton>,
+    wheel: &'a MouseWheel,
+    gamepads: &'a Gamepads,
+}
+
+impl Sources<'_> {
+    /// (pressed, value, axis) as in `ActionValue`
+    fn evaluate(&self, binding: &ActionBinding) -> (bool, f32, Vec2) {
+        match binding {
+            ActionBinding::Button(buttons) => {
+                let pressed = buttons
+                    .iter()
+                    .any(|b| b.pressed(self.keys, self.mouse, self.gamepads));
+                let value = if pressed { 1.0 } else { 0.0 };
+                (pressed, value, Vec2::new(value, 0.0))
+            }
+            ActionBinding::Axis {
+                bindings,
+                dead_zone,
+            } => {
+                let dead_zone = dead_zone.unwrap_or(DEFAULT_DEAD_ZONE);
+                let value = strongest(bindings.iter().map(|b| self.axis(b, dead_zone)));
+                (value != 0.0, value, Vec2::new(value, 0.0))
+            }
+            ActionBinding::Axis2d {
+                bindings,
+                dead_zone,
+            } => {
+                let dead_zone = dead_zone.unwrap_or(DEFAULT_DEAD_ZONE);
+                let axis = bindings.iter().map(|b| self.axis_2d(b, dead_zone)).fold(
+                    Vec2::ZERO,
+                    |best, v| {
+                        if v.length_squared() > best.length_squared() {
+                            v
+                        } else {
+                            best
+                        }
+                    },
+                );
+                (axis != Vec2::ZERO, axis.length(), axis)
+            }
+        }
+    }
+
+    fn button(&self, binding: &ButtonBinding) -> f32 {
+        if binding.pressed(self.keys, self.mouse, self.gamepads) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Strongest value of an axis over all gamepads.
+    fn pad_axis(&self, axis: GamepadAxis) -> f32 {
+        strongest(self.gamepads.iter().map(|(_, pad)| pad.axis(axis)))
+    }
+
+    fn axis(&self, binding: &AxisBinding, dead_zone: f32) -> f32 {
+        match binding {
+            AxisBinding::Keys { negative, positive } => {
+                self.button(positive) - self.button(negative)
+            }
+            AxisBinding::Gamepad(axis) => apply_dead_zone(self.pad_axis(*axis), dead_zone),
+            AxisBinding::WheelX => self.wheel.delta.x,
+            AxisBinding::WheelY => self.wheel.delta.y,
+        }
+    }
+
+    fn axis_2d(&self, binding: &Axis2dBinding, dead_zone: f32) -> Vec2 {
+        let stick = |x: GamepadAxis, y: GamepadAxis| {
+            // The standard layout reports y down; actions use y up
+            let value = Vec2::new(self.pad_axis(x), -self.pad_axis(y));
+            let length = value.length();
+            if length <= dead_zone {
+                Vec2::ZERO
+            } else {
+                value / length * apply_dead_zone(length.min(1.0), dead_zone)
+            }
+        };
+        match binding {
+            Axis2dBinding::Keys {
+                up,
+                down,
+                left,
+                right,
+            } => Vec2::new(
+                self.button(right) - self.button(left),
+                self.button(up) - self.button(down),
+            )
+            .normalize_or_zero(),
+            Axis2dBinding::LeftStick => stick(GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            Axis2dBinding::RightStick => stick(GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        }
+    }
+}
+
+/// Value furthest from 0.
+fn strongest(values: impl Iterator<Item = f32>) -> f32 {
+    values.fold(0.0, |best, v| if v.abs() > best.abs() { v } else { best })
+}
+
+/// 0 inside the dead zone, rescaled to reach 1 at the edge (keeps small movements precise).
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    if value.abs() <= dead_zone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
@@ -0,0 +1,189 @@
+/**
+ * path: /crates/engine_input/src/binding.rs
+ * description: Action/axis bindings as authored in `.input.ron` files, and user overrides.
+ *
+ * ```ron
+ * InputMap(
+ *     contexts: {
+ *         "gameplay": {
+ *             "jump": Button([Key(Space), Gamepad(South)]),
+ *             "zoom": Axis(bindings: [Keys(negative: Key(KeyQ), positive: Key(KeyE)), WheelY]),
+ *             "move": Axis2d(
+ *                 bindings: [Keys(up: Key(KeyW), down: Key(KeyS), left: Key(KeyA), right: Key(KeyD)), LeftStick],
+ *                 dead_zone: Some(0.2),
+ *             ),
+ *         },
+ *         "menu": { "confirm": Button([Key(Enter), Gamepad(South)]) },
+ *     },
+ *     active: ["gameplay"],
+ * )
+ * ```
+ */
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::{
+    ButtonInput,
+    GamepadAxis,
+    GamepadButton,
+    Gamepads,
+    KeyCode,
+    MouseButton,
+};
+
+/// File extension of input maps.
+pub const INPUT_MAP_EXTENSION: &str = "input.ron";
+
+/// Dead zone for analog bindings that don't set their own.
+pub const DEFAULT_DEAD_ZONE: f32 = 0.15;
+
+#[derive(Error, Debug)]
+pub enum InputMapError {
+    #[error("parse: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("write: {0}")]
+    Write(#[from] ron::Error),
+}
+
+/// A digital input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ButtonBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl ButtonBinding {
+    /// Held on the keyboard, the mouse or any gamepad.
+    pub fn pressed(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Gamepads,
+    ) -> bool {
+        match *self {
+            ButtonBinding::Key(key) => keys.pressed(key),
+            ButtonBinding::Mouse(button) => mouse.pressed(button),
+            ButtonBinding::Gamepad(button) => gamepads.iter().any(|(_, pad)| pad.pressed(button)),
+        }
+    }
+
+    /// First button pressed this frame, for "press a key" rebinding prompts.
+    pub fn first_just_pressed(
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Gamepads,
+    ) -> Option<ButtonBinding> {
+        // Sets are unordered; pick the smallest so the result doesn't vary between runs
+        let key = keys.get_just_pressed().min().copied();
+        let button = mouse.get_just_pressed().min().copied();
+        let pad_button = gamepads
+            .iter()
+            .flat_map(|(_, pad)| pad.buttons.get_just_pressed().copied())
+            .min();
+        key.map(ButtonBinding::Key)
+            .or(button.map(ButtonBinding::Mouse))
+            .or(pad_button.map(ButtonBinding::Gamepad))
+    }
+}
+
+/// A source of one value in `-1..=1` (triggers and wheel: `0..=1` / unbounded lines).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    /// Two buttons: `negative` gives -1, `positive` +1, both cancel out.
+    Keys {
+        negative: ButtonBinding,
+        positive: ButtonBinding,
+    },
+    Gamepad(GamepadAxis),
+    /// Wheel lines this frame (`MouseWheel`); not dead-zoned.
+    WheelX,
+    WheelY,
+}
+
+/// A source of a 2D vector (x right, y up, length at most 1).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Axis2dBinding {
+    Keys {
+        up: ButtonBinding,
+        down: ButtonBinding,
+        left: ButtonBinding,
+        right: ButtonBinding,
+    },
+    LeftStick,
+    RightStick,
+}
+
+/// How an action is triggered. Bindings of one action are alternatives: a button action is
+/// pressed if any binding is, an axis takes the strongest value.
+/// - `dead_zone`: analog values below it read as 0 (radial for sticks); default
+///   `DEFAULT_DEAD_ZONE`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ActionBinding {
+    Button(Vec<ButtonBinding>),
+    Axis {
+        bindings: Vec<AxisBinding>,
+        #[serde(default)]
+        dead_zone: Option<f32>,
+    },
+    Axis2d {
+        bindings: Vec<Axis2dBinding>,
+        #[serde(default)]
+        dead_zone: Option<f32>,
+    },
+}
+
+/// Bindings of one context (e.g. "gameplay", "menu") by action name.
+pub type BindingSet = BTreeMap<String, ActionBinding>;
+
+/// Contents of a `.input.ron` file.
+/// - `contexts`: binding sets by context name
+/// - `active`: contexts enabled when the map is first loaded (all if empty)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    #[serde(default)]
+    pub contexts: BTreeMap<String, BindingSet>,
+    #[serde(default)]
+    pub active: Vec<String>,
+}
+
+impl InputMap {
+    pub fn from_ron_str(text: &str) -> Result<InputMap, InputMapError> {
+        Ok(ron::from_str(text)?)
+    }
+
+    pub fn binding(&self, context: &str, action: &str) -> Option<&ActionBinding> {
+        self.contexts.get(context)?.get(action)
+    }
+}
+
+/// Bindings the user changed, by context and action; saved separately from the authored map
+/// so updates to the game's defaults still apply to everything else.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputOverrides {
+    #[serde(default)]
+    pub contexts: BTreeMap<String, BindingSet>,
+}
+
+impl InputOverrides {
+    pub fn from_ron_str(text: &str) -> Result<InputOverrides, InputMapError> {
+        Ok(ron::from_str(text)?)
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, InputMapError> {
+        let config = ron::ser::PrettyConfig::new();
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    pub fn binding(&self, context: &str, action: &str) -> Option<&ActionBinding> {
+        self.contexts.get(context)?.get(action)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contexts.values().all(BTreeMap::is_empty)
+    }
+}
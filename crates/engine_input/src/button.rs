@@ -0,0 +1,84 @@
+/**
+ * path: /crates/engine_input/src/button.rs
+ * description: Pressed / just-pressed / just-released state for any kind of button.
+ */
+use bevy_ecs::prelude::Resource;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Button state for one device (keys, mouse buttons, gamepad buttons).
+/// `just_pressed`/`just_released` hold for the frame in which the change was applied.
+#[derive(Resource, Debug, Clone)]
+pub struct ButtonInput<T: Copy + Eq + Hash + Send + Sync + 'static> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> Default for ButtonInput<T> {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            just_released: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> ButtonInput<T> {
+    /// Key repeat (pressing a held button again) doesn't count as just pressed.
+    pub fn press(&mut self, button: T) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    pub fn release(&mut self, button: T) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    /// Release everything, e.g. when the canvas loses focus and key-up events won't arrive.
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    pub fn pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    pub fn any_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|b| self.pressed(b))
+    }
+
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    pub fn any_just_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|b| self.just_pressed(b))
+    }
+
+    pub fn just_released(&self, button: T) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    pub fn get_pressed(&self) -> impl Iterator<Item = &T> {
+        self.pressed.iter()
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &T> {
+        self.just_pressed.iter()
+    }
+
+    pub fn get_just_released(&self) -> impl Iterator<Item = &T> {
+        self.just_released.iter()
+    }
+
+    /// Start a new frame: forget the just-pressed/just-released sets.
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
@@ -0,0 +1,95 @@
+/**
+ * path: /crates/engine_input/src/event.rs
+ * description: Device-agnostic input events and the queue backends push them into.
+ * Backends (DOM listeners, winit) run outside the ECS schedule; they only queue events.
+ * `update_input` applies the queue once per frame in `PreUpdate`.
+ */
+use bevy_ecs::prelude::Resource;
+use glam::Vec2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    GamepadAxis,
+    GamepadButton,
+    GamepadId,
+    KeyCode,
+    MouseButton,
+};
+
+/// One input change. Positions are canvas pixels (origin top-left, y down).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    KeyDown(KeyCode),
+    KeyUp(KeyCode),
+    MouseDown(MouseButton),
+    MouseUp(MouseButton),
+    CursorMoved(Vec2),
+    CursorLeft,
+    /// Wheel movement in lines (see `MouseWheel`).
+    Wheel(Vec2),
+    TouchStart {
+        id: u64,
+        position: Vec2,
+    },
+    TouchMove {
+        id: u64,
+        position: Vec2,
+    },
+    /// Lifted or cancelled.
+    TouchEnd {
+        id: u64,
+        position: Vec2,
+    },
+    GamepadConnected {
+        id: GamepadId,
+        name: String,
+    },
+    GamepadDisconnected(GamepadId),
+    GamepadButton {
+        id: GamepadId,
+        button: GamepadButton,
+        pressed: bool,
+    },
+    GamepadAxis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+    /// The canvas/window lost focus; everything held is released.
+    FocusLost,
+}
+
+/// Events received since the last frame, in arrival order.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct InputQueue {
+    events: Vec<InputEvent>,
+}
+
+impl InputQueue {
+    pub fn push(&mut self, event: InputEvent) {
+        self.events.push(event);
+    }
+
+    pub fn extend(&mut self, events: impl IntoIterator<Item = InputEvent>) {
+        self.events.extend(events);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &InputEvent> {
+        self.events.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn drain(&mut self) -> std::vec::Drain<'_, InputEvent> {
+        self.events.drain(..)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -0,0 +1,192 @@
+/**
+ * path: /crates/engine_input/src/gamepad.rs
+ * description: Connected gamepads with their buttons and axes.
+ * Buttons and axes follow the W3C "standard" gamepad layout, which browsers and most native
+ * libraries map controllers to.
+ */
+use bevy_ecs::prelude::Resource;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::BTreeMap;
+
+use crate::ButtonInput;
+
+/// Backend-assigned gamepad slot (the DOM `Gamepad.index`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GamepadId(pub u32);
+
+/// Gamepad button by position (`South` is A on Xbox, Cross on PlayStation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Mode,
+}
+
+impl GamepadButton {
+    /// Buttons in standard-mapping order (`Gamepad.buttons[i]`).
+    pub const STANDARD: [GamepadButton; 17] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::LeftTrigger,
+        GamepadButton::RightTrigger,
+        GamepadButton::Select,
+        GamepadButton::Start,
+        GamepadButton::LeftStick,
+        GamepadButton::RightStick,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+        GamepadButton::Mode,
+    ];
+
+    pub fn from_standard_index(index: usize) -> Option<GamepadButton> {
+        Self::STANDARD.get(index).copied()
+    }
+}
+
+/// Analog input; sticks range over `-1..=1` (y down, as reported by the standard layout),
+/// triggers over `0..=1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// Axes in standard-mapping order (`Gamepad.axes[i]`); triggers are buttons 6 and 7 there.
+    pub const STANDARD: [GamepadAxis; 4] = [
+        GamepadAxis::LeftStickX,
+        GamepadAxis::LeftStickY,
+        GamepadAxis::RightStickX,
+        GamepadAxis::RightStickY,
+    ];
+
+    pub fn from_standard_index(index: usize) -> Option<GamepadAxis> {
+        Self::STANDARD.get(index).copied()
+    }
+}
+
+/// State of one connected gamepad.
+#[derive(Debug, Clone, Default)]
+pub struct Gamepad {
+    pub name: String,
+    pub buttons: ButtonInput<GamepadButton>,
+    axes: BTreeMap<GamepadAxis, f32>,
+}
+
+impl Gamepad {
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.pressed(button)
+    }
+
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.just_pressed(button)
+    }
+
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.buttons.just_released(button)
+    }
+
+    /// Current value, 0 if the axis never reported.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.axes.insert(axis, value);
+    }
+}
+
+/// Connected gamepads by id.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Gamepads {
+    pads: BTreeMap<GamepadId, Gamepad>,
+    just_connected: Vec<GamepadId>,
+    just_disconnected: Vec<GamepadId>,
+}
+
+impl Gamepads {
+    pub fn connect(&mut self, id: GamepadId, name: impl Into<String>) {
+        let pad = Gamepad {
+            name: name.into(),
+            ..Default::default()
+        };
+        if self.pads.insert(id, pad).is_none() {
+            self.just_connected.push(id);
+        }
+    }
+
+    pub fn disconnect(&mut self, id: GamepadId) {
+        if self.pads.remove(&id).is_some() {
+            self.just_disconnected.push(id);
+        }
+    }
+
+    pub fn get(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.pads.get(&id)
+    }
+
+    /// The gamepad, connecting it (unnamed) if a backend reports input before the connection.
+    pub fn get_or_connect(&mut self, id: GamepadId) -> &mut Gamepad {
+        if !self.pads.contains_key(&id) {
+            self.connect(id, "");
+        }
+        self.pads.get_mut(&id).expect("just connected")
+    }
+
+    /// Connected gamepads, lowest id first; the first one is the usual "player one" pad.
+    pub fn iter(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        self.pads.iter().map(|(id, pad)| (*id, pad))
+    }
+
+    pub fn just_connected(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.just_connected.iter().copied()
+    }
+
+    pub fn just_disconnected(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.just_disconnected.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pads.is_empty()
+    }
+
+    /// Start a new frame.
+    pub fn clear(&mut self) {
+        self.just_connected.clear();
+        self.just_disconnected.clear();
+        for pad in self.pads.values_mut() {
+            pad.buttons.clear();
+        }
+    }
+}
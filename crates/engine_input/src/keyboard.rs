@@ -0,0 +1,245 @@
+/**
+ * path: /crates/engine_input/src/keyboard.rs
+ * description: Physical key codes, named after the DOM `KeyboardEvent.code` values.
+ * Codes describe key positions (`KeyW` is the key left of `KeyE` on any layout), which is what
+ * movement and shortcut bindings want.
+ */
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::fmt;
+
+/// A physical key. Keys the engine doesn't know are dropped by the backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum KeyCode {
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ShiftLeft,
+    ShiftRight,
+    ControlLeft,
+    ControlRight,
+    AltLeft,
+    AltRight,
+    MetaLeft,
+    MetaRight,
+    CapsLock,
+    Minus,
+    Equal,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Semicolon,
+    Quote,
+    Backquote,
+    Comma,
+    Period,
+    Slash,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadMultiply,
+    NumpadDivide,
+    NumpadDecimal,
+    NumpadEnter,
+}
+
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "KeyA"),
+    (KeyCode::KeyB, "KeyB"),
+    (KeyCode::KeyC, "KeyC"),
+    (KeyCode::KeyD, "KeyD"),
+    (KeyCode::KeyE, "KeyE"),
+    (KeyCode::KeyF, "KeyF"),
+    (KeyCode::KeyG, "KeyG"),
+    (KeyCode::KeyH, "KeyH"),
+    (KeyCode::KeyI, "KeyI"),
+    (KeyCode::KeyJ, "KeyJ"),
+    (KeyCode::KeyK, "KeyK"),
+    (KeyCode::KeyL, "KeyL"),
+    (KeyCode::KeyM, "KeyM"),
+    (KeyCode::KeyN, "KeyN"),
+    (KeyCode::KeyO, "KeyO"),
+    (KeyCode::KeyP, "KeyP"),
+    (KeyCode::KeyQ, "KeyQ"),
+    (KeyCode::KeyR, "KeyR"),
+    (KeyCode::KeyS, "KeyS"),
+    (KeyCode::KeyT, "KeyT"),
+    (KeyCode::KeyU, "KeyU"),
+    (KeyCode::KeyV, "KeyV"),
+    (KeyCode::KeyW, "KeyW"),
+    (KeyCode::KeyX, "KeyX"),
+    (KeyCode::KeyY, "KeyY"),
+    (KeyCode::KeyZ, "KeyZ"),
+    (KeyCode::Digit0, "Digit0"),
+    (KeyCode::Digit1, "Digit1"),
+    (KeyCode::Digit2, "Digit2"),
+    (KeyCode::Digit3, "Digit3"),
+    (KeyCode::Digit4, "Digit4"),
+    (KeyCode::Digit5, "Digit5"),
+    (KeyCode::Digit6, "Digit6"),
+    (KeyCode::Digit7, "Digit7"),
+    (KeyCode::Digit8, "Digit8"),
+    (KeyCode::Digit9, "Digit9"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::ArrowUp, "ArrowUp"),
+    (KeyCode::ArrowDown, "ArrowDown"),
+    (KeyCode::ArrowLeft, "ArrowLeft"),
+    (KeyCode::ArrowRight, "ArrowRight"),
+    (KeyCode::ShiftLeft, "ShiftLeft"),
+    (KeyCode::ShiftRight, "ShiftRight"),
+    (KeyCode::ControlLeft, "ControlLeft"),
+    (KeyCode::ControlRight, "ControlRight"),
+    (KeyCode::AltLeft, "AltLeft"),
+    (KeyCode::AltRight, "AltRight"),
+    (KeyCode::MetaLeft, "MetaLeft"),
+    (KeyCode::MetaRight, "MetaRight"),
+    (KeyCode::CapsLock, "CapsLock"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equal, "Equal"),
+    (KeyCode::BracketLeft, "BracketLeft"),
+    (KeyCode::BracketRight, "BracketRight"),
+    (KeyCode::Backslash, "Backslash"),
+    (KeyCode::Semicolon, "Semicolon"),
+    (KeyCode::Quote, "Quote"),
+    (KeyCode::Backquote, "Backquote"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Slash, "Slash"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+    (KeyCode::Numpad0, "Numpad0"),
+    (KeyCode::Numpad1, "Numpad1"),
+    (KeyCode::Numpad2, "Numpad2"),
+    (KeyCode::Numpad3, "Numpad3"),
+    (KeyCode::Numpad4, "Numpad4"),
+    (KeyCode::Numpad5, "Numpad5"),
+    (KeyCode::Numpad6, "Numpad6"),
+    (KeyCode::Numpad7, "Numpad7"),
+    (KeyCode::Numpad8, "Numpad8"),
+    (KeyCode::Numpad9, "Numpad9"),
+    (KeyCode::NumpadAdd, "NumpadAdd"),
+    (KeyCode::NumpadSubtract, "NumpadSubtract"),
+    (KeyCode::NumpadMultiply, "NumpadMultiply"),
+    (KeyCode::NumpadDivide, "NumpadDivide"),
+    (KeyCode::NumpadDecimal, "NumpadDecimal"),
+    (KeyCode::NumpadEnter, "NumpadEnter"),
+];
+
+impl KeyCode {
+    /// Key for a DOM `KeyboardEvent.code` (e.g. `"KeyW"`, `"ArrowUp"`).
+    pub fn from_code(code: &str) -> Option<KeyCode> {
+        KEY_NAMES
+            .iter()
+            .find(|(_, name)| *name == code)
+            .map(|(key, _)| *key)
+    }
+
+    /// The DOM `code` name; also used in binding files.
+    pub fn name(self) -> &'static str {
+        KEY_NAMES
+            .iter()
+            .find(|(key, _)| *key == self)
+            .map_or("", |(_, name)| name)
+    }
+
+    pub fn all() -> impl Iterator<Item = KeyCode> {
+        KEY_NAMES.iter().map(|(key, _)| *key)
+    }
+}
+
+impl fmt::Display for KeyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
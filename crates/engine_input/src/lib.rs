@@ -1,5 +1,178 @@
-// /**
-//  * path: /crates/engine_input/src/lib.rs
-//  * description: Input abstraction placeholder.
-//  */
-// Input abstraction placeholder
+/**
+ * path: /crates/engine_input/src/lib.rs
+ * description: Device-agnostic input state (keyboard, mouse, touch, gamepads) as ECS resources.
+ * Platform backends queue `InputEvent`s; `InputPlugin` applies them once per frame in
+ * `PreUpdate`, before gameplay systems run.
+ */
+use bevy_ecs::prelude::{
+    IntoSystemConfigs,
+    Res,
+    ResMut,
+};
+use bevy_ecs::schedule::SystemSet;
+use engine_core::{
+    EngineApp,
+    Plugin,
+    PreUpdate,
+};
+
+mod action;
+mod binding;
+mod button;
+mod event;
+mod gamepad;
+mod keyboard;
+mod mouse;
+mod record;
+mod touch;
+mod viewport;
+pub mod winit;
+pub use action::{
+    update_actions,
+    ActionMap,
+    ActionState,
+    ActionValue,
+};
+pub use binding::{
+    ActionBinding,
+    Axis2dBinding,
+    AxisBinding,
+    BindingSet,
+    ButtonBinding,
+    InputMap,
+    InputMapError,
+    InputOverrides,
+    DEFAULT_DEAD_ZONE,
+    INPUT_MAP_EXTENSION,
+};
+pub use button::ButtonInput;
+pub use event::{
+    InputEvent,
+    InputQueue,
+};
+pub use gamepad::{
+    Gamepad,
+    GamepadAxis,
+    GamepadButton,
+    GamepadId,
+    Gamepads,
+};
+pub use keyboard::KeyCode;
+pub use mouse::{
+    Cursor,
+    MouseButton,
+    MouseWheel,
+    PIXELS_PER_LINE,
+};
+pub use record::{
+    record_input,
+    InputRecorder,
+    InputRecording,
+    InputReplay,
+    RecordedFrame,
+    RecordingError,
+    INPUT_RECORDING_EXTENSION,
+    INPUT_RECORDING_VERSION,
+};
+pub use touch::{
+    Touch,
+    Touches,
+};
+pub use viewport::Viewport;
+
+/// Input resources (`ButtonInput<KeyCode>`, `ButtonInput<MouseButton>`, `Cursor`, `MouseWheel`,
+/// `Touches`, `Gamepads`, `Viewport`, `InputQueue`, `ActionMap`, `ActionState`,
+/// `InputRecorder`) with `record_input`, `update_input` and then `update_actions` in `PreUpdate`.
+pub struct InputPlugin;
+
+/// `record_input`, `update_input` and `update_actions`; `PreUpdate` systems reading input should run
+/// `.after(InputSystems)`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputSystems;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.init_resource::<InputQueue>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<Cursor>()
+            .init_resource::<MouseWheel>()
+            .init_resource::<Touches>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Viewport>()
+            .init_resource::<ActionMap>()
+            .init_resource::<ActionState>()
+            .init_resource::<InputRecorder>()
+            .add_systems(
+                PreUpdate,
+                (record_input, update_input, update_actions)
+                    .chain()
+                    .in_set(InputSystems),
+            );
+    }
+}
+
+/// Start a new input frame and apply the queued events in order.
+#[allow(clippy::too_many_arguments)]
+pub fn update_input(
+    mut queue: ResMut<InputQueue>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut cursor: ResMut<Cursor>,
+    mut wheel: ResMut<MouseWheel>,
+    mut touches: ResMut<Touches>,
+    mut gamepads: ResMut<Gamepads>,
+    viewport: Res<Viewport>,
+) {
+    keys.clear();
+    mouse.clear();
+    touches.clear();
+    gamepads.clear();
+    cursor.delta = glam::Vec2::ZERO;
+    wheel.delta = glam::Vec2::ZERO;
+
+    for event in queue.drain() {
+        match event {
+            InputEvent::KeyDown(key) => keys.press(key),
+            InputEvent::KeyUp(key) => keys.release(key),
+            InputEvent::MouseDown(button) => mouse.press(button),
+            InputEvent::MouseUp(button) => mouse.release(button),
+            InputEvent::CursorMoved(position) => {
+                if let Some(previous) = cursor.position {
+                    cursor.delta += position - previous;
+                }
+                cursor.position = Some(position);
+            }
+            InputEvent::CursorLeft => cursor.position = None,
+            InputEvent::Wheel(delta) => wheel.delta += delta,
+            InputEvent::TouchStart { id, position } => touches.start(id, position),
+            InputEvent::TouchMove { id, position } => touches.moved(id, position),
+            InputEvent::TouchEnd { id, position } => touches.end(id, position),
+            InputEvent::GamepadConnected { id, name } => gamepads.connect(id, name),
+            InputEvent::GamepadDisconnected(id) => gamepads.disconnect(id),
+            InputEvent::GamepadButton {
+                id,
+                button,
+                pressed,
+            } => {
+                let pad = gamepads.get_or_connect(id);
+                if pressed {
+                    pad.buttons.press(button);
+                } else {
+                    pad.buttons.release(button);
+                }
+            }
+            InputEvent::GamepadAxis { id, axis, value } => {
+                gamepads.get_or_connect(id).set_axis(axis, value)
+            }
+            InputEvent::FocusLost => {
+                keys.release_all();
+                mouse.release_all();
+                touches.end_all();
+            }
+        }
+    }
+
+    // Also follows camera movement while the cursor stands still
+    cursor.world_position = cursor.position.and_then(|p| viewport.to_world_2d(p));
+}
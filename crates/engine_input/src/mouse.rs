@@ -0,0 +1,57 @@
+/**
+ * path: /crates/engine_input/src/mouse.rs
+ * description: Mouse buttons, cursor position and wheel state.
+ */
+use bevy_ecs::prelude::Resource;
+use glam::Vec2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Mouse button, numbered like DOM `MouseEvent.button`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+    Other(u16),
+}
+
+impl MouseButton {
+    /// Button for a DOM `MouseEvent.button` value (0 = left, 1 = middle, 2 = right).
+    pub fn from_dom(button: i16) -> MouseButton {
+        match button {
+            0 => MouseButton::Left,
+            1 => MouseButton::Middle,
+            2 => MouseButton::Right,
+            3 => MouseButton::Back,
+            4 => MouseButton::Forward,
+            other => MouseButton::Other(other.max(0) as u16),
+        }
+    }
+}
+
+/// Cursor over the canvas.
+/// - `position`: canvas pixels (backing-store size, origin top-left, y down); `None` while the
+///   cursor is outside the canvas
+/// - `delta`: movement since the previous frame in canvas pixels
+/// - `world_position`: `position` projected through the `Viewport` onto the z = 0 plane
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Cursor {
+    pub position: Option<Vec2>,
+    pub delta: Vec2,
+    pub world_position: Option<Vec2>,
+}
+
+/// Mouse wheel movement this frame, in lines (one notch is usually one line; pixel-precise
+/// devices are converted with `PIXELS_PER_LINE`). Positive `y` scrolls down.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MouseWheel {
+    pub delta: Vec2,
+}
+
+/// Pixel deltas per wheel line, for backends reporting pixels.
+pub const PIXELS_PER_LINE: f32 = 100.0;
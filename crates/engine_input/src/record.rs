@@ -0,0 +1,387 @@
+/**
+ * path: /crates/engine_input/src/record.rs
+ * description: Recording the per-frame input stream and replaying it deterministically.
+ * A recording keeps, for every frame, the queued `InputEvent`s, the frame's raw delta, time
+ * scale and pause state, and the fixed step it started at. `InputReplay` feeds them back
+ * through `EngineApp::update`, so the same fixed steps see the same input (e.g. a tester's web
+ * session replayed by the headless runner as a regression test).
+ */
+use bevy_ecs::prelude::{
+    Res,
+    ResMut,
+    Resource,
+};
+use engine_core::{
+    EngineApp,
+    FixedTime,
+    Time,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::{
+    InputEvent,
+    InputQueue,
+    Viewport,
+};
+
+/// File extension of input recordings.
+pub const INPUT_RECORDING_EXTENSION: &str = "replay.ron";
+
+/// Format version written by `InputRecording::to_ron_string`.
+pub const INPUT_RECORDING_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum RecordingError {
+    #[error("parse: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("write: {0}")]
+    Write(#[from] ron::Error),
+    #[error("recording version {found} is newer than supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("desync at frame {frame}: recorded at fixed step {expected}, replay is at {actual}")]
+    Desync {
+        frame: u64,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+/// One `EngineApp::update` of a recording.
+/// - `frame`: frames since the recording started
+/// - `step`: fixed steps run since the recording started, before this frame's steps
+/// - `delta`: unscaled seconds of the frame (`Time::raw_delta`)
+/// - `time_scale`, `paused`: the game clock's settings during the frame
+/// - `viewport`: set on the first frame and whenever the canvas size or camera changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub frame: u64,
+    pub step: u64,
+    pub delta: f32,
+    #[serde(
+        default = "default_time_scale",
+        skip_serializing_if = "is_default_time_scale"
+    )]
+    pub time_scale: f32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub paused: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<InputEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<Viewport>,
+}
+
+fn default_time_scale() -> f32 {
+    1.0
+}
+
+fn is_default_time_scale(scale: &f32) -> bool {
+    *scale == 1.0
+}
+
+/// Contents of a `.replay.ron` file.
+/// - `timestep`, `accumulator`, `max_steps_per_frame`: the fixed clock when recording started
+/// - `max_delta`: the game clock's frame limit (`Time::max_delta`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub version: u32,
+    pub timestep: f32,
+    pub accumulator: f32,
+    #[serde(default = "default_max_steps_per_frame")]
+    pub max_steps_per_frame: u32,
+    #[serde(default = "default_max_delta")]
+    pub max_delta: f32,
+    pub frames: Vec<RecordedFrame>,
+}
+
+fn default_max_steps_per_frame() -> u32 {
+    FixedTime::default().max_steps_per_frame
+}
+
+fn default_max_delta() -> f32 {
+    Time::default().max_delta
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        Self {
+            version: INPUT_RECORDING_VERSION,
+            timestep: FixedTime::default().timestep,
+            accumulator: 0.0,
+            max_steps_per_frame: default_max_steps_per_frame(),
+            max_delta: default_max_delta(),
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl InputRecording {
+    pub fn from_ron_str(text: &str) -> Result<InputRecording, RecordingError> {
+        let recording: InputRecording = ron::from_str(text)?;
+        if recording.version > INPUT_RECORDING_VERSION {
+            return Err(RecordingError::UnsupportedVersion {
+                found: recording.version,
+                supported: INPUT_RECORDING_VERSION,
+            });
+        }
+        Ok(recording)
+    }
+
+    /// Pretty RON with one frame per line.
+    pub fn to_ron_string(&self) -> Result<String, RecordingError> {
+        let config = ron::ser::PrettyConfig::new()
+            .depth_limit(2)
+            .extensions(ron::extensions::Extensions::IMPLICIT_SOME);
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    /// Game time covered, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.frames
+            .iter()
+            .filter(|f| !f.paused)
+            .map(|f| (f.delta * f.time_scale.max(0.0)) as f64)
+            .sum()
+    }
+
+    /// First frame at or after fixed step `step`, e.g. to stop a replay where a bug shows up.
+    pub fn frame_at_step(&self, step: u64) -> Option<&RecordedFrame> {
+        self.frames.iter().find(|f| f.step >= step)
+    }
+}
+
+/// Records every frame's input while started. Recording should happen in Play mode, which is
+/// what replays run in.
+#[derive(Resource, Debug, Default)]
+pub struct InputRecorder {
+    recording: Option<InputRecording>,
+    start_step: u64,
+    viewport: Option<Viewport>,
+}
+
+impl InputRecorder {
+    /// Start a new recording with the next frame (dropping one in progress).
+    pub fn start(&mut self) {
+        self.recording = Some(InputRecording::default());
+        self.viewport = None;
+    }
+
+    pub fn stop(&mut self) -> Option<InputRecording> {
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn recording(&self) -> Option<&InputRecording> {
+        self.recording.as_ref()
+    }
+}
+
+/// Append this frame's queued events to the recording (before `update_input` drains them).
+pub fn record_input(
+    mut recorder: ResMut<InputRecorder>,
+    queue: Res<InputQueue>,
+    time: Res<Time>,
+    fixed: Res<FixedTime>,
+    viewport: Res<Viewport>,
+) {
+    let recorder = &mut *recorder;
+    let Some(recording) = recorder.recording.as_mut() else {
+        return;
+    };
+    if recording.frames.is_empty() {
+        recording.timestep = fixed.timestep;
+        recording.accumulator = fixed.accumulator;
+        recording.max_steps_per_frame = fixed.max_steps_per_frame;
+        recording.max_delta = time.max_delta;
+        recorder.start_step = fixed.step_count;
+    }
+    let viewport = (recorder.viewport.as_ref() != Some(&*viewport)).then(|| viewport.clone());
+    if let Some(viewport) = &viewport {
+        recorder.viewport = Some(viewport.clone());
+    }
+    recording.frames.push(RecordedFrame {
+        frame: recording.frames.len() as u64,
+        step: fixed.step_count - recorder.start_step,
+        delta: time.raw_delta,
+        time_scale: time.time_scale,
+        paused: time.paused,
+        events: queue.iter().cloned().collect(),
+        viewport,
+    });
+}
+
+/// Drives an app through a recording, one recorded frame per `step`. Live input queued in
+/// the meantime is discarded. Each frame runs with its recorded time scale and pause state, which
+/// the app keeps afterwards.
+#[derive(Debug, Clone)]
+pub struct InputReplay {
+    recording: InputRecording,
+    next: usize,
+    start_step: u64,
+}
+
+impl InputReplay {
+    /// Set `app`'s clocks to the recording's; the app should be in the state the recording
+    /// started from (same scene, Play mode).
+    pub fn start(recording: InputRecording, app: &mut EngineApp) -> Self {
+        app.world.resource_mut::<Time>().max_delta = recording.max_delta;
+        let mut fixed = app.world.resource_mut::<FixedTime>();
+        fixed.timestep = recording.timestep;
+        fixed.accumulator = recording.accumulator;
+        fixed.max_steps_per_frame = recording.max_steps_per_frame;
+        let start_step = fixed.step_count;
+        app.world.resource_mut::<InputQueue>().clear();
+        Self {
+            recording,
+            next: 0,
+            start_step,
+        }
+    }
+
+    /// Run the next recorded frame; `Ok(false)` once all frames ran. Fails if the app ran a
+    /// different number of fixed steps than during recording.
+    pub fn step(&mut self, app: &mut EngineApp) -> Result<bool, RecordingError> {
+        let Some(frame) = self.recording.frames.get(self.next) else {
+            return Ok(false);
+        };
+        let actual = app.world.resource::<FixedTime>().step_count - self.start_step;
+        if actual != frame.step {
+            return Err(RecordingError::Desync {
+                frame: frame.frame,
+                expected: frame.step,
+                actual,
+            });
+        }
+        let mut time = app.world.resource_mut::<Time>();
+        time.time_scale = frame.time_scale;
+        time.paused = frame.paused;
+        if let Some(viewport) = &frame.viewport {
+            app.world.insert_resource(viewport.clone());
+        }
+        let mut queue = app.world.resource_mut::<InputQueue>();
+        queue.clear();
+        queue.extend(frame.events.iter().cloned());
+        app.update(frame.delta);
+        self.next += 1;
+        Ok(true)
+    }
+
+    /// Replay all remaining frames; returns how many ran.
+    pub fn run(&mut self, app: &mut EngineApp) -> Result<u64, RecordingError> {
+        let mut frames = 0;
+        while self.step(app)? {
+            frames += 1;
+        }
+        Ok(frames)
+    }
+
+    /// Frames replayed so far.
+    pub fn frame(&self) -> usize {
+        self.next
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.frames.len()
+    }
+
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use engine_core::{
+        set_mode,
+        Mode,
+    };
+
+    use super::*;
+    use crate::InputPlugin;
+
+    fn play_app() -> EngineApp {
+        let mut app = EngineApp::new();
+        app.add_plugin(InputPlugin);
+        set_mode(&mut app, Mode::Play);
+        app
+    }
+
+    /// Record `frames` with the clock settings each one sets, then replay the recording (through
+    /// RON) in a fresh app; both must end on the same fixed step and game time.
+    fn record_and_replay(timestep: f32, frames: &[(f32, f32, bool)]) {
+        let mut recorded = play_app();
+        recorded.world.resource_mut::<FixedTime>().timestep = timestep;
+        recorded.world.resource_mut::<InputRecorder>().start();
+        for &(dt, time_scale, paused) in frames {
+            let mut time = recorded.world.resource_mut::<Time>();
+            time.time_scale = time_scale;
+            time.paused = paused;
+            recorded.update(dt);
+        }
+        let recording = recorded
+            .world
+            .resource_mut::<InputRecorder>()
+            .stop()
+            .unwrap();
+        let text = recording.to_ron_string().unwrap();
+        let recording = InputRecording::from_ron_str(&text).unwrap();
+
+        let mut replayed = play_app();
+        let mut replay = InputReplay::start(recording, &mut replayed);
+        assert_eq!(replay.run(&mut replayed).unwrap(), frames.len() as u64);
+        assert_eq!(
+            replayed.world.resource::<FixedTime>().step_count,
+            recorded.world.resource::<FixedTime>().step_count
+        );
+        assert_eq!(
+            replayed.world.resource::<Time>().elapsed,
+            recorded.world.resource::<Time>().elapsed
+        );
+    }
+
+    #[test]
+    fn replay_with_time_scale() {
+        record_and_replay(0.1, &[(0.2, 2.0, false); 5]);
+    }
+
+    #[test]
+    fn replay_with_pause_and_slow_motion() {
+        let frames = [
+            (1.0 / 60.0, 1.0, false),
+            (1.0 / 30.0, 0.5, false),
+            (0.1, 1.0, true),
+            (0.5, 3.0, false),
+            (1.0 / 60.0, 1.0, false),
+        ];
+        record_and_replay(1.0 / 60.0, &frames);
+    }
+
+    #[test]
+    fn replay_detects_desync() {
+        let mut recorded = play_app();
+        recorded.world.resource_mut::<InputRecorder>().start();
+        for _ in 0..3 {
+            recorded.update(0.1);
+        }
+        let recording = recorded
+            .world
+            .resource_mut::<InputRecorder>()
+            .stop()
+            .unwrap();
+
+        let mut replayed = play_app();
+        let mut replay = InputReplay::start(recording, &mut replayed);
+        replay.step(&mut replayed).unwrap();
+        replayed.world.resource_mut::<FixedTime>().step();
+        assert!(matches!(
+            replay.step(&mut replayed),
+            Err(RecordingError::Desync { frame: 1, .. })
+        ));
+    }
+}
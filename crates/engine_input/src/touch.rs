@@ -0,0 +1,106 @@
+/**
+ * path: /crates/engine_input/src/touch.rs
+ * description: Active touch points (multi-touch).
+ */
+use bevy_ecs::prelude::Resource;
+use glam::Vec2;
+use std::collections::BTreeMap;
+
+/// One finger on the canvas; positions in canvas pixels like `Cursor::position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    pub id: u64,
+    pub start_position: Vec2,
+    pub previous_position: Vec2,
+    pub position: Vec2,
+}
+
+impl Touch {
+    /// Movement since the previous frame.
+    pub fn delta(&self) -> Vec2 {
+        self.position - self.previous_position
+    }
+
+    /// Movement since the touch started.
+    pub fn distance(&self) -> Vec2 {
+        self.position - self.start_position
+    }
+}
+
+/// Touches currently down, by id, plus the ones that started or ended this frame.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Touches {
+    active: BTreeMap<u64, Touch>,
+    just_started: Vec<u64>,
+    just_ended: Vec<Touch>,
+}
+
+impl Touches {
+    pub fn start(&mut self, id: u64, position: Vec2) {
+        self.active.insert(
+            id,
+            Touch {
+                id,
+                start_position: position,
+                previous_position: position,
+                position,
+            },
+        );
+        self.just_started.push(id);
+    }
+
+    pub fn moved(&mut self, id: u64, position: Vec2) {
+        if let Some(touch) = self.active.get_mut(&id) {
+            touch.position = position;
+        }
+    }
+
+    /// End (lift or cancel) a touch; it stays readable through `just_ended` for this frame.
+    pub fn end(&mut self, id: u64, position: Vec2) {
+        if let Some(mut touch) = self.active.remove(&id) {
+            touch.position = position;
+            self.just_ended.push(touch);
+        }
+    }
+
+    pub fn end_all(&mut self) {
+        let active = std::mem::take(&mut self.active);
+        self.just_ended.extend(active.into_values());
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.active.get(&id)
+    }
+
+    /// Active touches, oldest id first.
+    pub fn iter(&self) -> impl Iterator<Item = &Touch> {
+        self.active.values()
+    }
+
+    pub fn just_started(&self) -> impl Iterator<Item = &Touch> {
+        self.just_started
+            .iter()
+            .filter_map(|id| self.active.get(id))
+    }
+
+    pub fn just_ended(&self) -> impl Iterator<Item = &Touch> {
+        self.just_ended.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Start a new frame: current positions become the previous ones.
+    pub fn clear(&mut self) {
+        self.just_started.clear();
+        self.just_ended.clear();
+        for touch in self.active.values_mut() {
+            touch.previous_position = touch.position;
+        }
+    }
+}
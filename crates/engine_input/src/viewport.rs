@@ -0,0 +1,76 @@
+/**
+ * path: /crates/engine_input/src/viewport.rs
+ * description: Canvas size and camera projection, for mapping canvas pixels to the world.
+ */
+use bevy_ecs::prelude::Resource;
+use glam::{
+    Mat4,
+    Vec2,
+    Vec3,
+    Vec4,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// The rendered view. Whoever owns the camera (the web engine, a game) keeps it current.
+/// - `size`: canvas size in pixels (backing store)
+/// - `view_proj`: world → clip transform of the camera, with wgpu's 0..1 depth range
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub size: Vec2,
+    pub view_proj: Mat4,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            size: Vec2::ONE,
+            view_proj: Mat4::IDENTITY,
+        }
+    }
+}
+
+impl Viewport {
+    pub fn new(size: Vec2, view_proj: Mat4) -> Self {
+        Self { size, view_proj }
+    }
+
+    /// Canvas pixels → normalized device coordinates (-1..1, y up).
+    pub fn to_ndc(&self, position: Vec2) -> Vec2 {
+        let size = self.size.max(Vec2::ONE);
+        Vec2::new(
+            position.x / size.x * 2.0 - 1.0,
+            1.0 - position.y / size.y * 2.0,
+        )
+    }
+
+    /// World-space ray through a canvas pixel: origin on the near plane and a unit direction.
+    /// `None` if the projection can't be inverted.
+    pub fn ray(&self, position: Vec2) -> Option<(Vec3, Vec3)> {
+        if self.view_proj.determinant().abs() <= f32::EPSILON {
+            return None;
+        }
+        let inverse = self.view_proj.inverse();
+        let ndc = self.to_ndc(position);
+        let unproject = |z: f32| {
+            let p: Vec4 = inverse * Vec4::new(ndc.x, ndc.y, z, 1.0);
+            (p.w.abs() > f32::EPSILON).then(|| p.truncate() / p.w)
+        };
+        let near = unproject(0.0)?;
+        let far = unproject(1.0)?;
+        let direction = (far - near).try_normalize()?;
+        Some((near, direction))
+    }
+
+    /// Point on the z = 0 plane (the 2D world) under a canvas pixel.
+    pub fn to_world_2d(&self, position: Vec2) -> Option<Vec2> {
+        let (origin, direction) = self.ray(position)?;
+        if direction.z.abs() <= f32::EPSILON {
+            return None;
+        }
+        let t = -origin.z / direction.z;
+        Some((origin + direction * t).truncate())
+    }
+}
@@ -0,0 +1,95 @@
+/**
+ * path: /crates/engine_input/src/winit.rs
+ * description: Native backend: translates winit window events into `InputEvent`s.
+ * Call `push_window_event` from the event loop's `window_event` handler with the app's
+ * `InputQueue`. Gamepads aren't reported by winit; native games push gamepad events themselves.
+ */
+use glam::Vec2;
+use winit::event::{
+    ElementState,
+    MouseScrollDelta,
+    TouchPhase,
+    WindowEvent,
+};
+use winit::keyboard::PhysicalKey;
+
+use crate::{
+    InputEvent,
+    InputQueue,
+    KeyCode,
+    MouseButton,
+    PIXELS_PER_LINE,
+};
+
+/// Queue the input contained in a window event; other events are ignored.
+pub fn push_window_event(queue: &mut InputQueue, event: &WindowEvent) {
+    match event {
+        WindowEvent::KeyboardInput { event, .. } if !event.repeat => {
+            let Some(key) = key_code(event.physical_key) else {
+                return;
+            };
+            queue.push(match event.state {
+                ElementState::Pressed => InputEvent::KeyDown(key),
+                ElementState::Released => InputEvent::KeyUp(key),
+            });
+        }
+        WindowEvent::MouseInput { state, button, .. } => {
+            let button = mouse_button(*button);
+            queue.push(match state {
+                ElementState::Pressed => InputEvent::MouseDown(button),
+                ElementState::Released => InputEvent::MouseUp(button),
+            });
+        }
+        WindowEvent::CursorMoved { position, .. } => {
+            queue.push(InputEvent::CursorMoved(Vec2::new(
+                position.x as f32,
+                position.y as f32,
+            )));
+        }
+        WindowEvent::CursorLeft { .. } => queue.push(InputEvent::CursorLeft),
+        WindowEvent::MouseWheel { delta, .. } => {
+            // winit's positive values move the content right/down, i.e. scroll left/up
+            let lines = match delta {
+                MouseScrollDelta::LineDelta(x, y) => Vec2::new(*x, *y),
+                MouseScrollDelta::PixelDelta(p) => {
+                    Vec2::new(p.x as f32, p.y as f32) / PIXELS_PER_LINE
+                }
+            };
+            queue.push(InputEvent::Wheel(-lines));
+        }
+        WindowEvent::Touch(touch) => {
+            let id = touch.id;
+            let position = Vec2::new(touch.location.x as f32, touch.location.y as f32);
+            queue.push(match touch.phase {
+                TouchPhase::Started => InputEvent::TouchStart { id, position },
+                TouchPhase::Moved => InputEvent::TouchMove { id, position },
+                TouchPhase::Ended | TouchPhase::Cancelled => InputEvent::TouchEnd { id, position },
+            });
+        }
+        WindowEvent::Focused(false) => queue.push(InputEvent::FocusLost),
+        _ => {}
+    }
+}
+
+/// winit key codes are named after the same W3C codes as ours, except for the Super keys.
+fn key_code(key: PhysicalKey) -> Option<KeyCode> {
+    let PhysicalKey::Code(code) = key else {
+        return None;
+    };
+    match code {
+        winit::keyboard::KeyCode::SuperLeft => Some(KeyCode::MetaLeft),
+        winit::keyboard::KeyCode::SuperRight => Some(KeyCode::MetaRight),
+        code => KeyCode::from_code(&format!("{code:?}")),
+    }
+}
+
+fn mouse_button(button: winit::event::MouseButton) -> MouseButton {
+    match button {
+        winit::event::MouseButton::Left => MouseButton::Left,
+        winit::event::MouseButton::Right => MouseButton::Right,
+        winit::event::MouseButton::Middle => MouseButton::Middle,
+        winit::event::MouseButton::Back => MouseButton::Back,
+        winit::event::MouseButton::Forward => MouseButton::Forward,
+        winit::event::MouseButton::Other(n) => MouseButton::Other(n),
+    }
+}
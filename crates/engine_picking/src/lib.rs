@@ -0,0 +1,396 @@
+/**
+ * path: /crates/engine_picking/src/lib.rs
+ * description: Pointer picking of 2D sprites and 3D meshes.
+ * The pointer (mouse cursor, or the first touch) is cast through the cameras in `Viewport` and
+ * `PickCamera3D` once per frame; `PickingState` holds the entity under it and this frame's
+ * hover/press/click/drag events.
+ */
+use bevy_ecs::entity::Entity;
+use bevy_ecs::prelude::{
+    Component,
+    IntoSystemConfigs,
+    Query,
+    Res,
+    ResMut,
+    Resource,
+    With,
+    Without,
+    World,
+};
+use bevy_ecs::schedule::SystemSet;
+use engine_core::{
+    EngineApp,
+    Plugin,
+    PreUpdate,
+};
+use engine_input::{
+    ButtonInput,
+    Cursor,
+    InputSystems,
+    MouseButton,
+    Touches,
+    Viewport,
+};
+use engine_scene::{
+    GlobalTransform2D,
+    GlobalTransform3D,
+    Mesh,
+    Sprite,
+    Transform2D,
+};
+use glam::{
+    Affine2,
+    Mat4,
+    Vec2,
+    Vec3,
+};
+
+mod mesh;
+mod ray;
+pub use mesh::PickMesh;
+pub use ray::{
+    ray_aabb,
+    ray_triangle,
+    sprite_contains,
+    Ray3d,
+};
+
+/// Pointer movement (canvas pixels) before a press turns into a drag instead of a click.
+pub const DRAG_THRESHOLD: f32 = 4.0;
+
+/// `PickingState`, `PickCamera3D` and `update_picking` in `PreUpdate` after `InputSystems`.
+pub struct PickingPlugin;
+
+/// `update_picking`; systems reading `PickingState` in `PreUpdate` should run after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PickingSystems;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.init_resource::<PickingState>()
+            .init_resource::<PickCamera3D>()
+            .add_systems(
+                PreUpdate,
+                update_picking.in_set(PickingSystems).after(InputSystems),
+            );
+    }
+}
+
+/// Camera that meshes are drawn with (world → clip, wgpu's 0..1 depth range). Sprites use the
+/// `Viewport` camera; both share its canvas size.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PickCamera3D {
+    pub view_proj: Option<Mat4>,
+}
+
+/// Excludes an entity from picking (e.g. backgrounds, editor gizmos while dragging).
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct NotPickable;
+
+/// Entity under a screen position.
+/// - `position`: world-space hit point (z = 0 for sprites)
+/// - `distance`: along the camera ray for meshes, 0 for sprites
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickHit {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub distance: f32,
+}
+
+/// Pointer interaction with entities this frame. A press that moves less than
+/// `DRAG_THRESHOLD` and is released over the same entity is a `Click`; otherwise it's a drag.
+/// - `delta`: pointer movement in canvas pixels; `world_delta`: on the 2D world plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickEvent {
+    HoverStart {
+        entity: Entity,
+    },
+    HoverEnd {
+        entity: Entity,
+    },
+    Press {
+        entity: Entity,
+        button: MouseButton,
+    },
+    /// Sent to the pressed entity, wherever the pointer is now.
+    Release {
+        entity: Entity,
+        button: MouseButton,
+    },
+    Click {
+        entity: Entity,
+        button: MouseButton,
+    },
+    DragStart {
+        entity: Entity,
+        button: MouseButton,
+    },
+    Drag {
+        entity: Entity,
+        button: MouseButton,
+        delta: Vec2,
+        world_delta: Vec2,
+    },
+    DragEnd {
+        entity: Entity,
+        button: MouseButton,
+    },
+}
+
+impl PickEvent {
+    pub fn entity(&self) -> Entity {
+        match *self {
+            PickEvent::HoverStart { entity }
+            | PickEvent::HoverEnd { entity }
+            | PickEvent::Press { entity, .. }
+            | PickEvent::Release { entity, .. }
+            | PickEvent::Click { entity, .. }
+            | PickEvent::DragStart { entity, .. }
+            | PickEvent::Drag { entity, .. }
+            | PickEvent::DragEnd { entity, .. } => entity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    entity: Entity,
+    button: MouseButton,
+    start: Vec2,
+    last: Vec2,
+    dragging: bool,
+}
+
+/// Hovered entity, the press in progress and this frame's events. The events aren't a bevy
+/// `Events` queue: they are replaced every `PreUpdate`, so read them in the same frame, after
+/// `PickingSystems` (in `PreUpdate`) or in `Update`/`PostUpdate`. `FixedUpdate` may run zero or
+/// several times a frame and then misses or repeats events.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PickingState {
+    hovered: Option<PickHit>,
+    press: Option<Press>,
+    events: Vec<PickEvent>,
+}
+
+impl PickingState {
+    pub fn hovered(&self) -> Option<&PickHit> {
+        self.hovered.as_ref()
+    }
+
+    pub fn hovered_entity(&self) -> Option<Entity> {
+        self.hovered.map(|hit| hit.entity)
+    }
+
+    /// Entity being pressed or dragged, with the button.
+    pub fn pressed(&self) -> Option<(Entity, MouseButton)> {
+        self.press.map(|press| (press.entity, press.button))
+    }
+
+    pub fn dragging(&self) -> Option<Entity> {
+        self.press
+            .filter(|press| press.dragging)
+            .map(|press| press.entity)
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &PickEvent> {
+        self.events.iter()
+    }
+
+    /// Events of one entity this frame.
+    pub fn events_for(&self, entity: Entity) -> impl Iterator<Item = &PickEvent> {
+        self.events.iter().filter(move |e| e.entity() == entity)
+    }
+
+    pub fn clicked(&self, entity: Entity) -> bool {
+        self.events_for(entity)
+            .any(|e| matches!(e, PickEvent::Click { .. }))
+    }
+}
+
+type SpriteItem<'a> = (
+    Entity,
+    &'a Sprite,
+    Option<&'a GlobalTransform2D>,
+    Option<&'a Transform2D>,
+);
+type MeshItem<'a> = (Entity, &'a PickMesh, Option<&'a GlobalTransform3D>);
+
+/// Cast the pointer, track the press and write this frame's `PickEvent`s.
+#[allow(clippy::too_many_arguments)]
+pub fn update_picking(
+    cursor: Res<Cursor>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    viewport: Res<Viewport>,
+    camera: Res<PickCamera3D>,
+    sprites: Query<SpriteItem, Without<NotPickable>>,
+    meshes: Query<MeshItem, (With<Mesh>, Without<NotPickable>)>,
+    mut state: ResMut<PickingState>,
+) {
+    let state = &mut *state;
+    state.events.clear();
+
+    // The first touch stands in for the left button on touch screens
+    let position = cursor.position.or_else(|| {
+        touches
+            .iter()
+            .chain(touches.just_ended())
+            .next()
+            .map(|t| t.position)
+    });
+    let down = |button: MouseButton| {
+        mouse.pressed(button) || (button == MouseButton::Left && !touches.is_empty())
+    };
+    let mut just_pressed: Vec<MouseButton> = mouse.get_just_pressed().copied().collect();
+    if touches.just_started().count() == touches.len() && !touches.is_empty() {
+        just_pressed.push(MouseButton::Left);
+    }
+
+    let hit = position.and_then(|p| {
+        cast(
+            p,
+            &viewport,
+            &camera,
+            sprites.iter().map(sprite_shape),
+            meshes.iter().map(mesh_shape),
+        )
+    });
+    let previous = state.hovered_entity();
+    let current = hit.map(|hit| hit.entity);
+    if previous != current {
+        if let Some(entity) = previous {
+            state.events.push(PickEvent::HoverEnd { entity });
+        }
+        if let Some(entity) = current {
+            state.events.push(PickEvent::HoverStart { entity });
+        }
+    }
+    state.hovered = hit;
+
+    if let (None, Some(entity), Some(position), Some(&button)) =
+        (state.press, current, position, just_pressed.first())
+    {
+        state.events.push(PickEvent::Press { entity, button });
+        state.press = Some(Press {
+            entity,
+            button,
+            start: position,
+            last: position,
+            dragging: false,
+        });
+    }
+
+    let Some(mut press) = state.press else {
+        return;
+    };
+    if let Some(position) = position {
+        if !press.dragging && position.distance(press.start) >= DRAG_THRESHOLD {
+            press.dragging = true;
+            state.events.push(PickEvent::DragStart {
+                entity: press.entity,
+                button: press.button,
+            });
+        }
+        if press.dragging && position != press.last {
+            let world = |p: Vec2| viewport.to_world_2d(p).unwrap_or(Vec2::ZERO);
+            state.events.push(PickEvent::Drag {
+                entity: press.entity,
+                button: press.button,
+                delta: position - press.last,
+                world_delta: world(position) - world(press.last),
+            });
+        }
+        press.last = position;
+    }
+    state.press = Some(press);
+
+    if !down(press.button) {
+        let (entity, button) = (press.entity, press.button);
+        state.events.push(PickEvent::Release { entity, button });
+        if press.dragging {
+            state.events.push(PickEvent::DragEnd { entity, button });
+        } else if current == Some(entity) {
+            state.events.push(PickEvent::Click { entity, button });
+        }
+        state.press = None;
+    }
+}
+
+/// Topmost pickable entity under a canvas position, using the world's `Viewport` and
+/// `PickCamera3D`; for one-off queries such as editor selection.
+pub fn pick_at(world: &mut World, position: Vec2) -> Option<PickHit> {
+    let mut sprites = world.query_filtered::<SpriteItem, Without<NotPickable>>();
+    let mut meshes = world.query_filtered::<MeshItem, (With<Mesh>, Without<NotPickable>)>();
+    let world = &*world;
+    let viewport = world
+        .get_resource::<Viewport>()
+        .cloned()
+        .unwrap_or_default();
+    let camera = world
+        .get_resource::<PickCamera3D>()
+        .cloned()
+        .unwrap_or_default();
+    cast(
+        position,
+        &viewport,
+        &camera,
+        sprites.iter(world).map(sprite_shape),
+        meshes.iter(world).map(mesh_shape),
+    )
+}
+
+fn sprite_shape((entity, sprite, global, local): SpriteItem) -> (Entity, Affine2, Vec2) {
+    // Without the hierarchy plugin there is no global transform; use the local one
+    let transform = global
+        .map(|g| g.0)
+        .or_else(|| local.map(Transform2D::to_affine))
+        .unwrap_or(Affine2::IDENTITY);
+    let dimensions = Vec2::new(sprite.dimensions.0, sprite.dimensions.1);
+    (entity, transform, dimensions)
+}
+
+fn mesh_shape<'a>((entity, mesh, global): MeshItem<'a>) -> (Entity, Mat4, &'a PickMesh) {
+    (entity, global.map_or(Mat4::IDENTITY, |g| g.0), mesh)
+}
+
+/// Meshes are drawn over sprites, so the nearest mesh wins; among sprites the one drawn last
+/// (the last in query order, as the sprite renderer iterates them) is on top.
+fn cast<'a>(
+    position: Vec2,
+    viewport: &Viewport,
+    camera: &PickCamera3D,
+    sprites: impl Iterator<Item = (Entity, Affine2, Vec2)>,
+    meshes: impl Iterator<Item = (Entity, Mat4, &'a PickMesh)>,
+) -> Option<PickHit> {
+    let mesh_viewport = Viewport::new(
+        viewport.size,
+        camera.view_proj.unwrap_or(viewport.view_proj),
+    );
+    if let Some(ray) = Ray3d::from_viewport(&mesh_viewport, position) {
+        let nearest = meshes
+            .filter(|(_, matrix, _)| matrix.determinant().abs() > f32::EPSILON)
+            .filter_map(|(entity, matrix, mesh)| {
+                let local = ray.transform(&matrix.inverse());
+                let t = mesh.intersect(&local)?;
+                Some(PickHit {
+                    entity,
+                    position: ray.at(t),
+                    distance: t,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance));
+        if nearest.is_some() {
+            return nearest;
+        }
+    }
+
+    let point = viewport.to_world_2d(position)?;
+    sprites
+        .filter(|(_, transform, dimensions)| sprite_contains(transform, *dimensions, point))
+        .last()
+        .map(|(entity, _, _)| PickHit {
+            entity,
+            position: point.extend(0.0),
+            distance: 0.0,
+        })
+}
@@ -0,0 +1,76 @@
+/**
+ * path: /crates/engine_picking/src/mesh.rs
+ * description: Pickable shape of a 3D mesh entity.
+ * Geometry lives with the renderer, not in the ECS; whoever loads a mesh attaches a `PickMesh`
+ * so the entity can be picked.
+ */
+use bevy_ecs::prelude::Component;
+use engine_types::MeshData;
+use glam::Vec3;
+
+use crate::ray::{
+    ray_aabb,
+    ray_triangle,
+    Ray3d,
+};
+
+/// Local-space bounds and, optionally, triangles of a mesh. Without triangles the bounds are
+/// the pickable shape (cheap, but loose for non-boxy meshes).
+#[derive(Component, Debug, Clone, Default)]
+pub struct PickMesh {
+    pub min: Vec3,
+    pub max: Vec3,
+    pub triangles: Vec<[Vec3; 3]>,
+}
+
+impl PickMesh {
+    pub fn from_bounds(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+            triangles: Vec::new(),
+        }
+    }
+
+    /// Bounds and triangles of indexed mesh data; indices out of range are skipped.
+    pub fn from_mesh_data(mesh: &MeshData) -> Self {
+        let positions: Vec<Vec3> = mesh
+            .vertices
+            .iter()
+            .map(|v| Vec3::from_array(v.position))
+            .collect();
+        let (min, max) = positions
+            .iter()
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), p| {
+                (min.min(*p), max.max(*p))
+            });
+        let triangles = mesh
+            .indices
+            .chunks_exact(3)
+            .filter_map(|tri| {
+                let vertex = |i: u32| positions.get(i as usize).copied();
+                Some([vertex(tri[0])?, vertex(tri[1])?, vertex(tri[2])?])
+            })
+            .collect();
+        if positions.is_empty() {
+            return Self::default();
+        }
+        Self {
+            min,
+            max,
+            triangles,
+        }
+    }
+
+    /// Nearest hit of a local-space ray: the bounds first, then the triangles if there are any.
+    pub fn intersect(&self, ray: &Ray3d) -> Option<f32> {
+        let bounds = ray_aabb(ray, self.min, self.max)?;
+        if self.triangles.is_empty() {
+            return Some(bounds);
+        }
+        self.triangles
+            .iter()
+            .filter_map(|tri| ray_triangle(ray, *tri))
+            .min_by(f32::total_cmp)
+    }
+}
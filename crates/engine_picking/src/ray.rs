@@ -0,0 +1,90 @@
+/**
+ * path: /crates/engine_picking/src/ray.rs
+ * description: Rays through the screen and the intersection tests picking is built on.
+ */
+use engine_input::Viewport;
+use glam::{
+    Affine2,
+    Mat4,
+    Vec2,
+    Vec3,
+};
+
+/// A ray `origin + direction * t`. `direction` is unit length for rays from the screen; after
+/// `transform` it isn't, which keeps `t` comparable between spaces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray3d {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Ray3d {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Ray through a canvas pixel, starting on the camera's near plane.
+    pub fn from_viewport(viewport: &Viewport, position: Vec2) -> Option<Ray3d> {
+        let (origin, direction) = viewport.ray(position)?;
+        Some(Ray3d { origin, direction })
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// The same ray in another space, e.g. a mesh's local space via the inverse of its world
+    /// matrix.
+    pub fn transform(&self, matrix: &Mat4) -> Ray3d {
+        Ray3d {
+            origin: matrix.transform_point3(self.origin),
+            direction: matrix.transform_vector3(self.direction),
+        }
+    }
+}
+
+/// Whether a world point lies on a sprite: a `dimensions`-sized rect centered on the origin of
+/// `transform` (scale and rotation included, as the quad shader draws it).
+pub fn sprite_contains(transform: &Affine2, dimensions: Vec2, point: Vec2) -> bool {
+    if transform.matrix2.determinant().abs() <= f32::EPSILON {
+        return false;
+    }
+    let local = transform.inverse().transform_point2(point);
+    let half = dimensions.abs() * 0.5;
+    local.x.abs() <= half.x && local.y.abs() <= half.y
+}
+
+/// Distance along the ray to an axis-aligned box (0 if the origin is inside it).
+pub fn ray_aabb(ray: &Ray3d, min: Vec3, max: Vec3) -> Option<f32> {
+    let inverse = ray.direction.recip();
+    let t1 = (min - ray.origin) * inverse;
+    let t2 = (max - ray.origin) * inverse;
+    // NaN (a zero direction component with the origin on a slab face) is ignored by min/max
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element();
+    (near <= far).then_some(near)
+}
+
+/// Distance along the ray to a triangle, from either side (Möller–Trumbore).
+pub fn ray_triangle(ray: &Ray3d, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() <= f32::EPSILON * ray.direction.length_squared().max(1.0) {
+        return None;
+    }
+    let inverse = 1.0 / det;
+    let s = ray.origin - a;
+    let u = s.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inverse;
+    (t >= 0.0).then_some(t)
+}
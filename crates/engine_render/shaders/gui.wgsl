@@ -0,0 +1,40 @@
+// gui.wgsl
+// Screen-space UI quads: pixel positions, per-vertex colour multiplied with a texture
+// (a white texel for untextured quads).
+
+struct Camera {
+  view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+@group(1) @binding(0)
+var gui_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var gui_sampler: sampler;
+
+struct VertexInput {
+  @location(0) position: vec2<f32>,
+  @location(1) uv: vec2<f32>,
+  @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+  @builtin(position) position: vec4<f32>,
+  @location(0) uv: vec2<f32>,
+  @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+  var out: VertexOutput;
+  out.position = camera.view_proj * vec4<f32>(in.position, 0.0, 1.0);
+  out.uv = in.uv;
+  out.color = in.color;
+  return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+  return textureSample(gui_texture, gui_sampler, in.uv) * in.color;
+}
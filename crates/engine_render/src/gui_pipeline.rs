@@ -1,60 +1,58 @@
+use std::collections::HashMap;
 
 use wgpu::util::DeviceExt;
 use wgpu::*;
-// use bytemuck::{Pod, Zeroable};
 use glam::Mat4;
 
 use engine_types::{
-    MeshVertex, 
     CameraUniform,
+    GuiBatch,
+    GuiVertex,
 };
 
-
-/// Pipeline for rendering 3D meshes
+/// Pipeline for screen-space UI: indexed, alpha-blended quads in canvas pixels, drawn in
+/// batches per texture. Texture 0 is a white texel, so untextured quads are plain colour.
+/// The camera defaults to canvas pixels; `set_camera` reuses the pipeline for world-space
+/// quads such as text labels.
 pub struct GUIPipeline {
     pub pipeline: RenderPipeline,
     pub vertex_buffer: Buffer,
     pub index_buffer: Buffer,
     pub camera_bind_group: BindGroup,
     pub camera_buffer: Buffer,
-    pub index_count: u32,
+    texture_layout: BindGroupLayout,
+    sampler: Sampler,
+    textures: HashMap<u32, (Texture, BindGroup)>,
+    batches: Vec<GuiBatch>,
 }
 
 impl GUIPipeline {
-    pub fn new(
-        device: &Device,
-        texture_format: TextureFormat,
-        vertices: &[MeshVertex],
-        indices: &[u32],
-    ) -> Self {
-        // Camera setup (perspective projection)
-        let aspect_ratio = 16.0 / 9.0;
-        let perspective = Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
+    pub fn new(device: &Device, queue: &Queue, texture_format: TextureFormat) -> Self {
         let camera_uniform = CameraUniform {
-            view_proj: perspective.to_cols_array(),
+            view_proj: Self::camera_matrix(1, 1).to_cols_array(),
         };
-
         let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Uniform Buffer"),
+            label: Some("GUI Camera Uniform Buffer"),
             contents: bytemuck::cast_slice(&[camera_uniform]),
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Mesh Vertex Buffer"),
-            contents: bytemuck::cast_slice(vertices),
+        let vertex_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("GUI Vertex Buffer"),
+            size: 64 * std::mem::size_of::<GuiVertex>() as u64,
             usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
-
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Mesh Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
+        let index_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("GUI Index Buffer"),
+            size: 96 * std::mem::size_of::<u32>() as u64,
             usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
         // Bind group for camera
         let camera_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("Camera Bind Group Layout"),
+            label: Some("GUI Camera Bind Group Layout"),
             entries: &[BindGroupLayoutEntry {
                 binding: 0,
                 visibility: ShaderStages::VERTEX,
@@ -66,9 +64,8 @@ impl GUIPipeline {
                 count: None,
             }],
         });
-
         let camera_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            label: Some("Camera Bind Group"),
+            label: Some("GUI Camera Bind Group"),
             layout: &camera_bgl,
             entries: &[BindGroupEntry {
                 binding: 0,
@@ -76,46 +73,77 @@ impl GUIPipeline {
             }],
         });
 
+        // Bind group layout for the texture of a batch
+        let texture_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("GUI Texture Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("GUI Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
         // Shader module
-        let shader_src = include_str!("../shaders/mesh.wgsl");
+        let shader_src = include_str!("../shaders/gui.wgsl");
         let shader = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("Mesh Shader"),
+            label: Some("GUI Shader"),
             source: ShaderSource::Wgsl(shader_src.into()),
         });
 
         // Pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-            label: Some("Mesh Pipeline Layout"),
-            bind_group_layouts: &[&camera_bgl],
+            label: Some("GUI Pipeline Layout"),
+            bind_group_layouts: &[&camera_bgl, &texture_layout],
             push_constant_ranges: &[],
         });
 
         // Vertex buffer layout
         let vertex_layout = VertexBufferLayout {
-            array_stride: std::mem::size_of::<MeshVertex>() as BufferAddress,
+            array_stride: std::mem::size_of::<GuiVertex>() as BufferAddress,
             step_mode: VertexStepMode::Vertex,
             attributes: &[
                 VertexAttribute {
                     offset: 0,
                     shader_location: 0,
-                    format: VertexFormat::Float32x3, // position
+                    format: VertexFormat::Float32x2, // position
                 },
                 VertexAttribute {
-                    offset: 12,
+                    offset: 8,
                     shader_location: 1,
-                    format: VertexFormat::Float32x3, // normal
+                    format: VertexFormat::Float32x2, // uv
                 },
                 VertexAttribute {
-                    offset: 24,
+                    offset: 16,
                     shader_location: 2,
-                    format: VertexFormat::Float32x2, // uv
+                    format: VertexFormat::Float32x4, // color
                 },
             ],
         };
 
         // Render pipeline
         let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Mesh Render Pipeline"),
+            label: Some("GUI Render Pipeline"),
             layout: Some(&pipeline_layout),
             vertex: VertexState {
                 module: &shader,
@@ -128,7 +156,7 @@ impl GUIPipeline {
                 entry_point: Some("fs_main"),
                 targets: &[Some(ColorTargetState {
                     format: texture_format,
-                    blend: Some(BlendState::REPLACE),
+                    blend: Some(BlendState::ALPHA_BLENDING),
                     write_mask: ColorWrites::ALL,
                 })],
                 compilation_options: PipelineCompilationOptions::default(),
@@ -143,66 +171,198 @@ impl GUIPipeline {
             cache: None,
         });
 
-        Self {
+        let mut gui = Self {
             pipeline,
             vertex_buffer,
             index_buffer,
             camera_bind_group,
             camera_buffer,
-            index_count: indices.len() as u32,
+            texture_layout,
+            sampler,
+            textures: HashMap::new(),
+            batches: Vec::new(),
+        };
+        gui.set_texture(device, queue, 0, 1, 1, &[255, 255, 255, 255]);
+        gui
+    }
+
+    /// Canvas pixels → clip space (origin top-left, y down).
+    pub fn camera_matrix(width: u32, height: u32) -> Mat4 {
+        Mat4::orthographic_rh(0.0, width.max(1) as f32, height.max(1) as f32, 0.0, -1.0, 1.0)
+    }
+
+    pub fn update_camera(&self, queue: &Queue, width: u32, height: u32) {
+        self.set_camera(queue, Self::camera_matrix(width, height));
+    }
+
+    /// Use another projection, e.g. the 2D world camera.
+    pub fn set_camera(&self, queue: &Queue, view_proj: Mat4) {
+        let camera_uniform = CameraUniform {
+            view_proj: view_proj.to_cols_array(),
+        };
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+    }
+
+    /// Upload (or replace) texture `id` from tightly packed sRGB RGBA8 pixels.
+    pub fn set_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        id: u32,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) {
+        let texture = device.create_texture_with_data(
+            queue,
+            &TextureDescriptor {
+                label: Some("GUI Texture"),
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8UnormSrgb,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            rgba,
+        );
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("GUI Texture Bind Group"),
+            layout: &self.texture_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        self.textures.insert(id, (texture, bind_group));
+    }
+
+    /// Overwrite a rect of texture `id` (e.g. glyphs added to an atlas); `rgba` is tightly
+    /// packed. Does nothing if the texture doesn't exist.
+    pub fn write_texture(
+        &self,
+        queue: &Queue,
+        id: u32,
+        origin: (u32, u32),
+        size: (u32, u32),
+        rgba: &[u8],
+    ) {
+        let Some((texture, _)) = self.textures.get(&id) else {
+            return;
+        };
+        queue.write_texture(
+            TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: origin.0,
+                    y: origin.1,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            rgba,
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(size.0 * 4),
+                rows_per_image: Some(size.1),
+            },
+            Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    pub fn has_texture(&self, id: u32) -> bool {
+        self.textures.contains_key(&id)
+    }
+
+    /// Upload this frame's UI geometry; batches index into `indices`.
+    pub fn update(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        vertices: &[GuiVertex],
+        indices: &[u32],
+        batches: &[GuiBatch],
+    ) {
+        self.batches = batches.to_vec();
+        if vertices.is_empty() || indices.is_empty() {
+            self.batches.clear();
+            return;
         }
+        self.ensure_vertex_capacity(device, vertices);
+        self.ensure_index_capacity(device, indices);
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+        queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(indices));
     }
 
+    /// Draw the batches of the last `update`; a batch whose texture isn't uploaded yet is drawn
+    /// untextured.
     pub fn draw<'a>(&'a self, rpass: &mut RenderPass<'a>) {
+        if self.batches.is_empty() {
+            return;
+        }
         rpass.set_pipeline(&self.pipeline);
         rpass.set_bind_group(0, &self.camera_bind_group, &[]);
         rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
         rpass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint32);
-        rpass.draw_indexed(0..self.index_count, 0, 0..1);
-    }
-    
-    pub fn update_camera(&self, queue: &wgpu::Queue, width: u32, height: u32) {
-        let aspect_ratio = if width > 0 && height > 0 {
-            width as f32 / height as f32
-        } else {
-            16.0 / 9.0
-        };
-        let perspective = glam::Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
-        let camera_uniform = CameraUniform {
-            view_proj: perspective.to_cols_array(),
-        };
-        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+        for batch in &self.batches {
+            let (_, texture) = self
+                .textures
+                .get(&batch.texture)
+                .or_else(|| self.textures.get(&0))
+                .expect("white texture");
+            rpass.set_bind_group(1, texture, &[]);
+            let end = batch.first_index + batch.index_count;
+            rpass.draw_indexed(batch.first_index..end, 0, 0..1);
+        }
     }
 
-    pub fn ensure_vertex_capacity(&mut self, device: &wgpu::Device, vertices: &[MeshVertex]) {
-        let needed = (vertices.len() * std::mem::size_of::<MeshVertex>()) as u64;
+    fn ensure_vertex_capacity(&mut self, device: &Device, vertices: &[GuiVertex]) {
+        let needed = std::mem::size_of_val(vertices) as u64;
         let current = self.vertex_buffer.size();
         if needed > current {
             let mut new_size = current.max(64) * 2;
             while new_size < needed {
                 new_size *= 2;
             }
-            self.vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Mesh Vertex Buffer (resized)"),
+            self.vertex_buffer = device.create_buffer(&BufferDescriptor {
+                label: Some("GUI Vertex Buffer (resized)"),
                 size: new_size,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             });
         }
     }
 
-    pub fn ensure_index_capacity(&mut self, device: &wgpu::Device, indices: &[u32]) {
-        let needed = (indices.len() * std::mem::size_of::<u32>()) as u64;
+    fn ensure_index_capacity(&mut self, device: &Device, indices: &[u32]) {
+        let needed = std::mem::size_of_val(indices) as u64;
         let current = self.index_buffer.size();
         if needed > current {
             let mut new_size = current.max(64) * 2;
             while new_size < needed {
                 new_size *= 2;
             }
-            self.index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Mesh Index Buffer (resized)"),
+            self.index_buffer = device.create_buffer(&BufferDescriptor {
+                label: Some("GUI Index Buffer (resized)"),
                 size: new_size,
-                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+                usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             });
         }
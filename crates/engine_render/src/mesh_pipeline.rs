@@ -28,8 +28,7 @@ impl MeshPipeline {
         indices: &[u32],
     ) -> Self {
         // Camera setup (perspective projection)
-        let aspect_ratio = 16.0 / 9.0;
-        let perspective = Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
+        let perspective = Self::camera_matrix(0, 0);
         let camera_uniform = CameraUniform {
             view_proj: perspective.to_cols_array(),
         };
@@ -161,13 +160,18 @@ impl MeshPipeline {
         rpass.draw_indexed(0..self.index_count, 0, 0..1);
     }
     
-    pub fn update_camera(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+    /// World → clip transform for meshes (also used to pick them); 16:9 for a zero size.
+    pub fn camera_matrix(width: u32, height: u32) -> Mat4 {
         let aspect_ratio = if width > 0 && height > 0 {
             width as f32 / height as f32
         } else {
             16.0 / 9.0
         };
-        let perspective = glam::Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
+        Mat4::perspective_rh(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0)
+    }
+
+    pub fn update_camera(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+        let perspective = Self::camera_matrix(width, height);
         let camera_uniform = CameraUniform {
             view_proj: perspective.to_cols_array(),
         };
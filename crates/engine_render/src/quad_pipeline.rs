@@ -152,6 +152,11 @@ impl QuadPipeline {
         }
     }
 
+    /// World → clip transform for sprites (also used to map the cursor into the world).
+    pub fn camera_matrix() -> Mat4 {
+        Mat4::orthographic_lh(0.0, 10.0, -10.0, 10.0, -1.0, 1.0)
+    }
+
     /// Update camera matrix (e.g., on window resize).
     pub fn update_camera(&self, queue: &Queue, width: u32, height: u32) {
         let mut new_width: f32 = width as f32;
@@ -173,7 +178,7 @@ impl QuadPipeline {
                             );
 
         // let ortho = Mat4::orthographic_lh(0.0, width as f32, 0.0, height as f32, -1.0, 1.0);
-        let ortho = Mat4::orthographic_lh(0.0, 10.0, -10.0, 10.0, -1.0, 1.0);
+        let ortho = Self::camera_matrix();
         let camera_uniform = CameraUniform {
             view_proj: ortho.to_cols_array(),
         };
@@ -0,0 +1,253 @@
+/**
+ * path: /crates/engine_runner/src/lib.rs
+ * description: Headless native runner for engine_core games.
+ * Loads RON scenes from the filesystem, spawns them into an `EngineApp` and steps it with a
+ * fixed timestep, so gameplay logic can run in CI, on servers or in tooling without a browser.
+ */
+use bevy_ecs::prelude::World;
+use engine_core::{
+    set_mode,
+    EngineApp,
+    FixedTime,
+    Mode,
+    Plugin,
+    DEFAULT_FIXED_HZ,
+};
+use engine_input::{
+    InputPlugin,
+    InputRecording,
+    InputReplay,
+    RecordingError,
+};
+use engine_scene::{
+    apply_scene_patch,
+    spawn_scene,
+    ComponentRegistry,
+    Diagnostics,
+    HierarchyPlugin,
+    PrefabLibrary,
+    Scene,
+    SceneError,
+    SceneFormat,
+    SceneInstance,
+    SceneLoader,
+    ScenePatch,
+    ScenePlugin,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+use thiserror::Error;
+
+#[cfg(feature = "offscreen")]
+pub mod offscreen;
+
+#[derive(Error, Debug)]
+pub enum RunnerError {
+    #[error("io: {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("scene {path}: {source}")]
+    Scene { path: PathBuf, source: SceneError },
+    #[error("condition not met after {frames} frames")]
+    Timeout { frames: u64 },
+    #[error("replay: {0}")]
+    Replay(#[from] RecordingError),
+    #[error("render: {0}")]
+    Render(String),
+}
+
+/// Drives an `EngineApp` in Play mode with exactly one fixed step per frame.
+pub struct HeadlessRunner {
+    pub app: EngineApp,
+    timestep: f32,
+    frame: u64,
+    scene: Option<SceneInstance>,
+    prefabs: PrefabLibrary,
+    warnings: Vec<Diagnostics>,
+}
+
+impl Default for HeadlessRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessRunner {
+    pub fn new() -> Self {
+        Self::with_fixed_hz(DEFAULT_FIXED_HZ)
+    }
+
+    pub fn with_fixed_hz(hz: f32) -> Self {
+        Self::with_app(EngineApp::default(), hz)
+    }
+
+    /// Wrap an already configured app (plugins, resources) and switch it to Play mode.
+    pub fn with_app(mut app: EngineApp, hz: f32) -> Self {
+        let fixed = FixedTime::from_hz(hz);
+        let timestep = fixed.timestep;
+        app.insert_resource(fixed);
+        app.add_plugin(HierarchyPlugin);
+        app.add_plugin(ScenePlugin);
+        set_mode(&mut app, Mode::Play);
+        Self {
+            app,
+            timestep,
+            frame: 0,
+            scene: None,
+            prefabs: PrefabLibrary::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn add_plugin(&mut self, plugin: impl Plugin) -> &mut Self {
+        self.app.add_plugin(plugin);
+        self
+    }
+
+    /// Read, validate and spawn a `.scene.ron` or `.scene.bin` file. Prefab and mesh paths are
+    /// resolved relative to the working directory; validation warnings are kept in `warnings()`.
+    pub fn load_scene_file(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<&SceneInstance, RunnerError> {
+        let path = path.as_ref();
+        let scene = self.read_scene(path)?;
+        while let Some(prefab_path) = self.prefabs.missing(&scene).pop() {
+            let prefab = self.read_scene(Path::new(&prefab_path))?;
+            self.prefabs.insert(prefab_path, prefab);
+        }
+        let resolved = self
+            .prefabs
+            .resolve(&scene, self.app.world.resource::<ComponentRegistry>())
+            .map_err(|source| RunnerError::Scene {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        self.load_scene(&resolved)
+            .map_err(|source| RunnerError::Scene {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+
+    /// Spawn a scene (with prefabs resolved) into the world. A previously loaded scene stays in the world.
+    pub fn load_scene(&mut self, scene: &Scene) -> Result<&SceneInstance, SceneError> {
+        let instance = spawn_scene(&mut self.app.world, scene)?;
+        Ok(self.scene.insert(instance))
+    }
+
+    /// Apply a scene patch (`Scene::diff`) to the most recently loaded scene, keeping runtime
+    /// state outside the changed fields. Without a loaded scene the patch is ignored.
+    pub fn apply_patch(&mut self, patch: &ScenePatch) -> Result<(), SceneError> {
+        match self.scene.as_mut() {
+            Some(instance) => apply_scene_patch(&mut self.app.world, instance, patch),
+            None => Ok(()),
+        }
+    }
+
+    /// Entities of the most recently loaded scene.
+    pub fn scene(&self) -> Option<&SceneInstance> {
+        self.scene.as_ref()
+    }
+
+    /// Run one frame (one fixed step).
+    pub fn step(&mut self) {
+        self.app.update(self.timestep);
+        self.frame += 1;
+    }
+
+    pub fn run_frames(&mut self, frames: u64) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Step until `done` returns true (checked after each frame) or `max_frames` have run.
+    /// Returns the number of frames run by this call.
+    pub fn run_until(
+        &mut self,
+        max_frames: u64,
+        mut done: impl FnMut(&mut World) -> bool,
+    ) -> Result<u64, RunnerError> {
+        for frames in 1..=max_frames {
+            self.step();
+            if done(&mut self.app.world) {
+                return Ok(frames);
+            }
+        }
+        Err(RunnerError::Timeout { frames: max_frames })
+    }
+
+    /// Replay an input recording (`InputRecorder`) frame by frame with its recorded deltas and
+    /// fixed timestep; the runner should be in the state the recording started from. Returns
+    /// the number of frames run. Adds `InputPlugin` if needed.
+    pub fn replay(&mut self, recording: InputRecording) -> Result<u64, RunnerError> {
+        self.app.add_plugin(InputPlugin);
+        self.timestep = recording.timestep;
+        let mut replay = InputReplay::start(recording, &mut self.app);
+        let result = replay.run(&mut self.app);
+        self.frame += replay.frame() as u64;
+        Ok(result?)
+    }
+
+    /// Read a `.replay.ron` file and `replay` it.
+    pub fn replay_file(&mut self, path: impl AsRef<Path>) -> Result<u64, RunnerError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| RunnerError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.replay(InputRecording::from_ron_str(&text)?)
+    }
+
+    /// Prefabs read by `load_scene_file`.
+    pub fn prefabs(&self) -> &PrefabLibrary {
+        &self.prefabs
+    }
+
+    /// Validation warnings (e.g. unknown fields), one entry per scene or prefab file that had any.
+    pub fn warnings(&self) -> &[Diagnostics] {
+        &self.warnings
+    }
+
+    /// Frames run since the runner was created.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn timestep(&self) -> f32 {
+        self.timestep
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    fn read_scene(&mut self, path: &Path) -> Result<Scene, RunnerError> {
+        let bytes = std::fs::read(path).map_err(|source| RunnerError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let (scene, diagnostics) = SceneLoader::new()
+            .file(path.display().to_string())
+            .components(self.app.world.resource::<ComponentRegistry>())
+            .asset_exists(&|asset| Path::new(asset).exists())
+            .load_as(SceneFormat::from_path(path), &bytes)
+            .map_err(|source| RunnerError::Scene {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if !diagnostics.is_empty() {
+            self.warnings.push(diagnostics);
+        }
+        Ok(scene)
+    }
+}
@@ -0,0 +1,94 @@
+/**
+ * path: /crates/engine_runner/src/main.rs
+ * description: CLI for the headless runner: load a scene, step it N frames, optionally render a frame.
+ */
+use clap::Parser;
+use engine_core::DEFAULT_FIXED_HZ;
+use engine_runner::HeadlessRunner;
+
+#[derive(Parser)]
+#[command(name = "engine_runner")]
+struct Args {
+    /// Path to a .scene.ron file
+    scene: String,
+    /// Number of frames (fixed steps) to run; with --replay, frames to run after the recording
+    #[arg(long)]
+    frames: Option<u64>,
+    /// Fixed steps per second
+    #[arg(long, default_value_t = DEFAULT_FIXED_HZ)]
+    hz: f32,
+    /// Replay an input recording (.replay.ron) from the start of the scene
+    #[arg(long)]
+    replay: Option<String>,
+    /// Render the final frame offscreen to this .ppm file (requires feature "offscreen")
+    #[arg(long)]
+    render: Option<String>,
+    /// Offscreen size as WIDTHxHEIGHT
+    #[arg(long, default_value = "640x360")]
+    size: String,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut runner = HeadlessRunner::with_fixed_hz(args.hz);
+    let spawned = match runner.load_scene_file(&args.scene) {
+        Ok(instance) => instance.entities.len(),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    for warnings in runner.warnings() {
+        eprintln!("{warnings}");
+    }
+    if let Some(replay) = &args.replay {
+        if let Err(e) = runner.replay_file(replay) {
+            eprintln!("{replay}: {e}");
+            std::process::exit(1);
+        }
+    }
+    let frames = args
+        .frames
+        .unwrap_or(if args.replay.is_some() { 0 } else { 60 });
+    runner.run_frames(frames);
+    println!(
+        "{}: spawned {} entities, ran {} frames ({:.3}s simulated)",
+        args.scene,
+        spawned,
+        runner.frame(),
+        runner.frame() as f64 * runner.timestep() as f64
+    );
+
+    if let Some(out) = args.render {
+        if let Err(e) = render(&mut runner, &out, &args.size) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "offscreen")]
+fn render(runner: &mut HeadlessRunner, out: &str, size: &str) -> Result<(), String> {
+    use engine_runner::offscreen::{
+        write_ppm,
+        OffscreenRenderer,
+    };
+
+    let (w, h) = size
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse::<u32>().ok()?, h.parse::<u32>().ok()?)))
+        .ok_or_else(|| format!("bad --size '{size}', expected WIDTHxHEIGHT"))?;
+    let mut renderer = OffscreenRenderer::new(w, h).map_err(|e| e.to_string())?;
+    let pixels = renderer
+        .render(runner.world_mut())
+        .map_err(|e| e.to_string())?;
+    write_ppm(out, w, h, &pixels).map_err(|e| format!("{out}: {e}"))?;
+    println!("rendered {w}x{h} frame to {out}");
+    Ok(())
+}
+
+#[cfg(not(feature = "offscreen"))]
+fn render(_runner: &mut HeadlessRunner, _out: &str, _size: &str) -> Result<(), String> {
+    Err("offscreen rendering requires `--features offscreen`".into())
+}
@@ -0,0 +1,207 @@
+/**
+ * path: /crates/engine_runner/src/offscreen.rs
+ * description: Optional offscreen rendering for the headless runner (feature "offscreen").
+ * Renders the world's sprites with the regular `QuadPipeline` into a texture and reads it back.
+ */
+use bevy_ecs::prelude::World;
+use engine_render::QuadPipeline;
+use engine_scene::{
+    GlobalTransform2D,
+    Sprite,
+};
+use engine_types::InstanceData;
+use std::io::Write;
+use std::path::Path;
+use wgpu::*;
+
+use crate::RunnerError;
+
+const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// Same sky-blue clear as the web viewport.
+const CLEAR: Color = Color {
+    r: 135.0 / 255.0,
+    g: 206.0 / 255.0,
+    b: 235.0 / 255.0,
+    a: 1.0,
+};
+
+pub struct OffscreenRenderer {
+    device: Device,
+    queue: Queue,
+    target: Texture,
+    quad_pipeline: QuadPipeline,
+    width: u32,
+    height: u32,
+}
+
+impl OffscreenRenderer {
+    /// Create a device without a surface. Fails if no adapter is available (e.g. CI without GPU).
+    pub fn new(width: u32, height: u32) -> Result<Self, RunnerError> {
+        let width = width.max(1);
+        let height = height.max(1);
+        let instance = Instance::new(&InstanceDescriptor {
+            backends: Backends::all(),
+            ..Default::default()
+        });
+        let adapter = pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
+            power_preference: PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .map_err(|e| RunnerError::Render(format!("no adapter: {e}")))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&DeviceDescriptor {
+            label: Some("ironhold_offscreen_device"),
+            ..Default::default()
+        }))
+        .map_err(|e| RunnerError::Render(format!("request_device failed: {e}")))?;
+
+        let target = device.create_texture(&TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let quad_pipeline = QuadPipeline::new(&device, FORMAT, &[]);
+        quad_pipeline.update_camera(&queue, width, height);
+
+        Ok(Self {
+            device,
+            queue,
+            target,
+            quad_pipeline,
+            width,
+            height,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Draw every entity with `GlobalTransform2D` + `Sprite` and return tightly packed RGBA8 pixels.
+    pub fn render(&mut self, world: &mut World) -> Result<Vec<u8>, RunnerError> {
+        let instances = sprite_instances(world);
+        self.quad_pipeline.ensure_capacity(&self.device, &instances);
+        self.quad_pipeline.update_instances(&self.queue, &instances);
+
+        let view = self.target.create_view(&TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("ironhold_offscreen_encoder"),
+            });
+        {
+            let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("ironhold_offscreen_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(CLEAR),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            if !instances.is_empty() {
+                self.quad_pipeline.draw(&mut rpass);
+            }
+        }
+
+        // Rows in the readback buffer must be 256-byte aligned
+        let row_bytes = self.width * 4;
+        let padded_row_bytes =
+            row_bytes.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Offscreen Readback"),
+            size: (padded_row_bytes * self.height) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            TexelCopyTextureInfo {
+                texture: &self.target,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(MapMode::Read, |_| {});
+        self.device
+            .poll(PollType::wait_indefinitely())
+            .map_err(|e| RunnerError::Render(format!("readback failed: {e}")))?;
+
+        let mut pixels = Vec::with_capacity((row_bytes * self.height) as usize);
+        {
+            let mapped = slice.get_mapped_range();
+            for row in mapped.chunks(padded_row_bytes as usize) {
+                pixels.extend_from_slice(&row[..row_bytes as usize]);
+            }
+        }
+        readback.unmap();
+        Ok(pixels)
+    }
+}
+
+/// Same packing as `engine_wasm_api::helpers::world_to_instances`.
+fn sprite_instances(world: &mut World) -> Vec<InstanceData> {
+    let mut query = world.query::<(&GlobalTransform2D, &Sprite)>();
+    query
+        .iter(world)
+        .map(|(g, s)| (g.to_transform(), s))
+        .map(|(t, s)| InstanceData {
+            transform: engine_types::Transform2D {
+                t0: [t.position.0, t.position.1, t.rotation.to_radians(), 0.0],
+                t1: [t.scale.0, t.scale.1, 0.0, 0.0],
+            },
+            sprite: engine_types::Sprite {
+                s0: [s.dimensions.0, s.dimensions.1, 0.0, 0.0],
+                color: [s.color.0, s.color.1, s.color.2, s.color.3],
+            },
+        })
+        .collect()
+}
+
+/// Write RGBA8 pixels as a binary PPM (alpha dropped); viewable without extra tooling.
+pub fn write_ppm(
+    path: impl AsRef<Path>,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+) -> std::io::Result<()> {
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write!(out, "P6\n{width} {height}\n255\n")?;
+    for px in rgba.chunks_exact(4) {
+        out.write_all(&px[..3])?;
+    }
+    out.flush()
+}
@@ -0,0 +1,191 @@
+/**
+ * path: /crates/engine_scene/src/binary.rs
+ * description: Binary scene encoding for shipping builds (`.scene.bin`).
+ * The scene is written in a packed container (`engine_types::packed`: header, version, CRC32)
+ * as a postcard payload. Component values stay generic, so unregistered components survive too.
+ * Only current-version scenes are encoded; older files are migrated from their RON source.
+ */
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::path::Path;
+
+use engine_types::{
+    pack,
+    unpack,
+    PackedKind,
+};
+
+use crate::{
+    Entity,
+    EntityUid,
+    PrefabOverride,
+    PrefabRef,
+    Scene,
+    SceneError,
+    SceneValue,
+    CURRENT_SCENE_VERSION,
+};
+
+/// File extension of binary scenes.
+pub const SCENE_BINARY_EXTENSION: &str = "scene.bin";
+
+/// How a scene file is encoded, picked from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    Ron,
+    Binary,
+}
+
+impl SceneFormat {
+    /// `Binary` for `.bin` files, `Ron` otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension() {
+            Some(ext) if ext == "bin" => SceneFormat::Binary,
+            _ => SceneFormat::Ron,
+        }
+    }
+}
+
+impl Scene {
+    /// Packed binary form of this scene; it must be at `CURRENT_SCENE_VERSION`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SceneError> {
+        if self.version != CURRENT_SCENE_VERSION {
+            return Err(SceneError::Migration {
+                from: self.version,
+                reason: "only current-version scenes can be written as binary".into(),
+            });
+        }
+        Ok(pack(
+            PackedKind::Scene,
+            self.version,
+            &PackedScene::from(self),
+        )?)
+    }
+
+    /// Decode a scene written by `to_bytes` (checks header and checksum, no validation;
+    /// see `SceneLoader::load_binary`).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Scene, SceneError> {
+        let (header, scene) = unpack::<PackedScene>(PackedKind::Scene, bytes)?;
+        match header.content_version {
+            CURRENT_SCENE_VERSION => Ok(scene.into()),
+            found if found > CURRENT_SCENE_VERSION => Err(SceneError::UnsupportedVersion {
+                found,
+                supported: CURRENT_SCENE_VERSION,
+            }),
+            from => Err(SceneError::Migration {
+                from,
+                reason: "binary scenes can't be migrated; convert the RON source again".into(),
+            }),
+        }
+    }
+}
+
+// Mirrors of the scene types for non-self-describing encodings: ids as raw 128-bit values.
+// `SceneValue` is already an explicit enum.
+
+#[derive(Serialize, Deserialize)]
+struct PackedScene {
+    name: String,
+    entities: Vec<PackedEntity>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackedEntity {
+    id: u128,
+    name: String,
+    parent: Option<u128>,
+    prefab: Option<PackedPrefab>,
+    components: Vec<(String, SceneValue)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackedPrefab {
+    path: String,
+    overrides: Vec<PackedOverride>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PackedOverride {
+    entity: Vec<u128>,
+    field: String,
+    value: SceneValue,
+}
+
+fn uid_bits(uid: EntityUid) -> u128 {
+    uid.uuid().as_u128()
+}
+
+fn uid_from_bits(bits: u128) -> EntityUid {
+    EntityUid::from_uuid(uuid::Uuid::from_u128(bits))
+}
+
+impl From<&Scene> for PackedScene {
+    fn from(scene: &Scene) -> Self {
+        Self {
+            name: scene.name.clone(),
+            entities: scene.entities.iter().map(PackedEntity::from).collect(),
+        }
+    }
+}
+
+impl From<PackedScene> for Scene {
+    fn from(scene: PackedScene) -> Self {
+        Self {
+            version: CURRENT_SCENE_VERSION,
+            name: scene.name,
+            entities: scene.entities.into_iter().map(Entity::from).collect(),
+        }
+    }
+}
+
+impl From<&Entity> for PackedEntity {
+    fn from(e: &Entity) -> Self {
+        Self {
+            id: uid_bits(e.id),
+            name: e.name.clone(),
+            parent: e.parent.map(uid_bits),
+            prefab: e.prefab.as_ref().map(|p| PackedPrefab {
+                path: p.path.clone(),
+                overrides: p
+                    .overrides
+                    .iter()
+                    .map(|o| PackedOverride {
+                        entity: o.entity.iter().copied().map(uid_bits).collect(),
+                        field: o.field.clone(),
+                        value: o.value.clone(),
+                    })
+                    .collect(),
+            }),
+            components: e
+                .components
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<PackedEntity> for Entity {
+    fn from(e: PackedEntity) -> Self {
+        Self {
+            id: uid_from_bits(e.id),
+            name: e.name,
+            parent: e.parent.map(uid_from_bits),
+            prefab: e.prefab.map(|p| PrefabRef {
+                path: p.path,
+                overrides: p
+                    .overrides
+                    .into_iter()
+                    .map(|o| PrefabOverride {
+                        entity: o.entity.into_iter().map(uid_from_bits).collect(),
+                        field: o.field,
+                        value: o.value,
+                    })
+                    .collect(),
+            }),
+            components: e.components.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+/**
+ * path: /crates/engine_scene/src/component.rs
+ * description: Registry of scene component types by name.
+ * Scene entities store components as `type name -> SceneValue`; the registry turns those values
+ * into ECS components. Games register their own types, and components nobody registered are
+ * kept as data (`UnknownComponents`) so loading and saving never drops them.
+ */
+use bevy_ecs::prelude::{
+    Component,
+    EntityRef,
+    EntityWorldMut,
+    Resource,
+};
+use bevy_reflect::{
+    GetTypeRegistration,
+    Reflect,
+};
+use engine_core::{
+    EngineApp,
+    Plugin,
+};
+use ron::extensions::Extensions;
+use ron::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{
+    EntityMap,
+    EntityUid,
+    Mesh,
+    Name,
+    SceneEntityId,
+    SceneValue,
+    Sprite,
+    Text,
+    Transform2D,
+    Transform3D,
+};
+
+/// A component that can be stored in scene files.
+/// It also derives `Reflect` (`#[reflect(Component, Default)]`) so editors and scripts can
+/// inspect and edit it by field path.
+pub trait SceneComponent:
+    Component + Reflect + GetTypeRegistration + Serialize + DeserializeOwned
+{
+    /// Key in an entity's `components` map, e.g. "Sprite".
+    const TYPE_NAME: &'static str;
+    /// Components that must be on the same entity (checked by validation).
+    const REQUIRES: &'static [&'static str] = &[];
+}
+
+impl SceneComponent for Transform2D {
+    const TYPE_NAME: &'static str = "Transform2D";
+}
+
+impl SceneComponent for Transform3D {
+    const TYPE_NAME: &'static str = "Transform3D";
+}
+
+impl SceneComponent for Sprite {
+    const TYPE_NAME: &'static str = "Sprite";
+    const REQUIRES: &'static [&'static str] = &["Transform2D"];
+}
+
+impl SceneComponent for Text {
+    const TYPE_NAME: &'static str = "Text";
+    const REQUIRES: &'static [&'static str] = &["Transform2D"];
+}
+
+impl SceneComponent for Mesh {
+    const TYPE_NAME: &'static str = "Mesh";
+    const REQUIRES: &'static [&'static str] = &["Transform3D"];
+}
+
+/// Scene components of a spawned entity whose type isn't registered, by type name.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct UnknownComponents(pub BTreeMap<String, SceneValue>);
+
+type UidMap<'a> = dyn FnMut(EntityUid) -> EntityUid + 'a;
+
+/// Type-erased operations for one registered component type.
+pub struct ComponentRegistration {
+    pub type_name: &'static str,
+    pub requires: &'static [&'static str],
+    normalize: fn(&SceneValue) -> Result<SceneValue, String>,
+    insert: fn(&mut EntityWorldMut, &SceneValue) -> Result<(), String>,
+    extract: fn(&EntityRef) -> Option<Result<SceneValue, String>>,
+    remove: fn(&mut EntityWorldMut),
+    map_uids: fn(&SceneValue, &mut UidMap) -> Result<Option<SceneValue>, String>,
+}
+
+impl ComponentRegistration {
+    fn of<T: SceneComponent>() -> Self {
+        Self {
+            type_name: T::TYPE_NAME,
+            requires: T::REQUIRES,
+            normalize: |value| to_value(&from_value::<T>(value)?),
+            insert: |entity, value| {
+                entity.insert(from_value::<T>(value)?);
+                Ok(())
+            },
+            extract: |entity| entity.get::<T>().map(to_value),
+            remove: |entity| {
+                entity.remove::<T>();
+            },
+            map_uids: |value, map| {
+                let mut component = from_value::<T>(value)?;
+                let found = engine_ecs::visit_values_mut(component.as_reflect_mut(), &mut |uid| {
+                    *uid = map(*uid)
+                });
+                match found {
+                    0 => Ok(None),
+                    _ => to_value(&component).map(Some),
+                }
+            },
+        }
+    }
+
+    /// Deserialize and serialize again: checks the value and drops fields the type doesn't have.
+    pub fn normalize(&self, value: &SceneValue) -> Result<SceneValue, String> {
+        (self.normalize)(value)
+    }
+
+    pub fn insert(&self, entity: &mut EntityWorldMut, value: &SceneValue) -> Result<(), String> {
+        (self.insert)(entity, value)
+    }
+
+    /// The entity's component as a scene value, if it has one.
+    pub fn extract(&self, entity: &EntityRef) -> Option<Result<SceneValue, String>> {
+        (self.extract)(entity)
+    }
+
+    pub fn remove(&self, entity: &mut EntityWorldMut) {
+        (self.remove)(entity)
+    }
+
+    /// Rewrite the `EntityUid` fields of a scene value; `None` if the type has none.
+    pub fn map_uids(
+        &self,
+        value: &SceneValue,
+        map: &mut UidMap,
+    ) -> Result<Option<SceneValue>, String> {
+        (self.map_uids)(value, map)
+    }
+}
+
+/// Component types that scenes can contain, by `TYPE_NAME`.
+/// `default()` holds the built-ins (`Transform2D`, `Transform3D`, `Sprite`, `Text`, `Mesh`).
+#[derive(Resource)]
+pub struct ComponentRegistry {
+    components: BTreeMap<&'static str, ComponentRegistration>,
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register::<Transform2D>()
+            .register::<Transform3D>()
+            .register::<Sprite>()
+            .register::<Text>()
+            .register::<Mesh>();
+        registry
+    }
+}
+
+impl ComponentRegistry {
+    pub fn empty() -> Self {
+        Self {
+            components: BTreeMap::new(),
+        }
+    }
+
+    /// Add (or replace) the registration for `T::TYPE_NAME`.
+    pub fn register<T: SceneComponent>(&mut self) -> &mut Self {
+        self.components
+            .insert(T::TYPE_NAME, ComponentRegistration::of::<T>());
+        self
+    }
+
+    pub fn get(&self, type_name: &str) -> Option<&ComponentRegistration> {
+        self.components.get(type_name)
+    }
+
+    pub fn contains(&self, type_name: &str) -> bool {
+        self.components.contains_key(type_name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentRegistration> {
+        self.components.values()
+    }
+}
+
+/// Registers scene component types on the app's `ComponentRegistry` resource
+/// and its reflection registry.
+pub trait SceneAppExt {
+    fn register_scene_component<T: SceneComponent>(&mut self) -> &mut Self;
+}
+
+impl SceneAppExt for EngineApp {
+    fn register_scene_component<T: SceneComponent>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(ComponentRegistry::default)
+            .register::<T>();
+        self.register_type::<T>()
+    }
+}
+
+/// Adds the `ComponentRegistry` and `EntityMap` and makes the built-in scene components, `Name`,
+/// `SceneEntityId` and `EntityUid` reflectable.
+pub struct ScenePlugin;
+
+impl Plugin for ScenePlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.init_resource::<ComponentRegistry>()
+            .init_resource::<EntityMap>()
+            .register_type::<Transform2D>()
+            .register_type::<Transform3D>()
+            .register_type::<Sprite>()
+            .register_type::<Text>()
+            .register_type::<Mesh>()
+            .register_type::<Name>()
+            .register_type::<SceneEntityId>()
+            .register_type::<EntityUid>();
+    }
+}
+
+pub(crate) fn from_value<T: DeserializeOwned>(value: &SceneValue) -> Result<T, String> {
+    // Values read from `implicit_some` documents have no `Some(..)` around optional fields;
+    // the typed parser knows where they belong
+    let text = value.to_ron().map_err(|e| e.to_string())?;
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(&text)
+        .or_else(|e| {
+            // Structs written as maps (`{"color": ..}`) and tuples as lists still read through
+            // `ron::Value`, which doesn't tell them apart
+            Value::from(value).into_rust().map_err(|_| e.to_string())
+        })
+}
+
+pub(crate) fn to_value<T: Serialize>(value: &T) -> Result<SceneValue, String> {
+    let text = ron::to_string(value).map_err(|e| e.to_string())?;
+    SceneValue::parse(&text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::prelude::ReflectComponent;
+    use bevy_ecs::world::World;
+    use bevy_reflect::std_traits::ReflectDefault;
+    use engine_text::TextAlign;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        extract_entity,
+        spawn_scene,
+        Entity,
+        Scene,
+        SceneLoader,
+        SceneWriter,
+    };
+
+    #[derive(Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+    enum Kind {
+        #[default]
+        Melee,
+        Ranged {
+            range: f32,
+        },
+        Thrown(u32),
+    }
+
+    #[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+    #[reflect(Component, Default)]
+    struct Weapon {
+        kind: Kind,
+        backup: Option<Kind>,
+        fallback: Kind,
+    }
+
+    impl SceneComponent for Weapon {
+        const TYPE_NAME: &'static str = "Weapon";
+    }
+
+    fn bow() -> Weapon {
+        Weapon {
+            kind: Kind::Ranged { range: 4.5 },
+            backup: Some(Kind::Thrown(3)),
+            fallback: Kind::Melee,
+        }
+    }
+
+    fn scene() -> Scene {
+        let text = Text {
+            text: "archer".into(),
+            align: TextAlign::Center,
+            ..Default::default()
+        };
+        let mut scene = Scene::default();
+        scene.entities.push(
+            Entity::new(1, "archer")
+                .with_component(&Transform2D::default())
+                .with_component(&text)
+                .with_component(&bow()),
+        );
+        scene
+    }
+
+    fn registry() -> ComponentRegistry {
+        let mut registry = ComponentRegistry::default();
+        registry.register::<Weapon>();
+        registry
+    }
+
+    #[test]
+    fn entity_keeps_enum_variants() {
+        let entity = &scene().entities[0];
+        assert_eq!(entity.component::<Weapon>(), Some(bow()));
+        assert_eq!(entity.component::<Text>().unwrap().align, TextAlign::Center);
+    }
+
+    #[test]
+    fn ron_text_keeps_enum_variants() {
+        let scene = scene();
+        let registry = registry();
+        let text = scene.to_ron_string().unwrap();
+        assert!(text.contains("kind: Ranged(\n"), "{text}");
+        assert!(text.contains("backup: Thrown(3),"), "{text}");
+        assert!(text.contains("align: Center,"), "{text}");
+        let (loaded, diagnostics) = SceneLoader::new()
+            .components(&registry)
+            .load(&text)
+            .unwrap();
+        assert!(diagnostics.is_empty(), "{diagnostics}");
+        assert_eq!(loaded.entities[0].component::<Weapon>(), Some(bow()));
+        assert_eq!(
+            loaded.entities[0].component::<Text>().unwrap().align,
+            TextAlign::Center
+        );
+
+        let explicit = SceneWriter::new()
+            .implicit_some(false)
+            .write(&scene)
+            .unwrap();
+        let (loaded, _) = SceneLoader::new()
+            .components(&registry)
+            .load(&explicit)
+            .unwrap();
+        assert_eq!(loaded, scene);
+    }
+
+    #[test]
+    fn binary_keeps_enum_variants() {
+        let scene = scene();
+        assert_eq!(
+            Scene::from_bytes(&scene.to_bytes().unwrap()).unwrap(),
+            scene
+        );
+    }
+
+    #[test]
+    fn spawn_and_extract_keep_enum_variants() {
+        let scene = scene();
+        let mut world = World::new();
+        world.insert_resource(registry());
+        let instance = spawn_scene(&mut world, &scene).unwrap();
+        let entity = instance.entities[&EntityUid::from(1)];
+        assert_eq!(world.get::<Weapon>(entity), Some(&bow()));
+        let extracted = extract_entity(&world, entity).unwrap().unwrap();
+        assert_eq!(extracted.component::<Weapon>(), Some(bow()));
+        assert_eq!(extracted.components, scene.entities[0].components);
+    }
+
+    #[test]
+    fn unregistered_components_keep_enum_variants() {
+        let text = "(version: 3, name: \"s\", entities: [(id: 1, name: \"a\", components: {\
+                    \"Weapon\": (kind: Thrown(2), backup: None, fallback: Melee)})])";
+        let (scene, _) = SceneLoader::new().load(text).unwrap();
+        assert_eq!(
+            scene.entities[0].component::<Weapon>(),
+            Some(Weapon {
+                kind: Kind::Thrown(2),
+                backup: None,
+                fallback: Kind::Melee,
+            })
+        );
+        let written = scene.to_ron_string().unwrap();
+        assert!(written.contains("kind: Thrown(2),"), "{written}");
+    }
+}
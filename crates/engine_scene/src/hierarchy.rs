@@ -0,0 +1,168 @@
+/**
+ * path: /crates/engine_scene/src/hierarchy.rs
+ * description: Parent/child relationships and world-space transform propagation.
+ * Scenes author local transforms; `propagate_transforms` writes `GlobalTransform2D`/`GlobalTransform3D`
+ * every frame (PostUpdate) so renderers can draw world-space results.
+ */
+use bevy_ecs::entity::Entity as EcsEntity;
+use bevy_ecs::prelude::{
+    Component,
+    Query,
+    Without,
+};
+use engine_core::{
+    EngineApp,
+    Plugin,
+    PostUpdate,
+};
+use glam::{
+    Affine2,
+    EulerRot,
+    Mat4,
+    Quat,
+    Vec2,
+    Vec3,
+};
+
+use crate::{
+    Transform2D,
+    Transform3D,
+};
+
+/// The entity this entity is attached to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub EcsEntity);
+
+/// Entities attached to this entity, in authored order.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(pub Vec<EcsEntity>);
+
+/// World-space 2D transform, written by `propagate_transforms`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GlobalTransform2D(pub Affine2);
+
+/// World-space 3D transform, written by `propagate_transforms`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GlobalTransform3D(pub Mat4);
+
+impl Transform2D {
+    /// Local matrix: scale, then rotate (degrees, same direction as the quad shader), then translate.
+    pub fn to_affine(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(
+            Vec2::new(self.scale.0, self.scale.1),
+            self.rotation.to_radians(),
+            Vec2::new(self.position.0, self.position.1),
+        )
+    }
+}
+
+impl Transform3D {
+    /// Local matrix; rotation is applied as Euler angles in X, Y, Z order (degrees).
+    pub fn to_matrix(&self) -> Mat4 {
+        let (rx, ry, rz) = self.rotation;
+        Mat4::from_scale_rotation_translation(
+            Vec3::new(self.scale.0, self.scale.1, self.scale.2),
+            Quat::from_euler(
+                EulerRot::XYZ,
+                rx.to_radians(),
+                ry.to_radians(),
+                rz.to_radians(),
+            ),
+            Vec3::new(self.position.0, self.position.1, self.position.2),
+        )
+    }
+}
+
+impl GlobalTransform2D {
+    /// World-space (position, rotation in degrees, scale), in the same units as `Transform2D`.
+    /// Lossy if a parent with non-uniform scale is rotated relative to its child (shear).
+    pub fn to_transform(&self) -> Transform2D {
+        let (scale, angle, translation) = self.0.to_scale_angle_translation();
+        Transform2D {
+            position: (translation.x, translation.y),
+            rotation: angle.to_degrees(),
+            scale: (scale.x, scale.y),
+        }
+    }
+}
+
+impl GlobalTransform3D {
+    pub fn translation(&self) -> Vec3 {
+        self.0.w_axis.truncate()
+    }
+}
+
+/// Registers `propagate_transforms` in `PostUpdate`.
+pub struct HierarchyPlugin;
+
+impl Plugin for HierarchyPlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.add_systems(PostUpdate, propagate_transforms);
+    }
+}
+
+type Locals<'a> = (
+    Option<&'a Transform2D>,
+    Option<&'a Transform3D>,
+    Option<&'a Children>,
+);
+type Globals<'a> = (
+    Option<&'a mut GlobalTransform2D>,
+    Option<&'a mut GlobalTransform3D>,
+);
+
+/// Walk every hierarchy from its roots and compose local transforms into global ones.
+/// An entity without a 2D (or 3D) transform passes its parent's global through to its
+/// children, so plain entities can be used as groups.
+pub fn propagate_transforms(
+    roots: Query<EcsEntity, Without<Parent>>,
+    locals: Query<Locals>,
+    mut globals: Query<Globals>,
+) {
+    for root in &roots {
+        propagate(root, None, None, &locals, &mut globals, 0);
+    }
+}
+
+/// Deeper than any sane scene; stops runaway recursion if a cycle was created at runtime.
+const MAX_DEPTH: usize = 256;
+
+fn propagate(
+    entity: EcsEntity,
+    parent_2d: Option<Affine2>,
+    parent_3d: Option<Mat4>,
+    locals: &Query<Locals>,
+    globals: &mut Query<Globals>,
+    depth: usize,
+) {
+    let Ok((local_2d, local_3d, children)) = locals.get(entity) else {
+        return;
+    };
+
+    let global_2d = match local_2d {
+        Some(t) => Some(parent_2d.unwrap_or(Affine2::IDENTITY) * t.to_affine()),
+        None => parent_2d,
+    };
+    let global_3d = match local_3d {
+        Some(t) => Some(parent_3d.unwrap_or(Mat4::IDENTITY) * t.to_matrix()),
+        None => parent_3d,
+    };
+
+    if let Ok((g2, g3)) = globals.get_mut(entity) {
+        if let (Some(mut g), Some(m)) = (g2, global_2d) {
+            g.0 = m;
+        }
+        if let (Some(mut g), Some(m)) = (g3, global_3d) {
+            g.0 = m;
+        }
+    }
+
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    if let Some(children) = children {
+        for &child in &children.0 {
+            propagate(child, global_2d, global_3d, locals, globals, depth + 1);
+        }
+    }
+}
@@ -5,41 +5,234 @@
  * engine_scene should only describe what exists (entities, components, references to assets).
  * It should not know how assets are loaded or rendered.
  */
+use bevy_ecs::prelude::{
+    Component,
+    ReflectComponent,
+};
+use bevy_reflect::std_traits::ReflectDefault;
+use bevy_reflect::Reflect;
+use engine_text::TextAlign;
 use serde::{
     Deserialize,
     Serialize,
 };
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+use thiserror::Error;
+
+mod binary;
+mod component;
+mod hierarchy;
+mod locate;
+mod migrate;
+mod patch;
+mod prefab;
+mod reader;
+mod spawn;
+mod uid;
+mod validate;
+mod value;
+mod writer;
+pub use binary::{
+    SceneFormat,
+    SCENE_BINARY_EXTENSION,
+};
+pub use component::{
+    ComponentRegistration,
+    ComponentRegistry,
+    SceneAppExt,
+    SceneComponent,
+    ScenePlugin,
+    UnknownComponents,
+};
+pub use hierarchy::{
+    propagate_transforms,
+    Children,
+    GlobalTransform2D,
+    GlobalTransform3D,
+    HierarchyPlugin,
+    Parent,
+};
+pub use locate::{
+    PathSegment,
+    Position,
+    SourceMap,
+};
+pub use migrate::{
+    scene_version,
+    Migration,
+    MigrationFn,
+    MigrationRegistry,
+    CURRENT_SCENE_VERSION,
+};
+pub use patch::{
+    apply_scene_patch,
+    SceneChange,
+    ScenePatch,
+};
+pub use prefab::{
+    PrefabLibrary,
+    PrefabOverride,
+    PrefabRef,
+};
+pub use spawn::{
+    despawn_scene,
+    extract_entity,
+    spawn_scene,
+    Name,
+    PrefabInstance,
+    SceneEntityId,
+    SceneInstance,
+};
+pub use uid::{
+    remap_entity,
+    EntityMap,
+    EntityUid,
+};
+pub use validate::{
+    Diagnostic,
+    Diagnostics,
+    SceneLoader,
+    Severity,
+};
+pub use value::SceneValue;
+pub use writer::SceneWriter;
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Error, Debug)]
+pub enum SceneError {
+    #[error("parse: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("scene text is not UTF-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("migrated scene: {0}")]
+    Migrated(#[from] ron::Error),
+    #[error("write: {0}")]
+    Write(ron::Error),
+    #[error("scene version {found} is newer than this engine supports ({supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("migrating from version {from}: {reason}")]
+    Migration { from: u32, reason: String },
+    #[error("duplicate entity id {0}")]
+    DuplicateId(EntityUid),
+    #[error("entity {id} has unknown parent {parent}")]
+    DanglingParent { id: EntityUid, parent: EntityUid },
+    #[error("parent cycle: {}", fmt_ids(.0))]
+    ParentCycle(Vec<EntityUid>),
+    #[error("no entity {0} in the scene")]
+    MissingEntity(EntityUid),
+    #[error("prefab not loaded: {0}")]
+    MissingPrefab(String),
+    #[error("prefab cycle: {}", .0.join(" -> "))]
+    PrefabCycle(Vec<String>),
+    #[error("override '{field}' of prefab entity {}: {reason}", fmt_ids(.entity))]
+    BadOverride {
+        entity: Vec<EntityUid>,
+        field: String,
+        reason: String,
+    },
+    #[error("entity {entity}: component {component}: {reason}")]
+    Component {
+        entity: EntityUid,
+        component: String,
+        reason: String,
+    },
+    #[error("invalid scene:\n{0}")]
+    Invalid(Diagnostics),
+    #[error("binary scene: {0}")]
+    Packed(#[from] engine_types::PackedError),
+}
+
+fn fmt_ids(ids: &[EntityUid]) -> String {
+    ids.iter()
+        .map(EntityUid::to_string)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Scene {
+    /// Format version (see `migrate`); files without it are version 0.
+    #[serde(default)]
+    pub version: u32,
     pub name: String,
     pub entities: Vec<Entity>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_SCENE_VERSION,
+            name: String::new(),
+            entities: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct Entity {
-    pub id: u32,
+    /// Stable id (see `EntityUid`); small integers are fine for hand-written scenes.
+    pub id: EntityUid,
     pub name: String,
 
-    // Optional components
-    #[serde(default)]
-    pub transform_2d: Option<Transform2D>,
+    /// Id of the entity this one is attached to; transforms are relative to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<EntityUid>,
 
-    #[serde(default)]
-    pub transform_3d: Option<Transform3D>,
+    /// Makes this entity the root of a prefab instance (see `PrefabLibrary::resolve`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefab: Option<PrefabRef>,
 
-    #[serde(default)]
-    pub sprite: Option<Sprite>,
+    /// Components by type name (see `ComponentRegistry`), e.g.
+    /// `{"Transform2D": (position: (0.0, 0.0), rotation: 0.0, scale: (1.0, 1.0))}`.
+    /// Names that aren't registered are kept as-is.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, SceneValue>,
+}
 
-    #[serde(default)]
-    pub mesh: Option<Mesh>,
+impl Entity {
+    pub fn new(id: impl Into<EntityUid>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The component of type `T`; `None` if it isn't set or its data doesn't fit `T`.
+    pub fn component<T: SceneComponent>(&self) -> Option<T> {
+        self.components
+            .get(T::TYPE_NAME)
+            .and_then(|value| component::from_value(value).ok())
+    }
+
+    pub fn has_component(&self, type_name: &str) -> bool {
+        self.components.contains_key(type_name)
+    }
+
+    pub fn set_component<T: SceneComponent>(&mut self, value: &T) {
+        let value = component::to_value(value).expect("scene components serialize to RON");
+        self.components.insert(T::TYPE_NAME.to_string(), value);
+    }
+
+    pub fn with_component<T: SceneComponent>(mut self, value: &T) -> Self {
+        self.set_component(value);
+        self
+    }
+
+    pub fn remove_component(&mut self, type_name: &str) -> Option<SceneValue> {
+        self.components.remove(type_name)
+    }
 }
 
 /// Spatial transform.
 /// - `position`: (x, y) in world units
 /// - `rotation`: degrees (clockwise, screen-space; adjust later if needed)
 /// - `scale`: (sx, sy) multiplicative scaling
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone)]
+#[reflect(Component, Default)]
 pub struct Transform2D {
     pub position: (f32, f32),
     pub rotation: f32,
@@ -49,24 +242,60 @@ pub struct Transform2D {
 /// Sprite rendering description.
 /// - `dimensions`: (width, height) in world units (pre-scale)
 /// - `color`: RGBA (0..1)
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone)]
+#[reflect(Component, Default)]
 pub struct Sprite {
     pub dimensions: (f32, f32),
     pub color: (f32, f32, f32, f32),
 }
 
+/// A text label in the world, centred on the entity's transform.
+/// - `size`: font size in world units
+/// - `color`: RGBA (0..1)
+/// - `font`: name of a loaded font (see `engine_text::Fonts`); the default font if unset
+/// - `align`: `Left`, `Center` or `Right`
+/// - `max_width`: wrap lines at this width (world units); only at newlines if unset
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone)]
+#[reflect(Component, Default)]
+#[serde(default)]
+pub struct Text {
+    pub text: String,
+    pub size: f32,
+    pub color: (f32, f32, f32, f32),
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    pub align: TextAlign,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<f32>,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            size: 1.0,
+            color: (1.0, 1.0, 1.0, 1.0),
+            font: None,
+            align: TextAlign::Left,
+            max_width: None,
+        }
+    }
+}
+
 /// Spatial transform.
 /// - `position`: (x, y, z) in world units
 /// - `rotation`: degrees for (x, y, z, clockwize)
 /// - `scale`: (sx, sy, sz) multiplicative scaling
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone)]
+#[reflect(Component, Default)]
 pub struct Transform3D {
     pub position: (f32, f32, f32),
     pub rotation: (f32, f32, f32),
     pub scale: (f32, f32, f32),
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Default, Clone)]
+#[reflect(Component, Default)]
 pub struct Mesh {
     pub file: String,
     pub node: Option<usize>,
@@ -74,10 +303,64 @@ pub struct Mesh {
 }
 
 impl Scene {
-    pub fn from_ron_str(s: &str) -> Result<Scene, ron::error::SpannedError> {
-        ron::from_str(s)
+    /// Parse, migrate older versions and validate; see `SceneLoader` for file names and warnings.
+    pub fn from_ron_str(s: &str) -> Result<Scene, SceneError> {
+        SceneLoader::new().load(s).map(|(scene, _)| scene)
+    }
+
+    /// Like `from_ron_str`, with a custom migration registry.
+    pub fn from_ron_str_with(s: &str, migrations: &MigrationRegistry) -> Result<Scene, SceneError> {
+        SceneLoader::new()
+            .migrations(migrations)
+            .load(s)
+            .map(|(scene, _)| scene)
     }
-    pub fn to_ron_string(&self) -> String {
-        ron::to_string(self).unwrap_or_default()
+
+    /// Ids must be unique and every `parent` must name another entity without forming a cycle.
+    pub fn validate_hierarchy(&self) -> Result<(), SceneError> {
+        let mut parents: HashMap<EntityUid, Option<EntityUid>> = HashMap::new();
+        for e in &self.entities {
+            if parents.insert(e.id, e.parent).is_some() {
+                return Err(SceneError::DuplicateId(e.id));
+            }
+        }
+        for e in &self.entities {
+            if let Some(parent) = e.parent {
+                if !parents.contains_key(&parent) {
+                    return Err(SceneError::DanglingParent { id: e.id, parent });
+                }
+            }
+        }
+
+        // Walk up from every entity; revisiting an id on the same walk means a cycle
+        let mut acyclic: HashSet<EntityUid> = HashSet::new();
+        for e in &self.entities {
+            let mut path = Vec::new();
+            let mut current = Some(e.id);
+            while let Some(id) = current {
+                if acyclic.contains(&id) {
+                    break;
+                }
+                if let Some(start) = path.iter().position(|p| *p == id) {
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(id);
+                    return Err(SceneError::ParentCycle(cycle));
+                }
+                path.push(id);
+                current = parents[&id];
+            }
+            acyclic.extend(path);
+        }
+        Ok(())
+    }
+
+    /// Authored children of `id`, in scene order.
+    pub fn children_of(&self, id: EntityUid) -> impl Iterator<Item = &Entity> {
+        self.entities.iter().filter(move |e| e.parent == Some(id))
+    }
+
+    /// Pretty RON with the default `SceneWriter` settings; use `SceneWriter` to keep comments.
+    pub fn to_ron_string(&self) -> Result<String, SceneError> {
+        SceneWriter::new().write(self)
     }
 }
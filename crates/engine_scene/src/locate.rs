@@ -0,0 +1,427 @@
+/**
+ * path: /crates/engine_scene/src/locate.rs
+ * description: Source positions for RON documents.
+ * `ron` only reports positions for syntax errors, so validation re-scans the text into a light tree
+ * of field names and list items to point diagnostics at `entities[2].components.Sprite.color`.
+ */
+use std::iter;
+use std::ops::Range;
+
+/// 1-based line and column (in characters).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Shape of a parsed RON value, with byte offsets.
+#[derive(Debug)]
+enum Node {
+    /// `(a: .., b: ..)`, `Name(a: ..)` or `{"a": ..}`: (field name or key, offset of the name, value)
+    Struct(Vec<(String, usize, SpannedNode)>),
+    /// `(..)`, `[..]`
+    Seq(Vec<SpannedNode>),
+    /// `Some(..)`: paths step through it
+    Some(Box<SpannedNode>),
+    /// Anything else (numbers, strings, maps with non-string keys, `None`, ...)
+    Leaf,
+}
+
+/// - `lead`: where the whitespace/comments before the value start
+/// - `offset`..`end`: the value itself
+#[derive(Debug)]
+struct SpannedNode {
+    lead: usize,
+    offset: usize,
+    end: usize,
+    node: Node,
+}
+
+/// Span index of one document.
+pub struct SourceMap<'a> {
+    text: &'a str,
+    root: Option<SpannedNode>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Scan `text`; a document that can't be scanned yields no positions rather than an error.
+    pub fn new(text: &'a str) -> Self {
+        let mut scanner = Scanner {
+            src: text.as_bytes(),
+            i: 0,
+        };
+        scanner.skip_attributes();
+        let root = scanner.value();
+        Self { text, root }
+    }
+
+    /// Position of the value at `path` (e.g. `entities.2.components.Sprite`), or of its nearest
+    /// existing ancestor. Field values point at the field name, list items at their first character.
+    pub fn position(&self, path: &[PathSegment]) -> Option<Position> {
+        let (offset, _, _) = self.walk(path)?;
+        Some(self.offset_to_position(offset))
+    }
+
+    /// Field names of the struct at `path` with their positions (stepping through `Some`).
+    pub fn fields(&self, path: &[PathSegment]) -> Vec<(&str, Position)> {
+        let Some((_, node, true)) = self.walk(path) else {
+            return Vec::new();
+        };
+        match &unwrap_some(node).node {
+            Node::Struct(fields) => fields
+                .iter()
+                .map(|(name, offset, _)| (name.as_str(), self.offset_to_position(*offset)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Byte range of the value at `path` and where the whitespace/comments before it start;
+    /// `None` unless the whole path exists.
+    pub(crate) fn span(&self, path: &[PathSegment]) -> Option<(usize, Range<usize>)> {
+        match self.walk(path)? {
+            (_, node, true) => Some((node.lead, node.offset..node.end)),
+            _ => None,
+        }
+    }
+
+    /// Follow `path` as far as it exists: (offset, node, whether the whole path matched).
+    fn walk(&self, path: &[PathSegment]) -> Option<(usize, &SpannedNode, bool)> {
+        let mut node = self.root.as_ref()?;
+        let mut offset = node.offset;
+        for segment in path {
+            let next = match (&unwrap_some(node).node, segment) {
+                (Node::Struct(fields), PathSegment::Field(name)) => fields
+                    .iter()
+                    .find(|(field, _, _)| field == name)
+                    .map(|(_, name_offset, value)| (*name_offset, value)),
+                (Node::Seq(items), PathSegment::Index(i)) => items.get(*i).map(|v| (v.offset, v)),
+                _ => None,
+            };
+            let Some((next_offset, next)) = next else {
+                return Some((offset, node, false));
+            };
+            offset = next_offset;
+            node = next;
+        }
+        Some((offset, node, true))
+    }
+
+    fn offset_to_position(&self, offset: usize) -> Position {
+        position_at(self.text, offset)
+    }
+}
+
+pub(crate) fn position_at(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+fn unwrap_some(mut node: &SpannedNode) -> &SpannedNode {
+    while let Node::Some(inner) = &node.node {
+        node = inner;
+    }
+    node
+}
+
+/// One step of a value path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// Tokenizing helpers shared with the `SceneValue` parser.
+pub(crate) struct Scanner<'a> {
+    pub(crate) src: &'a [u8],
+    pub(crate) i: usize,
+}
+
+impl Scanner<'_> {
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.src.get(self.i).copied()
+    }
+
+    /// Skip whitespace, `// line` and (nested) `/* block */` comments.
+    pub(crate) fn skip_trivia(&mut self) {
+        loop {
+            match (self.peek(), self.src.get(self.i + 1).copied()) {
+                (Some(c), _) if c.is_ascii_whitespace() => self.i += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|c| c != b'\n') {
+                        self.i += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    let mut depth = 0;
+                    while self.i < self.src.len() {
+                        if self.src[self.i..].starts_with(b"/*") {
+                            depth += 1;
+                            self.i += 2;
+                        } else if self.src[self.i..].starts_with(b"*/") {
+                            depth -= 1;
+                            self.i += 2;
+                            if depth == 0 {
+                                break;
+                            }
+                        } else {
+                            self.i += 1;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    pub(crate) fn eat(&mut self, c: u8) -> bool {
+        self.skip_trivia();
+        if self.peek() == Some(c) {
+            self.i += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn ident(&mut self) -> Option<String> {
+        self.skip_trivia();
+        let start = self.i;
+        if self.src[start..].starts_with(b"r#") {
+            self.i += 2;
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_')
+        {
+            self.i += 1;
+        }
+        if self.i == start || self.src[start].is_ascii_digit() {
+            self.i = start;
+            return None;
+        }
+        let ident = std::str::from_utf8(&self.src[start..self.i]).ok()?;
+        Some(ident.trim_start_matches("r#").to_string())
+    }
+
+    /// Skip inner attributes such as `#![enable(implicit_some)]` at the start of a document.
+    pub(crate) fn skip_attributes(&mut self) {
+        loop {
+            self.skip_trivia();
+            if !self.src[self.i..].starts_with(b"#![") {
+                return;
+            }
+            while self.peek().is_some_and(|c| c != b']') {
+                self.i += 1;
+            }
+            self.i += 1;
+        }
+    }
+
+    fn value(&mut self) -> Option<SpannedNode> {
+        let lead = self.i;
+        self.skip_trivia();
+        let offset = self.i;
+        let node = match self.peek()? {
+            b'(' => self.parens()?,
+            b'[' => {
+                self.i += 1;
+                Node::Seq(self.items(b']')?)
+            }
+            b'{' => {
+                self.i += 1;
+                self.map()?
+            }
+            b'"' => {
+                self.string()?;
+                Node::Leaf
+            }
+            b'\'' => {
+                self.char_literal()?;
+                Node::Leaf
+            }
+            b'r' if self.at_raw_string() => {
+                self.raw_string()?;
+                Node::Leaf
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => self.ident_or_named()?,
+            _ => {
+                // Numbers and other scalar tokens
+                while self.peek().is_some_and(|c| {
+                    !matches!(c, b',' | b')' | b']' | b'}' | b':') && !c.is_ascii_whitespace()
+                }) {
+                    self.i += 1;
+                }
+                Node::Leaf
+            }
+        };
+        Some(SpannedNode {
+            lead,
+            offset,
+            end: self.i,
+            node,
+        })
+    }
+
+    /// `Some(x)`, `Name(..)` or a bare identifier such as `None`/`true`.
+    fn ident_or_named(&mut self) -> Option<Node> {
+        let name = self.ident()?;
+        self.skip_trivia();
+        if self.peek() != Some(b'(') {
+            return Some(Node::Leaf);
+        }
+        if name == "Some" {
+            self.i += 1;
+            let inner = self.value()?;
+            self.eat(b',');
+            return self.eat(b')').then(|| Node::Some(Box::new(inner)));
+        }
+        self.parens()
+    }
+
+    /// `(field: value, ..)` struct or `(a, b)` tuple.
+    fn parens(&mut self) -> Option<Node> {
+        self.i += 1;
+        let start = self.i;
+        let is_struct = self.ident().is_some() && self.eat(b':');
+        self.i = start;
+        if !is_struct {
+            return Some(Node::Seq(self.items(b')')?));
+        }
+        let mut fields = Vec::new();
+        loop {
+            if self.eat(b')') {
+                return Some(Node::Struct(fields));
+            }
+            self.skip_trivia();
+            let name_offset = self.i;
+            let name = self.ident()?;
+            if !self.eat(b':') {
+                return None;
+            }
+            fields.push((name, name_offset, self.value()?));
+            if !self.eat(b',') {
+                return self.eat(b')').then_some(Node::Struct(fields));
+            }
+        }
+    }
+
+    fn items(&mut self, close: u8) -> Option<Vec<SpannedNode>> {
+        let mut items = Vec::new();
+        loop {
+            let before = self.i;
+            if self.eat(close) {
+                return Some(items);
+            }
+            // Keep the comments before the item in its `lead`
+            self.i = before;
+            items.push(self.value()?);
+            if !self.eat(b',') {
+                return self.eat(close).then_some(items);
+            }
+        }
+    }
+
+    /// `{key: value, ..}`; indexed like a struct when every key is a string.
+    fn map(&mut self) -> Option<Node> {
+        let mut entries = Vec::new();
+        let mut string_keys = true;
+        loop {
+            if self.eat(b'}') {
+                break;
+            }
+            self.skip_trivia();
+            let key_offset = self.i;
+            self.value()?;
+            let key = &self.src[key_offset..self.i];
+            let name = match key {
+                [b'"', inner @ .., b'"'] if !inner.contains(&b'\\') => {
+                    std::str::from_utf8(inner).ok()
+                }
+                _ => None,
+            };
+            string_keys &= name.is_some();
+            if !self.eat(b':') {
+                return None;
+            }
+            let value = self.value()?;
+            if let Some(name) = name {
+                entries.push((name.to_string(), key_offset, value));
+            }
+            if !self.eat(b',') {
+                if !self.eat(b'}') {
+                    return None;
+                }
+                break;
+            }
+        }
+        Some(if string_keys {
+            Node::Struct(entries)
+        } else {
+            Node::Leaf
+        })
+    }
+
+    fn string(&mut self) -> Option<()> {
+        self.i += 1;
+        while let Some(c) = self.peek() {
+            self.i += 1;
+            match c {
+                b'\\' => self.i += 1,
+                b'"' => return Some(()),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn char_literal(&mut self) -> Option<()> {
+        self.i += 1;
+        while let Some(c) = self.peek() {
+            self.i += 1;
+            match c {
+                b'\\' => self.i += 1,
+                b'\'' => return Some(()),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// `r"..."` / `r#"..."#` (as opposed to the identifier `r#name`).
+    pub(crate) fn at_raw_string(&self) -> bool {
+        let rest = &self.src[self.i + 1..];
+        let hashes = rest.iter().take_while(|c| **c == b'#').count();
+        rest.get(hashes) == Some(&b'"')
+    }
+
+    /// Skip a raw string; the range of its contents.
+    pub(crate) fn raw_string(&mut self) -> Option<Range<usize>> {
+        self.i += 1;
+        let hashes = self.src[self.i..]
+            .iter()
+            .take_while(|c| **c == b'#')
+            .count();
+        self.i += hashes;
+        if self.peek() != Some(b'"') {
+            return None;
+        }
+        self.i += 1;
+        let start = self.i;
+        let mut closing = vec![b'"'];
+        closing.extend(iter::repeat_n(b'#', hashes));
+        while self.i < self.src.len() {
+            if self.src[self.i..].starts_with(&closing) {
+                let contents = start..self.i;
+                self.i += closing.len();
+                return Some(contents);
+            }
+            self.i += 1;
+        }
+        None
+    }
+}
@@ -0,0 +1,320 @@
+/**
+ * path: /crates/engine_scene/src/migrate.rs
+ * description: Scene format versions and migrations.
+ * Older documents are parsed into a generic `ron::Value` tree and upgraded one version at a time
+ * before being deserialized into `Scene`.
+ *
+ * Versions:
+ * - 0: no `version` field; entities may use `transform:` (2D or 3D), bare `mesh.node`/`mesh.primitive`
+ *   indices, and omit `id`/`name`
+ * - 1: `transform` split into `transform_2d` / `transform_3d`; mesh indices are `Some(..)`
+ * - 2: every entity has an `id` and a `name`
+ * - 3: components move from fixed fields (`transform_2d`, `sprite`, ..) into the `components` map
+ *   keyed by type name; override paths start with the type name
+ */
+use ron::value::{
+    Map,
+    Number,
+};
+use ron::Value;
+
+use crate::SceneError;
+
+/// Version written by this engine; files with a higher version are rejected.
+pub const CURRENT_SCENE_VERSION: u32 = 3;
+
+/// Upgrades a scene document from `from` to `from + 1` in place.
+pub type MigrationFn = fn(&mut Value) -> Result<(), String>;
+
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: MigrationFn,
+}
+
+/// Ordered migration steps. `default()` contains the built-in steps up to `CURRENT_SCENE_VERSION`.
+pub struct MigrationRegistry {
+    migrations: Vec<Migration>,
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(
+                0,
+                "split `transform` into `transform_2d`/`transform_3d`, wrap mesh indices in `Some`",
+                split_transform,
+            )
+            .register(1, "assign missing entity ids and names", fill_ids_and_names)
+            .register(
+                2,
+                "move component fields into the `components` map",
+                components_map,
+            );
+        registry
+    }
+}
+
+impl MigrationRegistry {
+    pub fn empty() -> Self {
+        Self {
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Add (or replace) the step upgrading `from` to `from + 1`.
+    pub fn register(
+        &mut self,
+        from: u32,
+        description: &'static str,
+        apply: MigrationFn,
+    ) -> &mut Self {
+        self.migrations.retain(|m| m.from != from);
+        self.migrations.push(Migration {
+            from,
+            description,
+            apply,
+        });
+        self.migrations.sort_by_key(|m| m.from);
+        self
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Upgrade `doc` to `CURRENT_SCENE_VERSION` and set its `version` field.
+    /// Returns the version the document had before.
+    pub fn migrate(&self, doc: &mut Value) -> Result<u32, SceneError> {
+        let original = scene_version(doc)?;
+        if original > CURRENT_SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion {
+                found: original,
+                supported: CURRENT_SCENE_VERSION,
+            });
+        }
+        for version in original..CURRENT_SCENE_VERSION {
+            let step = self
+                .migrations
+                .iter()
+                .find(|m| m.from == version)
+                .ok_or_else(|| SceneError::Migration {
+                    from: version,
+                    reason: "no migration registered".into(),
+                })?;
+            (step.apply)(doc).map_err(|reason| SceneError::Migration {
+                from: version,
+                reason,
+            })?;
+            set_field(
+                doc,
+                "version",
+                Value::Number(Number::new(i64::from(version + 1))),
+            );
+        }
+        Ok(original)
+    }
+}
+
+/// The document's `version` field; 0 when absent.
+pub fn scene_version(doc: &Value) -> Result<u32, SceneError> {
+    let invalid = |reason: &str| SceneError::Migration {
+        from: 0,
+        reason: reason.into(),
+    };
+    let Value::Map(map) = doc else {
+        return Err(invalid("scene is not a struct"));
+    };
+    match field(map, "version") {
+        None => Ok(0),
+        Some(Value::Number(n)) => n
+            .as_i64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| invalid("`version` must be a non-negative integer")),
+        Some(_) => Err(invalid("`version` must be a non-negative integer")),
+    }
+}
+
+fn field<'a>(map: &'a Map, name: &str) -> Option<&'a Value> {
+    map.iter()
+        .find(|(k, _)| matches!(k, Value::String(s) if s == name))
+        .map(|(_, v)| v)
+}
+
+fn set_field(doc: &mut Value, name: &str, value: Value) {
+    if let Value::Map(map) = doc {
+        map.insert(Value::String(name.into()), value);
+    }
+}
+
+/// Entity maps of a scene document.
+fn entities_mut(doc: &mut Value) -> Result<impl Iterator<Item = &mut Map>, String> {
+    let Value::Map(map) = doc else {
+        return Err("scene is not a struct".into());
+    };
+    let entities = map
+        .iter_mut()
+        .find(|(k, _)| matches!(k, Value::String(s) if s == "entities"))
+        .map(|(_, v)| v);
+    match entities {
+        Some(Value::Seq(entities)) => Ok(entities.iter_mut().filter_map(|e| match e {
+            Value::Map(m) => Some(m),
+            _ => None,
+        })),
+        _ => Err("`entities` must be a list".into()),
+    }
+}
+
+/// 0 -> 1: `transform: (position: (x, y), ..)` becomes `transform_2d: Some(..)`,
+/// a 3-component position becomes `transform_3d: Some(..)`; `mesh: Some((node: 0, ..))`
+/// becomes `node: Some(0)`.
+fn split_transform(doc: &mut Value) -> Result<(), String> {
+    for entity in entities_mut(doc)? {
+        let mesh = entity
+            .iter_mut()
+            .find(|(k, _)| matches!(k, Value::String(s) if s == "mesh"))
+            .map(|(_, v)| v);
+        if let Some(Value::Option(Some(mesh))) = mesh {
+            if let Value::Map(mesh) = mesh.as_mut() {
+                for (key, value) in mesh.iter_mut() {
+                    let is_index =
+                        matches!(key, Value::String(s) if s == "node" || s == "primitive");
+                    if is_index && matches!(value, Value::Number(_)) {
+                        *value = Value::Option(Some(Box::new(value.clone())));
+                    }
+                }
+            }
+        }
+
+        let Some(transform) = entity.remove(&Value::String("transform".into())) else {
+            continue;
+        };
+        let inner = match &transform {
+            Value::Option(Some(inner)) => inner.as_ref(),
+            Value::Option(None) => continue,
+            other => other,
+        };
+        let Value::Map(inner) = inner else {
+            return Err("`transform` must be a struct".into());
+        };
+        let target = match field(inner, "position") {
+            Some(Value::Seq(p)) if p.len() == 2 => "transform_2d",
+            Some(Value::Seq(p)) if p.len() == 3 => "transform_3d",
+            _ => return Err("`transform.position` must have 2 or 3 components".into()),
+        };
+        if field(entity, target).is_some_and(|v| *v != Value::Option(None)) {
+            return Err(format!("entity has both `transform` and `{target}`"));
+        }
+        let transform = match transform {
+            Value::Option(_) => transform,
+            other => Value::Option(Some(Box::new(other))),
+        };
+        entity.insert(Value::String(target.into()), transform);
+    }
+    Ok(())
+}
+
+/// 1 -> 2: entities without `id` get the next free id, entities without `name` get "Entity<id>".
+fn fill_ids_and_names(doc: &mut Value) -> Result<(), String> {
+    let mut next_id = entities_mut(doc)?
+        .filter_map(|e| match field(e, "id") {
+            Some(Value::Number(n)) => n.as_i64(),
+            _ => None,
+        })
+        .max()
+        .map_or(1, |max| max + 1);
+    for entity in entities_mut(doc)? {
+        let id = match field(entity, "id") {
+            Some(Value::Number(n)) => n.as_i64().ok_or("`id` must be an integer")?,
+            Some(_) => return Err("`id` must be an integer".into()),
+            None => {
+                let id = next_id;
+                next_id += 1;
+                entity.insert(Value::String("id".into()), Value::Number(Number::new(id)));
+                id
+            }
+        };
+        if field(entity, "name").is_none() {
+            entity.insert(
+                Value::String("name".into()),
+                Value::String(format!("Entity{id}")),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Component fields before version 3 and their type names.
+const LEGACY_COMPONENTS: [(&str, &str); 4] = [
+    ("transform_2d", "Transform2D"),
+    ("transform_3d", "Transform3D"),
+    ("sprite", "Sprite"),
+    ("mesh", "Mesh"),
+];
+
+/// 2 -> 3: `sprite: Some((..))` becomes `components: {"Sprite": (..)}` (unset components are
+/// dropped), and prefab override paths such as `sprite.color` become `Sprite.color`.
+fn components_map(doc: &mut Value) -> Result<(), String> {
+    for entity in entities_mut(doc)? {
+        let mut components = match entity.remove(&Value::String("components".into())) {
+            Some(Value::Map(map)) => map,
+            Some(_) => return Err("`components` must be a map".into()),
+            None => Map::new(),
+        };
+        for (field, type_name) in LEGACY_COMPONENTS {
+            match entity.remove(&Value::String(field.into())) {
+                None | Some(Value::Option(None)) => {}
+                Some(Value::Option(Some(value))) => {
+                    components.insert(Value::String(type_name.into()), *value);
+                }
+                Some(value) => {
+                    components.insert(Value::String(type_name.into()), value);
+                }
+            }
+        }
+        entity.insert(Value::String("components".into()), Value::Map(components));
+
+        let prefab = entity
+            .iter_mut()
+            .find(|(k, _)| matches!(k, Value::String(s) if s == "prefab"))
+            .map(|(_, v)| v);
+        let Some(Value::Option(Some(prefab))) = prefab else {
+            continue;
+        };
+        let Value::Map(prefab) = prefab.as_mut() else {
+            return Err("`prefab` must be a struct".into());
+        };
+        let overrides = prefab
+            .iter_mut()
+            .find(|(k, _)| matches!(k, Value::String(s) if s == "overrides"))
+            .map(|(_, v)| v);
+        let Some(Value::Seq(overrides)) = overrides else {
+            continue;
+        };
+        for o in overrides.iter_mut() {
+            let Value::Map(o) = o else {
+                continue;
+            };
+            let Some(Value::String(path)) = field(o, "field").cloned() else {
+                continue;
+            };
+            let (head, rest) = path.split_once('.').unwrap_or((&path, ""));
+            let Some((_, type_name)) = LEGACY_COMPONENTS.iter().find(|(f, _)| *f == head) else {
+                continue;
+            };
+            let new_path = match rest {
+                "" => type_name.to_string(),
+                rest => format!("{type_name}.{rest}"),
+            };
+            o.insert(Value::String("field".into()), Value::String(new_path));
+            // A whole component was `Some(..)`; in the map it is the bare value
+            if rest.is_empty() {
+                if let Some(Value::Option(Some(value))) = field(o, "value").cloned() {
+                    o.insert(Value::String("value".into()), *value);
+                }
+            }
+        }
+    }
+    Ok(())
+}
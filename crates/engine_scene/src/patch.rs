@@ -0,0 +1,506 @@
+/**
+ * path: /crates/engine_scene/src/patch.rs
+ * description: Structural scene diffs.
+ * `Scene::diff` lists what changed between two scenes (entities added/removed, names, parents,
+ * prefab links, components and component fields) as a `ScenePatch`. A patch applies to a `Scene`
+ * or to a spawned instance in a live world, touching only what changed, and can be inverted, so
+ * the same format serves hot reload, undo/redo and collaborative editing.
+ */
+use bevy_ecs::entity::Entity as EcsEntity;
+use bevy_ecs::prelude::{
+    Mut,
+    World,
+};
+use bevy_ecs::system::RunSystemOnce;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use crate::prefab::{
+    diff_value,
+    set_path,
+};
+use crate::spawn::{
+    insert_globals,
+    link_child,
+    spawn_entity,
+    unlink_child,
+};
+use crate::{
+    propagate_transforms,
+    Children,
+    ComponentRegistry,
+    Entity,
+    EntityMap,
+    EntityUid,
+    Name,
+    Parent,
+    PrefabInstance,
+    PrefabRef,
+    Scene,
+    SceneError,
+    SceneInstance,
+    SceneValue,
+    UnknownComponents,
+};
+
+/// One change to a scene. Every change carries the previous value too, so it can be inverted.
+/// - `SetField.path`: a component type name (whole value) or a dotted path into one
+///   ("Transform2D.position.0"), as in prefab overrides
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SceneChange {
+    AddEntity(Entity),
+    RemoveEntity(Entity),
+    SetName {
+        entity: EntityUid,
+        old: String,
+        new: String,
+    },
+    SetParent {
+        entity: EntityUid,
+        old: Option<EntityUid>,
+        new: Option<EntityUid>,
+    },
+    SetPrefab {
+        entity: EntityUid,
+        old: Option<PrefabRef>,
+        new: Option<PrefabRef>,
+    },
+    AddComponent {
+        entity: EntityUid,
+        component: String,
+        value: SceneValue,
+    },
+    RemoveComponent {
+        entity: EntityUid,
+        component: String,
+        value: SceneValue,
+    },
+    SetField {
+        entity: EntityUid,
+        path: String,
+        old: SceneValue,
+        new: SceneValue,
+    },
+}
+
+/// Ordered list of scene changes; see `Scene::diff`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ScenePatch {
+    pub changes: Vec<SceneChange>,
+}
+
+impl SceneChange {
+    /// Id of the entity this change is about.
+    pub fn entity(&self) -> EntityUid {
+        match self {
+            SceneChange::AddEntity(e) | SceneChange::RemoveEntity(e) => e.id,
+            SceneChange::SetName { entity, .. }
+            | SceneChange::SetParent { entity, .. }
+            | SceneChange::SetPrefab { entity, .. }
+            | SceneChange::AddComponent { entity, .. }
+            | SceneChange::RemoveComponent { entity, .. }
+            | SceneChange::SetField { entity, .. } => *entity,
+        }
+    }
+
+    /// The change that undoes this one.
+    pub fn inverse(&self) -> SceneChange {
+        match self.clone() {
+            SceneChange::AddEntity(e) => SceneChange::RemoveEntity(e),
+            SceneChange::RemoveEntity(e) => SceneChange::AddEntity(e),
+            SceneChange::SetName { entity, old, new } => SceneChange::SetName {
+                entity,
+                old: new,
+                new: old,
+            },
+            SceneChange::SetParent { entity, old, new } => SceneChange::SetParent {
+                entity,
+                old: new,
+                new: old,
+            },
+            SceneChange::SetPrefab { entity, old, new } => SceneChange::SetPrefab {
+                entity,
+                old: new,
+                new: old,
+            },
+            SceneChange::AddComponent {
+                entity,
+                component,
+                value,
+            } => SceneChange::RemoveComponent {
+                entity,
+                component,
+                value,
+            },
+            SceneChange::RemoveComponent {
+                entity,
+                component,
+                value,
+            } => SceneChange::AddComponent {
+                entity,
+                component,
+                value,
+            },
+            SceneChange::SetField {
+                entity,
+                path,
+                old,
+                new,
+            } => SceneChange::SetField {
+                entity,
+                path,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+impl ScenePatch {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The patch that undoes this one (inverted changes in reverse order).
+    pub fn inverse(&self) -> ScenePatch {
+        ScenePatch {
+            changes: self
+                .changes
+                .iter()
+                .rev()
+                .map(SceneChange::inverse)
+                .collect(),
+        }
+    }
+
+    /// Append the changes of `other` (applied after these).
+    pub fn extend(&mut self, other: ScenePatch) {
+        self.changes.extend(other.changes);
+    }
+}
+
+impl Scene {
+    /// Changes that turn `self` into `other`, matching entities by id: removed entities first,
+    /// then added ones (in `other`'s order), then per-entity edits. Component values are compared
+    /// field by field, so a patch only carries the fields that differ.
+    pub fn diff(&self, other: &Scene) -> ScenePatch {
+        let before: HashMap<EntityUid, &Entity> = self.entities.iter().map(|e| (e.id, e)).collect();
+        let after: HashSet<EntityUid> = other.entities.iter().map(|e| e.id).collect();
+        let mut changes: Vec<SceneChange> = self
+            .entities
+            .iter()
+            .filter(|e| !after.contains(&e.id))
+            .map(|e| SceneChange::RemoveEntity(e.clone()))
+            .collect();
+        changes.extend(
+            other
+                .entities
+                .iter()
+                .filter(|e| !before.contains_key(&e.id))
+                .map(|e| SceneChange::AddEntity(e.clone())),
+        );
+        for new in &other.entities {
+            if let Some(old) = before.get(&new.id) {
+                diff_entity(old, new, &mut changes);
+            }
+        }
+        ScenePatch { changes }
+    }
+
+    /// Apply `patch` in order. Fails on the first change that doesn't fit (unknown entity,
+    /// duplicate id, missing component or field); earlier changes stay applied.
+    /// Previous values are not checked, so patches from concurrent edits apply last-writer-wins.
+    pub fn apply_patch(&mut self, patch: &ScenePatch) -> Result<(), SceneError> {
+        for change in &patch.changes {
+            self.apply_change(change)?;
+        }
+        Ok(())
+    }
+
+    fn apply_change(&mut self, change: &SceneChange) -> Result<(), SceneError> {
+        let id = change.entity();
+        if let SceneChange::AddEntity(entity) = change {
+            if self.entities.iter().any(|e| e.id == id) {
+                return Err(SceneError::DuplicateId(id));
+            }
+            self.entities.push(entity.clone());
+            return Ok(());
+        }
+        let index = self
+            .entities
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or(SceneError::MissingEntity(id))?;
+        let entity = &mut self.entities[index];
+        match change {
+            SceneChange::AddEntity(_) => unreachable!(),
+            SceneChange::RemoveEntity(_) => {
+                self.entities.remove(index);
+            }
+            SceneChange::SetName { new, .. } => entity.name = new.clone(),
+            SceneChange::SetParent { new, .. } => entity.parent = *new,
+            SceneChange::SetPrefab { new, .. } => entity.prefab = new.clone(),
+            SceneChange::AddComponent {
+                component, value, ..
+            } => {
+                entity.components.insert(component.clone(), value.clone());
+            }
+            SceneChange::RemoveComponent { component, .. } => {
+                entity.components.remove(component);
+            }
+            SceneChange::SetField { path, new, .. } => {
+                let (component, rest) = split_path(path);
+                let value = entity
+                    .components
+                    .get_mut(component)
+                    .ok_or_else(|| component_error(id, component, "component is not set"))?;
+                set_path(value, &rest, new.clone())
+                    .map_err(|reason| component_error(id, component, &reason))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn diff_entity(old: &Entity, new: &Entity, out: &mut Vec<SceneChange>) {
+    let entity = new.id;
+    if old.name != new.name {
+        out.push(SceneChange::SetName {
+            entity,
+            old: old.name.clone(),
+            new: new.name.clone(),
+        });
+    }
+    if old.parent != new.parent {
+        out.push(SceneChange::SetParent {
+            entity,
+            old: old.parent,
+            new: new.parent,
+        });
+    }
+    if old.prefab != new.prefab {
+        out.push(SceneChange::SetPrefab {
+            entity,
+            old: old.prefab.clone(),
+            new: new.prefab.clone(),
+        });
+    }
+    for (component, value) in &old.components {
+        if !new.components.contains_key(component) {
+            out.push(SceneChange::RemoveComponent {
+                entity,
+                component: component.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    for (component, value) in &new.components {
+        match old.components.get(component) {
+            Some(old_value) => {
+                let mut fields = Vec::new();
+                diff_value(old_value, value, component.clone(), &mut fields);
+                out.extend(
+                    fields
+                        .into_iter()
+                        .map(|(path, old, new)| SceneChange::SetField {
+                            entity,
+                            path,
+                            old,
+                            new,
+                        }),
+                );
+            }
+            None => out.push(SceneChange::AddComponent {
+                entity,
+                component: component.clone(),
+                value: value.clone(),
+            }),
+        }
+    }
+}
+
+fn split_path(path: &str) -> (&str, Vec<&str>) {
+    let mut segments = path.split('.');
+    let component = segments.next().unwrap_or_default();
+    (component, segments.collect())
+}
+
+fn component_error(entity: EntityUid, component: &str, reason: &str) -> SceneError {
+    SceneError::Component {
+        entity,
+        component: component.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Apply `patch` to the entities `instance` spawned in `world` (a scene with prefabs resolved,
+/// like `spawn_scene` takes). Only the named entities and components are touched: component fields
+/// are written into the live component, so its other fields, other components and entities outside
+/// the scene keep their runtime state. Changes to entities or components that are no longer in the
+/// world are skipped. `instance`, the `EntityMap` and global transforms are kept up to date.
+pub fn apply_scene_patch(
+    world: &mut World,
+    instance: &mut SceneInstance,
+    patch: &ScenePatch,
+) -> Result<(), SceneError> {
+    world.get_resource_or_insert_with(ComponentRegistry::default);
+    world.get_resource_or_insert_with(EntityMap::default);
+    let mut pending_parents = Vec::new();
+    world.resource_scope(|world, registry: Mut<ComponentRegistry>| {
+        for change in &patch.changes {
+            apply_world_change(world, &registry, instance, change, &mut pending_parents)?;
+        }
+        Ok::<(), SceneError>(())
+    })?;
+    // Added entities are linked last, their parents may come later in the patch
+    for (child, parent) in pending_parents {
+        if let (Some(child), Some(parent)) = (instance.get(child), instance.get(parent)) {
+            link_child(world, parent, child);
+        }
+    }
+    world.run_system_once(propagate_transforms);
+    Ok(())
+}
+
+fn apply_world_change(
+    world: &mut World,
+    registry: &ComponentRegistry,
+    instance: &mut SceneInstance,
+    change: &SceneChange,
+    pending_parents: &mut Vec<(EntityUid, EntityUid)>,
+) -> Result<(), SceneError> {
+    let id = change.entity();
+    if let SceneChange::AddEntity(entity) = change {
+        if let Some(previous) = instance.entities.remove(&id) {
+            despawn_entity(world, previous);
+        }
+        let spawned = spawn_entity(world, registry, entity)?;
+        instance.entities.insert(id, spawned);
+        world.resource_mut::<EntityMap>().insert(id, spawned);
+        if let Some(parent) = entity.parent {
+            pending_parents.push((id, parent));
+        }
+        return Ok(());
+    }
+    let Some(target) = instance.get(id).filter(|e| world.get_entity(*e).is_some()) else {
+        return Ok(());
+    };
+    match change {
+        SceneChange::AddEntity(_) => unreachable!(),
+        SceneChange::RemoveEntity(_) => {
+            instance.entities.remove(&id);
+            despawn_entity(world, target);
+        }
+        SceneChange::SetName { new, .. } => {
+            world.entity_mut(target).insert(Name(new.clone()));
+        }
+        SceneChange::SetParent { new, .. } => {
+            unlink_child(world, target);
+            if let Some(parent) = new.and_then(|p| instance.get(p)) {
+                link_child(world, parent, target);
+            }
+        }
+        SceneChange::SetPrefab { new, .. } => {
+            let mut e = world.entity_mut(target);
+            match new {
+                Some(prefab) => {
+                    e.insert(PrefabInstance(prefab.path.clone()));
+                }
+                None => {
+                    e.remove::<PrefabInstance>();
+                }
+            }
+        }
+        SceneChange::AddComponent {
+            component, value, ..
+        } => insert_component(world, registry, id, target, component, value)?,
+        SceneChange::RemoveComponent { component, .. } => {
+            let mut e = world.entity_mut(target);
+            match registry.get(component) {
+                Some(registration) => registration.remove(&mut e),
+                None => {
+                    if let Some(mut unknown) = e.get_mut::<UnknownComponents>() {
+                        unknown.0.remove(component);
+                    }
+                }
+            }
+            insert_globals(&mut e);
+        }
+        SceneChange::SetField { path, new, .. } => {
+            let (component, rest) = split_path(path);
+            let e = world.entity(target);
+            let live = match registry.get(component) {
+                Some(registration) => registration.extract(&e).transpose(),
+                None => Ok(e
+                    .get::<UnknownComponents>()
+                    .and_then(|unknown| unknown.0.get(component).cloned())),
+            };
+            let Some(mut value) = live.map_err(|reason| component_error(id, component, &reason))?
+            else {
+                return Ok(());
+            };
+            set_path(&mut value, &rest, new.clone())
+                .map_err(|reason| component_error(id, component, &reason))?;
+            insert_component(world, registry, id, target, component, &value)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_component(
+    world: &mut World,
+    registry: &ComponentRegistry,
+    id: EntityUid,
+    target: EcsEntity,
+    component: &str,
+    value: &SceneValue,
+) -> Result<(), SceneError> {
+    let mut e = world.entity_mut(target);
+    match registry.get(component) {
+        Some(registration) => registration
+            .insert(&mut e, value)
+            .map_err(|reason| component_error(id, component, &reason))?,
+        None => match e.get_mut::<UnknownComponents>() {
+            Some(mut unknown) => {
+                unknown.0.insert(component.to_string(), value.clone());
+            }
+            None => {
+                let mut unknown = UnknownComponents::default();
+                unknown.0.insert(component.to_string(), value.clone());
+                e.insert(unknown);
+            }
+        },
+    }
+    insert_globals(&mut e);
+    Ok(())
+}
+
+/// Despawn one scene entity: detach it from its parent, orphan its children and drop it from the
+/// `EntityMap`.
+fn despawn_entity(world: &mut World, entity: EcsEntity) {
+    if world.get_entity(entity).is_none() {
+        return;
+    }
+    unlink_child(world, entity);
+    let children = world
+        .get::<Children>(entity)
+        .map(|c| c.0.clone())
+        .unwrap_or_default();
+    for child in children {
+        if let Some(mut e) = world.get_entity_mut(child) {
+            e.remove::<Parent>();
+        }
+    }
+    world.despawn(entity);
+    world.resource_mut::<EntityMap>().remove(entity);
+}
@@ -0,0 +1,481 @@
+/**
+ * path: /crates/engine_scene/src/prefab.rs
+ * description: Prefabs: scene files instanced by path from other scenes.
+ * An entity with `prefab: Some((path: .., overrides: [..]))` becomes the instance root; the prefab's
+ * root entities are attached to it. Overrides set any component field of an instanced entity.
+ * The library only holds parsed prefabs; fetching/reading files is up to the caller.
+ */
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use crate::{
+    remap_entity,
+    ComponentRegistry,
+    Entity,
+    EntityUid,
+    Scene,
+    SceneError,
+    SceneValue,
+};
+
+/// Link from a scene entity to a prefab scene file.
+/// - `path`: prefab file, e.g. "assets/prefabs/enemy.scene.ron"
+/// - `overrides`: per-instance field values, applied after the prefab's own overrides
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PrefabRef {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<PrefabOverride>,
+}
+
+/// One overridden field of an instanced entity.
+/// - `entity`: id inside the prefab; nested instances are addressed by chaining ids, e.g. `[5, 1]`
+/// - `field`: "name", a component type name ("Sprite") or a dotted path into one
+///   ("Sprite.color", "Transform2D.position.0")
+/// - `value`: RON value for that field; `None` for a whole component removes it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PrefabOverride {
+    pub entity: Vec<EntityUid>,
+    pub field: String,
+    pub value: SceneValue,
+}
+
+/// Parsed prefabs by path.
+#[derive(Debug, Default, Clone)]
+pub struct PrefabLibrary {
+    prefabs: HashMap<String, Scene>,
+}
+
+/// A resolved entity plus the chain of authored ids leading to it through prefab instances.
+struct Expanded {
+    chain: Vec<EntityUid>,
+    parent: Option<Vec<EntityUid>>,
+    entity: Entity,
+}
+
+/// Fields that define structure rather than data; they can't be overridden.
+const STRUCTURAL_FIELDS: [&str; 4] = ["id", "parent", "prefab", "components"];
+
+impl PrefabLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: impl Into<String>, prefab: Scene) {
+        self.prefabs.insert(path.into(), prefab);
+    }
+
+    /// Parse (and validate) a prefab file's contents.
+    pub fn insert_ron(&mut self, path: impl Into<String>, ron: &str) -> Result<(), SceneError> {
+        let prefab = Scene::from_ron_str(ron)?;
+        self.insert(path, prefab);
+        Ok(())
+    }
+
+    pub fn get(&self, path: &str) -> Option<&Scene> {
+        self.prefabs.get(path)
+    }
+
+    pub fn remove(&mut self, path: &str) -> Option<Scene> {
+        self.prefabs.remove(path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.prefabs.keys().map(String::as_str)
+    }
+
+    /// Prefab paths used by `scene`, directly or through loaded prefabs, that are not loaded yet.
+    /// Load them and call again until empty to pull in nested prefabs.
+    pub fn missing(&self, scene: &Scene) -> Vec<String> {
+        let mut missing = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![scene];
+        while let Some(current) = pending.pop() {
+            for prefab_ref in current.entities.iter().filter_map(|e| e.prefab.as_ref()) {
+                let path = prefab_ref.path.as_str();
+                if !seen.insert(path) {
+                    continue;
+                }
+                match self.prefabs.get(path) {
+                    Some(prefab) => pending.push(prefab),
+                    None => missing.push(path.to_string()),
+                }
+            }
+        }
+        missing
+    }
+
+    /// Flatten every prefab instance into plain entities.
+    /// Entities of `scene` keep their ids; instanced entities get ids derived from the instance
+    /// root and their id in the prefab (`EntityUid::derive`), so resolving the same files twice
+    /// yields the same ids. Parents and `EntityUid` fields of registered components in instanced
+    /// entities are remapped to those ids.
+    pub fn resolve(
+        &self,
+        scene: &Scene,
+        components: &ComponentRegistry,
+    ) -> Result<Scene, SceneError> {
+        let resolved = Scene {
+            version: scene.version,
+            name: scene.name.clone(),
+            entities: self
+                .resolve_chains(scene, components)?
+                .into_iter()
+                .map(|(_, entity)| entity)
+                .collect(),
+        };
+        resolved.validate_hierarchy()?;
+        Ok(resolved)
+    }
+
+    /// Turn an edited copy of `resolve(scene)` back into an authoring scene: prefab links are
+    /// kept and instanced entities are stored only as overrides of what differs from the prefab.
+    /// Entities added to the edited scene are kept as plain entities; instanced entities that
+    /// were removed are not recorded.
+    pub fn extract_overrides(
+        &self,
+        scene: &Scene,
+        edited: &Scene,
+        components: &ComponentRegistry,
+    ) -> Result<Scene, SceneError> {
+        let edited_by_id: HashMap<EntityUid, &Entity> =
+            edited.entities.iter().map(|e| (e.id, e)).collect();
+        let resolved = self.resolve_chains(scene, components)?;
+        let ids: HashMap<&[EntityUid], EntityUid> = resolved
+            .iter()
+            .map(|(chain, e)| (chain.as_slice(), e.id))
+            .collect();
+
+        let mut entities = Vec::new();
+        for authored in &scene.entities {
+            let Some(edited_entity) = edited_by_id.get(&authored.id) else {
+                continue;
+            };
+            let mut entity = (*edited_entity).clone();
+            entity.prefab = match &authored.prefab {
+                Some(prefab_ref) => Some(PrefabRef {
+                    path: prefab_ref.path.clone(),
+                    overrides: self.instance_overrides(
+                        authored.id,
+                        prefab_ref,
+                        &ids,
+                        &edited_by_id,
+                        components,
+                    )?,
+                }),
+                None => None,
+            };
+            entities.push(entity);
+        }
+
+        let known: HashSet<EntityUid> = ids.values().copied().collect();
+        entities.extend(
+            edited
+                .entities
+                .iter()
+                .filter(|e| !known.contains(&e.id))
+                .cloned(),
+        );
+
+        Ok(Scene {
+            version: edited.version,
+            name: edited.name.clone(),
+            entities,
+        })
+    }
+
+    /// Overrides that turn the bare prefab into the edited instance rooted at `root`.
+    fn instance_overrides(
+        &self,
+        root: EntityUid,
+        prefab_ref: &PrefabRef,
+        ids: &HashMap<&[EntityUid], EntityUid>,
+        edited_by_id: &HashMap<EntityUid, &Entity>,
+        components: &ComponentRegistry,
+    ) -> Result<Vec<PrefabOverride>, SceneError> {
+        let prefab = self
+            .prefabs
+            .get(&prefab_ref.path)
+            .ok_or_else(|| SceneError::MissingPrefab(prefab_ref.path.clone()))?;
+        let chains: HashMap<EntityUid, &[EntityUid]> =
+            ids.iter().map(|(chain, id)| (*id, *chain)).collect();
+        let mut overrides = Vec::new();
+        for base in self.expand(prefab, &mut vec![prefab_ref.path.clone()])? {
+            let chain = prepend(root, &base.chain);
+            let Some(edited) = ids
+                .get(chain.as_slice())
+                .and_then(|id| edited_by_id.get(id))
+            else {
+                continue;
+            };
+            // Compare in prefab terms: references to siblings go back to their prefab ids
+            let prefix = &chain[..chain.len() - 1];
+            let mut edited = (*edited).clone();
+            remap_entity(&mut edited, components, &mut |uid| match chains.get(&uid) {
+                Some(c) if c.len() == chain.len() && c.starts_with(prefix) => c[c.len() - 1],
+                _ => uid,
+            })?;
+            for (field, value) in diff_fields(&base.entity, &edited) {
+                overrides.push(PrefabOverride {
+                    entity: base.chain.clone(),
+                    field,
+                    value,
+                });
+            }
+        }
+        Ok(overrides)
+    }
+
+    fn resolve_chains(
+        &self,
+        scene: &Scene,
+        components: &ComponentRegistry,
+    ) -> Result<Vec<(Vec<EntityUid>, Entity)>, SceneError> {
+        let expanded = self.expand(scene, &mut Vec::new())?;
+        let ids: HashMap<Vec<EntityUid>, EntityUid> = expanded
+            .iter()
+            .map(|x| {
+                let id = x.chain[1..]
+                    .iter()
+                    .fold(x.chain[0], |root, id| root.derive(*id));
+                (x.chain.clone(), id)
+            })
+            .collect();
+
+        expanded
+            .into_iter()
+            .map(|x| {
+                let mut entity = x.entity;
+                entity.id = ids[&x.chain];
+                // References inside an instanced entity use its prefab's ids
+                let prefix = &x.chain[..x.chain.len() - 1];
+                if !prefix.is_empty() {
+                    remap_entity(&mut entity, components, &mut |uid| {
+                        let mut chain = prefix.to_vec();
+                        chain.push(uid);
+                        ids.get(&chain).copied().unwrap_or(uid)
+                    })?;
+                }
+                entity.parent = x.parent.map(|parent| ids[&parent]);
+                Ok((x.chain, entity))
+            })
+            .collect()
+    }
+
+    /// Recursively inline prefab instances. `stack` holds the prefab paths being expanded.
+    fn expand(&self, scene: &Scene, stack: &mut Vec<String>) -> Result<Vec<Expanded>, SceneError> {
+        scene.validate_hierarchy()?;
+
+        let mut out = Vec::new();
+        for e in &scene.entities {
+            out.push(Expanded {
+                chain: vec![e.id],
+                parent: e.parent.map(|p| vec![p]),
+                entity: e.clone(),
+            });
+            let Some(prefab_ref) = &e.prefab else {
+                continue;
+            };
+
+            let path = &prefab_ref.path;
+            if stack.contains(path) {
+                let mut cycle = stack.clone();
+                cycle.push(path.clone());
+                return Err(SceneError::PrefabCycle(cycle));
+            }
+            let prefab = self
+                .prefabs
+                .get(path)
+                .ok_or_else(|| SceneError::MissingPrefab(path.clone()))?;
+            stack.push(path.clone());
+            let inner = self.expand(prefab, stack)?;
+            stack.pop();
+
+            // Prefab roots hang off the instance entity
+            let start = out.len();
+            out.extend(inner.into_iter().map(|x| Expanded {
+                chain: prepend(e.id, &x.chain),
+                parent: Some(match x.parent {
+                    Some(parent) => prepend(e.id, &parent),
+                    None => vec![e.id],
+                }),
+                entity: x.entity,
+            }));
+
+            for o in &prefab_ref.overrides {
+                let target = out[start..]
+                    .iter_mut()
+                    .find(|x| x.chain[1..] == o.entity[..])
+                    .ok_or_else(|| bad_override(o, "no such entity in prefab".into()))?;
+                apply_override(&mut target.entity, o)?;
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn prepend(id: EntityUid, chain: &[EntityUid]) -> Vec<EntityUid> {
+    let mut out = Vec::with_capacity(chain.len() + 1);
+    out.push(id);
+    out.extend_from_slice(chain);
+    out
+}
+
+fn bad_override(o: &PrefabOverride, reason: String) -> SceneError {
+    SceneError::BadOverride {
+        entity: o.entity.clone(),
+        field: o.field.clone(),
+        reason,
+    }
+}
+
+fn apply_override(entity: &mut Entity, o: &PrefabOverride) -> Result<(), SceneError> {
+    let path: Vec<&str> = o.field.split('.').collect();
+    let (head, rest) = (path[0], &path[1..]);
+    if STRUCTURAL_FIELDS.contains(&head) {
+        return Err(bad_override(o, "field can't be overridden".into()));
+    }
+    if head == "name" {
+        entity.name = match (&o.value, rest) {
+            (SceneValue::String(name), []) => name.clone(),
+            _ => return Err(bad_override(o, "name must be a string".into())),
+        };
+        return Ok(());
+    }
+    match (entity.components.get_mut(head), rest) {
+        (_, []) if o.value == SceneValue::Option(None) => {
+            entity.components.remove(head);
+        }
+        (_, []) => {
+            entity.components.insert(head.to_string(), o.value.clone());
+        }
+        (Some(component), rest) => {
+            set_path(component, rest, o.value.clone()).map_err(|reason| bad_override(o, reason))?
+        }
+        (None, _) => return Err(bad_override(o, format!("component '{head}' is not set"))),
+    }
+    Ok(())
+}
+
+/// Set the value at `path` inside a component. Options are stepped through, so "node.0" reaches
+/// into `Some(..)`; a plain value assigned to an optional field is wrapped in `Some`.
+pub(crate) fn set_path(
+    target: &mut SceneValue,
+    path: &[&str],
+    value: SceneValue,
+) -> Result<(), String> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = match (&*target, value) {
+            (SceneValue::Option(_), value @ SceneValue::Option(_)) => value,
+            (SceneValue::Option(_), value) => SceneValue::Option(Some(Box::new(value))),
+            (_, value) => value,
+        };
+        return Ok(());
+    };
+    match target {
+        SceneValue::Option(Some(inner)) => set_path(inner, path, value),
+        SceneValue::Option(None) => Err(format!("'{segment}' is inside a field that is not set")),
+        SceneValue::Seq(items) | SceneValue::Tuple(items) => {
+            let item = segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .ok_or_else(|| format!("no element '{segment}'"))?;
+            set_path(item, rest, value)
+        }
+        SceneValue::Named(_, inner) if matches!(**inner, SceneValue::Tuple(_)) => {
+            set_path(inner, path, value)
+        }
+        SceneValue::Struct(_) | SceneValue::Map(_) | SceneValue::Named(..) => {
+            let field = target
+                .field_mut(segment)
+                .ok_or_else(|| format!("unknown field '{segment}'"))?;
+            set_path(field, rest, value)
+        }
+        _ => Err(format!("'{segment}' is not a field")),
+    }
+}
+
+/// Field paths and values where `edited` differs from `base`: the name and component fields.
+/// A removed component is recorded as `None`.
+fn diff_fields(base: &Entity, edited: &Entity) -> Vec<(String, SceneValue)> {
+    let mut out = Vec::new();
+    if base.name != edited.name {
+        out.push(("name".to_string(), SceneValue::String(edited.name.clone())));
+    }
+    let mut changes = Vec::new();
+    for (type_name, edited_value) in &edited.components {
+        match base.components.get(type_name) {
+            Some(base_value) => {
+                diff_value(base_value, edited_value, type_name.clone(), &mut changes)
+            }
+            None => out.push((type_name.clone(), edited_value.clone())),
+        }
+    }
+    out.extend(changes.into_iter().map(|(path, _, value)| (path, value)));
+    for type_name in base.components.keys() {
+        if !edited.components.contains_key(type_name) {
+            out.push((type_name.clone(), SceneValue::Option(None)));
+        }
+    }
+    out
+}
+
+/// Paths where `edited` differs from `base`, as `(path, old, new)`, as deep as both have the same
+/// struct fields; `set_path(path, new)` on `base` reproduces `edited`.
+pub(crate) fn diff_value(
+    base: &SceneValue,
+    edited: &SceneValue,
+    path: String,
+    out: &mut Vec<(String, SceneValue, SceneValue)>,
+) {
+    if base == edited {
+        return;
+    }
+    match (base, edited) {
+        (SceneValue::Option(Some(base)), SceneValue::Option(Some(edited))) => {
+            diff_value(base, edited, path, out)
+        }
+        (SceneValue::Named(base_name, base), SceneValue::Named(edited_name, edited))
+            if base_name == edited_name =>
+        {
+            diff_value(base, edited, path, out)
+        }
+        (SceneValue::Struct(base_fields), SceneValue::Struct(edited_fields))
+            if base_fields.len() == edited_fields.len()
+                && edited_fields.iter().all(|(f, _)| base.field(f).is_some()) =>
+        {
+            for (field, edited_value) in edited_fields {
+                let base_value = base.field(field).expect("checked above");
+                diff_value(base_value, edited_value, format!("{path}.{field}"), out);
+            }
+        }
+        (SceneValue::Map(base_map), SceneValue::Map(edited_map)) => {
+            let fields: Option<Vec<_>> = edited_map
+                .iter()
+                .map(|(key, edited_value)| match key {
+                    SceneValue::String(field) => base_map
+                        .iter()
+                        .find(|(k, _)| k == key)
+                        .map(|(_, base_value)| (field, base_value, edited_value)),
+                    _ => None,
+                })
+                .collect();
+            match fields {
+                Some(fields) if fields.len() == base_map.len() => {
+                    for (field, base_value, edited_value) in fields {
+                        diff_value(base_value, edited_value, format!("{path}.{field}"), out);
+                    }
+                }
+                _ => out.push((path, base.clone(), edited.clone())),
+            }
+        }
+        _ => out.push((path, base.clone(), edited.clone())),
+    }
+}
@@ -0,0 +1,188 @@
+/**
+ * path: /crates/engine_scene/src/reader.rs
+ * description: Reading a `Scene` out of a parsed RON document.
+ * The document is a `SceneValue` tree instead of serde input, so component and override values
+ * keep their enum variants and struct names. Fields the format doesn't have are ignored here
+ * (validation warns about them).
+ */
+use std::collections::BTreeMap;
+
+use crate::locate::PathSegment;
+use crate::{
+    Entity,
+    EntityUid,
+    PrefabOverride,
+    PrefabRef,
+    Scene,
+    SceneValue,
+};
+
+/// Path of the value that couldn't be read, and why.
+pub(crate) type ReadError = (Vec<PathSegment>, String);
+
+pub(crate) fn read_scene(doc: &SceneValue) -> Result<Scene, ReadError> {
+    let path = Vec::new();
+    record(doc, &path)?;
+    let entities = required(doc, "entities", &path)?;
+    let entities_path = child(&path, "entities");
+    Ok(Scene {
+        version: match doc.field("version") {
+            Some(version) => read_u32(version, &child(&path, "version"))?,
+            None => 0,
+        },
+        name: read_string(required(doc, "name", &path)?, &child(&path, "name"))?,
+        entities: list(entities, &entities_path)?
+            .iter()
+            .enumerate()
+            .map(|(i, e)| read_entity(e, &index(&entities_path, i)))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn read_entity(value: &SceneValue, path: &[PathSegment]) -> Result<Entity, ReadError> {
+    record(value, path)?;
+    let parent = value.field("parent").and_then(optional);
+    let prefab = value.field("prefab").and_then(optional);
+    Ok(Entity {
+        id: read_uid(required(value, "id", path)?, &child(path, "id"))?,
+        name: read_string(required(value, "name", path)?, &child(path, "name"))?,
+        parent: parent
+            .map(|parent| read_uid(parent, &child(path, "parent")))
+            .transpose()?,
+        prefab: prefab
+            .map(|prefab| read_prefab(prefab, &child(path, "prefab")))
+            .transpose()?,
+        components: match value.field("components") {
+            Some(components) => read_components(components, &child(path, "components"))?,
+            None => BTreeMap::new(),
+        },
+    })
+}
+
+fn read_components(
+    value: &SceneValue,
+    path: &[PathSegment],
+) -> Result<BTreeMap<String, SceneValue>, ReadError> {
+    match value {
+        SceneValue::Map(entries) => entries
+            .iter()
+            .map(|(name, value)| match name {
+                SceneValue::String(name) => Ok((name.clone(), value.clone())),
+                _ => Err((path.to_vec(), "component names must be strings".into())),
+            })
+            .collect(),
+        // Migrated documents, where `{"Sprite": ..}` can't be told from `(Sprite: ..)`
+        SceneValue::Struct(fields) => Ok(fields.iter().cloned().collect()),
+        _ => Err((path.to_vec(), "expected a map of components".into())),
+    }
+}
+
+fn read_prefab(value: &SceneValue, path: &[PathSegment]) -> Result<PrefabRef, ReadError> {
+    record(value, path)?;
+    let overrides_path = child(path, "overrides");
+    Ok(PrefabRef {
+        path: read_string(required(value, "path", path)?, &child(path, "path"))?,
+        overrides: match value.field("overrides") {
+            Some(overrides) => list(overrides, &overrides_path)?
+                .iter()
+                .enumerate()
+                .map(|(i, o)| read_override(o, &index(&overrides_path, i)))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        },
+    })
+}
+
+fn read_override(value: &SceneValue, path: &[PathSegment]) -> Result<PrefabOverride, ReadError> {
+    record(value, path)?;
+    let entity_path = child(path, "entity");
+    Ok(PrefabOverride {
+        entity: list(required(value, "entity", path)?, &entity_path)?
+            .iter()
+            .enumerate()
+            .map(|(i, id)| read_uid(id, &index(&entity_path, i)))
+            .collect::<Result<_, _>>()?,
+        field: read_string(required(value, "field", path)?, &child(path, "field"))?,
+        value: required(value, "value", path)?.clone(),
+    })
+}
+
+fn child(path: &[PathSegment], name: &str) -> Vec<PathSegment> {
+    let mut path = path.to_vec();
+    path.push(PathSegment::Field(name.to_string()));
+    path
+}
+
+fn index(path: &[PathSegment], i: usize) -> Vec<PathSegment> {
+    let mut path = path.to_vec();
+    path.push(PathSegment::Index(i));
+    path
+}
+
+/// `(field: ..)`, `Name(field: ..)` or `{"field": ..}`.
+fn record(value: &SceneValue, path: &[PathSegment]) -> Result<(), ReadError> {
+    match value {
+        SceneValue::Struct(_) | SceneValue::Map(_) => Ok(()),
+        SceneValue::Named(_, inner) if matches!(**inner, SceneValue::Struct(_)) => Ok(()),
+        _ => Err((path.to_vec(), "expected a struct".into())),
+    }
+}
+
+fn required<'v>(
+    value: &'v SceneValue,
+    name: &str,
+    path: &[PathSegment],
+) -> Result<&'v SceneValue, ReadError> {
+    value
+        .field(name)
+        .ok_or_else(|| (path.to_vec(), format!("missing field `{name}`")))
+}
+
+/// `None`, `Some(x)` or (`implicit_some`) a bare `x`.
+fn optional(value: &SceneValue) -> Option<&SceneValue> {
+    match value {
+        SceneValue::Option(inner) => inner.as_deref(),
+        value => Some(value),
+    }
+}
+
+fn list<'v>(value: &'v SceneValue, path: &[PathSegment]) -> Result<&'v [SceneValue], ReadError> {
+    match value {
+        SceneValue::Seq(items) | SceneValue::Tuple(items) => Ok(items),
+        SceneValue::Unit => Ok(&[]),
+        _ => Err((path.to_vec(), "expected a list".into())),
+    }
+}
+
+fn read_string(value: &SceneValue, path: &[PathSegment]) -> Result<String, ReadError> {
+    match value {
+        SceneValue::String(s) => Ok(s.clone()),
+        _ => Err((path.to_vec(), "expected a string".into())),
+    }
+}
+
+fn read_u32(value: &SceneValue, path: &[PathSegment]) -> Result<u32, ReadError> {
+    match value {
+        SceneValue::Integer(i) => {
+            u32::try_from(*i).map_err(|_| (path.to_vec(), format!("{i} is out of range")))
+        }
+        _ => Err((path.to_vec(), "expected a non-negative integer".into())),
+    }
+}
+
+/// Same rules as `EntityUid`'s `Deserialize`.
+fn read_uid(value: &SceneValue, path: &[PathSegment]) -> Result<EntityUid, ReadError> {
+    let reason = match value {
+        SceneValue::Integer(i) if *i < 0 => "entity id must not be negative".to_string(),
+        SceneValue::Integer(i) => match u32::try_from(*i) {
+            Ok(id) => return Ok(id.into()),
+            Err(_) => "entity id is too large; use a UUID string".to_string(),
+        },
+        SceneValue::String(s) => match s.parse() {
+            Ok(uid) => return Ok(uid),
+            Err(e) => e.to_string(),
+        },
+        _ => "expected an entity id (non-negative integer or UUID string)".to_string(),
+    };
+    Err((path.to_vec(), reason))
+}
@@ -0,0 +1,238 @@
+/**
+ * path: /crates/engine_scene/src/spawn.rs
+ * description: Instantiate a `Scene` into an ECS world.
+ * Each scene entity becomes one ECS entity carrying its components (through the world's
+ * `ComponentRegistry`), plus `Parent`/`Children` for the authored hierarchy and global transforms.
+ */
+use bevy_ecs::entity::Entity as EcsEntity;
+use bevy_ecs::prelude::{
+    Component,
+    EntityWorldMut,
+    Mut,
+    ReflectComponent,
+    World,
+};
+use bevy_ecs::system::RunSystemOnce;
+use bevy_reflect::std_traits::ReflectDefault;
+use bevy_reflect::Reflect;
+use std::collections::HashMap;
+
+use crate::{
+    propagate_transforms,
+    Children,
+    ComponentRegistry,
+    Entity,
+    EntityMap,
+    EntityUid,
+    GlobalTransform2D,
+    GlobalTransform3D,
+    Parent,
+    PrefabRef,
+    Scene,
+    SceneError,
+    Transform2D,
+    Transform3D,
+    UnknownComponents,
+};
+
+/// Authored scene id of a spawned entity.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct SceneEntityId(pub EntityUid);
+
+/// Authored display name of a spawned entity.
+#[derive(Component, Reflect, Debug, Clone, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct Name(pub String);
+
+/// Marks the root entity of a prefab instance with the prefab's path.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct PrefabInstance(pub String);
+
+/// The ECS entities created for one scene, keyed by scene id.
+#[derive(Debug, Default, Clone)]
+pub struct SceneInstance {
+    pub entities: HashMap<EntityUid, EcsEntity>,
+}
+
+impl SceneInstance {
+    pub fn get(&self, id: EntityUid) -> Option<EcsEntity> {
+        self.entities.get(&id).copied()
+    }
+}
+
+/// Spawn every scene entity into `world`, link the hierarchy and compute global transforms.
+/// Components are created through the world's `ComponentRegistry` (the built-in one is inserted if
+/// there is none); unregistered ones end up in `UnknownComponents`. On invalid component data
+/// nothing stays spawned. Spawned entities are recorded in the world's `EntityMap`.
+/// The scene is expected to be validated (`Scene::validate_hierarchy`); unknown parents are ignored.
+/// Prefab instances must already be resolved (`PrefabLibrary::resolve`).
+pub fn spawn_scene(world: &mut World, scene: &Scene) -> Result<SceneInstance, SceneError> {
+    let mut instance = SceneInstance::default();
+    world.get_resource_or_insert_with(ComponentRegistry::default);
+    let spawned = world.resource_scope(|world, registry: Mut<ComponentRegistry>| {
+        for entity in &scene.entities {
+            let e = spawn_entity(world, &registry, entity)?;
+            instance.entities.insert(entity.id, e);
+        }
+        Ok(())
+    });
+    if let Err(e) = spawned {
+        despawn_scene(world, &instance);
+        return Err(e);
+    }
+    let mut map = world.get_resource_or_insert_with(EntityMap::default);
+    for (uid, entity) in &instance.entities {
+        map.insert(*uid, *entity);
+    }
+
+    for entity in &scene.entities {
+        let (Some(parent_id), Some(child)) = (entity.parent, instance.get(entity.id)) else {
+            continue;
+        };
+        if let Some(parent) = instance.get(parent_id) {
+            link_child(world, parent, child);
+        }
+    }
+
+    // Globals are valid right away, not only after the next PostUpdate
+    world.run_system_once(propagate_transforms);
+    Ok(instance)
+}
+
+/// Spawn one scene entity with its components, without hierarchy links. Nothing stays spawned
+/// on invalid component data.
+pub(crate) fn spawn_entity(
+    world: &mut World,
+    registry: &ComponentRegistry,
+    entity: &Entity,
+) -> Result<EcsEntity, SceneError> {
+    let mut e = world.spawn((SceneEntityId(entity.id), Name(entity.name.clone())));
+    let mut unknown = UnknownComponents::default();
+    for (type_name, value) in &entity.components {
+        let Some(registration) = registry.get(type_name) else {
+            unknown.0.insert(type_name.clone(), value.clone());
+            continue;
+        };
+        if let Err(reason) = registration.insert(&mut e, value) {
+            e.despawn();
+            return Err(SceneError::Component {
+                entity: entity.id,
+                component: type_name.clone(),
+                reason,
+            });
+        }
+    }
+    if !unknown.0.is_empty() {
+        e.insert(unknown);
+    }
+    insert_globals(&mut e);
+    if let Some(prefab) = &entity.prefab {
+        e.insert(PrefabInstance(prefab.path.clone()));
+    }
+    Ok(e.id())
+}
+
+/// A spawned entity as a scene entity, as it is now: its registered and unknown components,
+/// name, parent and prefab link (without overrides, instances are spawned resolved). `None` if it
+/// isn't a scene entity (has no `SceneEntityId`).
+pub fn extract_entity(world: &World, entity: EcsEntity) -> Option<Result<Entity, SceneError>> {
+    let e = world.get_entity(entity)?;
+    let id = e.get::<SceneEntityId>()?.0;
+    let default_registry;
+    let registry = match world.get_resource::<ComponentRegistry>() {
+        Some(registry) => registry,
+        None => {
+            default_registry = ComponentRegistry::default();
+            &default_registry
+        }
+    };
+    let mut components = e
+        .get::<UnknownComponents>()
+        .map(|unknown| unknown.0.clone())
+        .unwrap_or_default();
+    for registration in registry.iter() {
+        match registration.extract(&e) {
+            Some(Ok(value)) => {
+                components.insert(registration.type_name.to_string(), value);
+            }
+            Some(Err(reason)) => {
+                return Some(Err(SceneError::Component {
+                    entity: id,
+                    component: registration.type_name.to_string(),
+                    reason,
+                }));
+            }
+            None => {}
+        }
+    }
+    Some(Ok(Entity {
+        id,
+        name: e
+            .get::<Name>()
+            .map(|name| name.0.clone())
+            .unwrap_or_default(),
+        parent: e
+            .get::<Parent>()
+            .and_then(|parent| world.get::<SceneEntityId>(parent.0))
+            .map(|parent| parent.0),
+        prefab: e.get::<PrefabInstance>().map(|prefab| PrefabRef {
+            path: prefab.0.clone(),
+            overrides: Vec::new(),
+        }),
+        components,
+    }))
+}
+
+/// Give an entity with local transforms matching global ones (refined by `propagate_transforms`),
+/// and drop globals whose local transform is gone.
+pub(crate) fn insert_globals(e: &mut EntityWorldMut) {
+    match e.get::<Transform2D>().map(Transform2D::to_affine) {
+        Some(affine) => {
+            e.insert(GlobalTransform2D(affine));
+        }
+        None => {
+            e.remove::<GlobalTransform2D>();
+        }
+    }
+    match e.get::<Transform3D>().map(Transform3D::to_matrix) {
+        Some(matrix) => {
+            e.insert(GlobalTransform3D(matrix));
+        }
+        None => {
+            e.remove::<GlobalTransform3D>();
+        }
+    }
+}
+
+/// Attach `child` to `parent` (`Parent` + `Children`).
+pub(crate) fn link_child(world: &mut World, parent: EcsEntity, child: EcsEntity) {
+    world.entity_mut(child).insert(Parent(parent));
+    let mut parent = world.entity_mut(parent);
+    match parent.get_mut::<Children>() {
+        Some(mut children) => children.0.push(child),
+        None => {
+            parent.insert(Children(vec![child]));
+        }
+    }
+}
+
+/// Detach `child` from its parent, if it has one.
+pub(crate) fn unlink_child(world: &mut World, child: EcsEntity) {
+    let Some(Parent(parent)) = world.entity_mut(child).take::<Parent>() else {
+        return;
+    };
+    if let Some(mut children) = world.get_mut::<Children>(parent) {
+        children.0.retain(|c| *c != child);
+    }
+}
+
+/// Despawn everything `spawn_scene` created for `instance`.
+pub fn despawn_scene(world: &mut World, instance: &SceneInstance) {
+    for entity in instance.entities.values() {
+        world.despawn(*entity);
+        if let Some(mut map) = world.get_resource_mut::<EntityMap>() {
+            map.remove(*entity);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+/**
+ * path: /crates/engine_scene/src/uid.rs
+ * description: Stable entity identifiers.
+ * Scene entities are identified by `EntityUid`, a UUID that survives saving, editing, merging and
+ * prefab instancing. Small ids (`id: 3`) remain valid in RON and map to UUIDs with only the low
+ * bits set, so hand-written scenes stay readable; tools create random ones. `EntityMap` links
+ * uids to runtime ECS entities.
+ */
+use bevy_ecs::entity::Entity as EcsEntity;
+use bevy_ecs::prelude::Resource;
+use bevy_reflect::std_traits::ReflectDefault;
+use bevy_reflect::{
+    Reflect,
+    ReflectDeserialize,
+    ReflectSerialize,
+};
+use serde::de::{
+    self,
+    Visitor,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{
+    ComponentRegistry,
+    Entity,
+    Scene,
+    SceneError,
+};
+
+/// Identifier of a scene entity, unique across scenes.
+/// Written as a plain integer when it is a small id (`EntityUid::from(3)`), otherwise as a
+/// hyphenated UUID string. Components can hold `EntityUid` fields to reference other entities;
+/// those are remapped whenever entities get new ids (prefab instances, `Scene::paste`).
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[reflect_value(PartialEq, Hash, Serialize, Deserialize, Default)]
+pub struct EntityUid(Uuid);
+
+impl EntityUid {
+    /// A new random (v4) uid.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub fn uuid(&self) -> Uuid {
+        self.0
+    }
+
+    /// The small id this uid was made from, if any.
+    pub fn as_small(&self) -> Option<u32> {
+        u32::try_from(self.0.as_u128()).ok()
+    }
+
+    /// Deterministic uid for `child` inside an instance rooted at `self` (name-based v5 UUID),
+    /// so instancing the same prefab twice gives distinct ids and re-resolving gives the same ones.
+    pub fn derive(&self, child: EntityUid) -> Self {
+        Self(Uuid::new_v5(&self.0, child.0.as_bytes()))
+    }
+}
+
+impl From<u32> for EntityUid {
+    fn from(id: u32) -> Self {
+        Self(Uuid::from_u128(id as u128))
+    }
+}
+
+impl FromStr for EntityUid {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<u32>() {
+            Ok(id) => Ok(id.into()),
+            Err(_) => Uuid::parse_str(s).map(Self),
+        }
+    }
+}
+
+impl fmt::Display for EntityUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_small() {
+            Some(id) => write!(f, "{id}"),
+            None => write!(f, "{}", self.0.hyphenated()),
+        }
+    }
+}
+
+impl fmt::Debug for EntityUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EntityUid({self})")
+    }
+}
+
+impl Serialize for EntityUid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.as_small() {
+            Some(id) => serializer.serialize_u32(id),
+            None => serializer.collect_str(&self.0.hyphenated()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityUid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UidVisitor;
+
+        impl Visitor<'_> for UidVisitor {
+            type Value = EntityUid;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an entity id (non-negative integer or UUID string)")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<EntityUid, E> {
+                u32::try_from(v)
+                    .map(EntityUid::from)
+                    .map_err(|_| E::custom("entity id is too large; use a UUID string"))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<EntityUid, E> {
+                u64::try_from(v)
+                    .map_err(|_| E::custom("entity id must not be negative"))
+                    .and_then(|v| self.visit_u64(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<EntityUid, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(UidVisitor)
+    }
+}
+
+/// Runtime lookup between scene uids and spawned ECS entities, kept up to date by
+/// `spawn_scene` / `despawn_scene`. Use it to follow `EntityUid` references in components.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct EntityMap {
+    entities: HashMap<EntityUid, EcsEntity>,
+    uids: HashMap<EcsEntity, EntityUid>,
+}
+
+impl EntityMap {
+    pub fn insert(&mut self, uid: EntityUid, entity: EcsEntity) {
+        if let Some(old) = self.entities.insert(uid, entity) {
+            self.uids.remove(&old);
+        }
+        self.uids.insert(entity, uid);
+    }
+
+    pub fn remove(&mut self, entity: EcsEntity) -> Option<EntityUid> {
+        let uid = self.uids.remove(&entity)?;
+        self.entities.remove(&uid);
+        Some(uid)
+    }
+
+    pub fn get(&self, uid: EntityUid) -> Option<EcsEntity> {
+        self.entities.get(&uid).copied()
+    }
+
+    pub fn uid(&self, entity: EcsEntity) -> Option<EntityUid> {
+        self.uids.get(&entity).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityUid, EcsEntity)> + '_ {
+        self.entities.iter().map(|(uid, entity)| (*uid, *entity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// Rewrite the `EntityUid` fields of `entity`'s registered components (and its parent) with `map`.
+/// Unregistered components are left as they are.
+pub fn remap_entity(
+    entity: &mut Entity,
+    components: &ComponentRegistry,
+    map: &mut dyn FnMut(EntityUid) -> EntityUid,
+) -> Result<(), SceneError> {
+    entity.parent = entity.parent.map(&mut *map);
+    for (type_name, value) in entity.components.iter_mut() {
+        let Some(registration) = components.get(type_name) else {
+            continue;
+        };
+        let mapped = registration
+            .map_uids(value, map)
+            .map_err(|reason| SceneError::Component {
+                entity: entity.id,
+                component: type_name.clone(),
+                reason,
+            })?;
+        if let Some(mapped) = mapped {
+            *value = mapped;
+        }
+    }
+    Ok(())
+}
+
+impl Scene {
+    /// Add copies of `entities` (e.g. from the clipboard or another scene) with fresh uids.
+    /// Parents and entity references within the pasted set follow the copies; pasted entities
+    /// whose parent isn't part of the set are attached to `parent`. References to entities
+    /// outside the set are kept. Returns the new uid of every pasted entity by its old one.
+    pub fn paste(
+        &mut self,
+        entities: &[Entity],
+        parent: Option<EntityUid>,
+        components: &ComponentRegistry,
+    ) -> Result<HashMap<EntityUid, EntityUid>, SceneError> {
+        let ids: HashMap<EntityUid, EntityUid> =
+            entities.iter().map(|e| (e.id, EntityUid::new())).collect();
+        let mut pasted = Vec::with_capacity(entities.len());
+        for e in entities {
+            let mut copy = e.clone();
+            copy.id = ids[&e.id];
+            if copy.parent.is_some_and(|p| !ids.contains_key(&p)) {
+                copy.parent = None;
+            }
+            remap_entity(&mut copy, components, &mut |uid| {
+                ids.get(&uid).copied().unwrap_or(uid)
+            })?;
+            if copy.parent.is_none() {
+                copy.parent = parent;
+            }
+            pasted.push(copy);
+        }
+        self.entities.extend(pasted);
+        Ok(ids)
+    }
+}
@@ -0,0 +1,533 @@
+/**
+ * path: /crates/engine_scene/src/validate.rs
+ * description: Scene loading with a validation pass.
+ * All problems of a document are collected at once as diagnostics with file/line/column, so a bad
+ * scene fails to load with a full report instead of misbehaving later in the render loop.
+ */
+use std::fmt;
+
+use crate::locate::{
+    PathSegment,
+    Position,
+    SourceMap,
+};
+use crate::reader::read_scene;
+use crate::{
+    scene_version,
+    ComponentRegistry,
+    EntityUid,
+    Mesh,
+    MigrationRegistry,
+    Scene,
+    SceneComponent,
+    SceneError,
+    SceneFormat,
+    SceneValue,
+    Sprite,
+    Text,
+    CURRENT_SCENE_VERSION,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a scene.
+/// - `path`: value path, e.g. "entities[2].components.Sprite.color"
+/// - `position`: where it is in the source text, when loaded from text
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+    pub position: Option<Position>,
+}
+
+/// All diagnostics of one document.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    pub file: Option<String>,
+    pub items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items
+            .iter()
+            .filter(|d| d.severity == Severity::Warning)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}: {}", self.path, self.message)
+    }
+}
+
+/// One diagnostic per line: `file:line:column: error: path: message`.
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<scene>");
+        for (i, d) in self.items.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match d.position {
+                Some(p) => write!(f, "{file}:{}:{}: {d}", p.line, p.column)?,
+                None => write!(f, "{file}: {d}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+const SCENE_FIELDS: &[&str] = &["version", "name", "entities"];
+const ENTITY_FIELDS: &[&str] = &["id", "name", "parent", "prefab", "components"];
+const PREFAB_FIELDS: &[&str] = &["path", "overrides"];
+
+/// Parses, migrates and validates scene text.
+/// ```ignore
+/// let (scene, warnings) = SceneLoader::new()
+///     .file("assets/level1.scene.ron")
+///     .asset_exists(&|path| std::path::Path::new(path).exists())
+///     .load(&text)?;
+/// ```
+#[derive(Default)]
+pub struct SceneLoader<'a> {
+    file: Option<String>,
+    migrations: Option<&'a MigrationRegistry>,
+    components: Option<&'a ComponentRegistry>,
+    asset_exists: Option<&'a dyn Fn(&str) -> bool>,
+}
+
+impl<'a> SceneLoader<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// File name used in diagnostics.
+    pub fn file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn migrations(mut self, migrations: &'a MigrationRegistry) -> Self {
+        self.migrations = Some(migrations);
+        self
+    }
+
+    /// Component types to check against; the built-in ones by default.
+    pub fn components(mut self, components: &'a ComponentRegistry) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    /// Enables the unknown-asset check for `mesh.file` and `prefab.path`.
+    pub fn asset_exists(mut self, asset_exists: &'a dyn Fn(&str) -> bool) -> Self {
+        self.asset_exists = Some(asset_exists);
+        self
+    }
+
+    /// On success the diagnostics contain only warnings; any error fails with `SceneError::Invalid`.
+    pub fn load(&self, text: &str) -> Result<(Scene, Diagnostics), SceneError> {
+        let mut doc: ron::Value = ron::from_str(text)?;
+        let migrated = scene_version(&doc)? != CURRENT_SCENE_VERSION;
+        let source = SourceMap::new(text);
+        let scene = if migrated {
+            let default_migrations;
+            let migrations = match self.migrations {
+                Some(m) => m,
+                None => {
+                    default_migrations = MigrationRegistry::default();
+                    &default_migrations
+                }
+            };
+            migrations.migrate(&mut doc)?;
+            read_scene(&doc.into()).map_err(|(path, reason)| {
+                SceneError::Migrated(ron::Error::Message(format!(
+                    "{}: {reason}",
+                    format_path(&path)
+                )))
+            })?
+        } else {
+            // Read the text itself: `ron::Value` drops enum variant names
+            read_scene(&SceneValue::parse(text)?).map_err(|(path, message)| {
+                SceneError::Invalid(Diagnostics {
+                    file: self.file.clone(),
+                    items: vec![Diagnostic {
+                        severity: Severity::Error,
+                        message,
+                        position: source.position(&path),
+                        path: format_path(&path),
+                    }],
+                })
+            })?
+        };
+
+        let mut diagnostics = Diagnostics {
+            file: self.file.clone(),
+            items: Vec::new(),
+        };
+        let default_components;
+        let components = match self.components {
+            Some(c) => c,
+            None => {
+                default_components = ComponentRegistry::default();
+                &default_components
+            }
+        };
+        let mut validator = Validator {
+            source: Some(&source),
+            components,
+            out: &mut diagnostics.items,
+        };
+        validator.check_scene(&scene, self.asset_exists);
+        // Field names of migrated documents don't match the current format
+        if !migrated {
+            validator.check_unused_fields(&scene);
+        }
+        // Report in document order
+        diagnostics
+            .items
+            .sort_by_key(|d| d.position.map_or((usize::MAX, 0), |p| (p.line, p.column)));
+
+        if diagnostics.has_errors() {
+            return Err(SceneError::Invalid(diagnostics));
+        }
+        Ok((scene, diagnostics))
+    }
+
+    /// Decode and validate a binary scene (`Scene::to_bytes`). Diagnostics have no positions;
+    /// migrations don't apply.
+    pub fn load_binary(&self, bytes: &[u8]) -> Result<(Scene, Diagnostics), SceneError> {
+        let scene = Scene::from_bytes(bytes)?;
+        let default_components;
+        let components = match self.components {
+            Some(c) => c,
+            None => {
+                default_components = ComponentRegistry::default();
+                &default_components
+            }
+        };
+        let mut diagnostics = scene.validate(components, self.asset_exists);
+        diagnostics.file = self.file.clone();
+        if diagnostics.has_errors() {
+            return Err(SceneError::Invalid(diagnostics));
+        }
+        Ok((scene, diagnostics))
+    }
+
+    /// `load` or `load_binary` depending on `format`; RON must be UTF-8.
+    pub fn load_as(
+        &self,
+        format: SceneFormat,
+        bytes: &[u8],
+    ) -> Result<(Scene, Diagnostics), SceneError> {
+        match format {
+            SceneFormat::Binary => self.load_binary(bytes),
+            SceneFormat::Ron => self.load(std::str::from_utf8(bytes)?),
+        }
+    }
+}
+
+impl Scene {
+    /// Validate an in-memory scene (no source positions; unknown fields only inside components).
+    pub fn validate(
+        &self,
+        components: &ComponentRegistry,
+        asset_exists: Option<&dyn Fn(&str) -> bool>,
+    ) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        Validator {
+            source: None,
+            components,
+            out: &mut diagnostics.items,
+        }
+        .check_scene(self, asset_exists);
+        diagnostics
+    }
+}
+
+struct Validator<'s, 'o> {
+    source: Option<&'s SourceMap<'s>>,
+    components: &'s ComponentRegistry,
+    out: &'o mut Vec<Diagnostic>,
+}
+
+fn field(name: &str) -> PathSegment {
+    PathSegment::Field(name.to_string())
+}
+
+fn entity_path(i: usize, rest: &[&str]) -> Vec<PathSegment> {
+    let mut path = vec![field("entities"), PathSegment::Index(i)];
+    path.extend(rest.iter().map(|f| field(f)));
+    path
+}
+
+fn format_path(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Field(name) if out.is_empty() => out.push_str(name),
+            PathSegment::Field(name) => {
+                out.push('.');
+                out.push_str(name);
+            }
+            PathSegment::Index(i) => out.push_str(&format!("[{i}]")),
+        }
+    }
+    out
+}
+
+fn component_path(i: usize, type_name: &str, rest: &[&str]) -> Vec<PathSegment> {
+    let mut path = entity_path(i, &["components", type_name]);
+    path.extend(rest.iter().map(|f| field(f)));
+    path
+}
+
+impl Validator<'_, '_> {
+    fn report(&mut self, severity: Severity, path: Vec<PathSegment>, message: String) {
+        self.out.push(Diagnostic {
+            severity,
+            path: format_path(&path),
+            message,
+            position: self.source.and_then(|s| s.position(&path)),
+        });
+    }
+
+    fn error(&mut self, path: Vec<PathSegment>, message: String) {
+        self.report(Severity::Error, path, message);
+    }
+
+    fn check_scene(&mut self, scene: &Scene, asset_exists: Option<&dyn Fn(&str) -> bool>) {
+        self.check_hierarchy(scene);
+
+        for (i, e) in scene.entities.iter().enumerate() {
+            for (type_name, value) in &e.components {
+                let path = component_path(i, type_name, &[]);
+                let Some(registration) = self.components.get(type_name) else {
+                    self.report(
+                        Severity::Warning,
+                        path,
+                        "unknown component is kept but not spawned".into(),
+                    );
+                    continue;
+                };
+                match registration.normalize(value) {
+                    Ok(normalized) => self.check_unknown_fields(value, &normalized, path),
+                    Err(reason) => {
+                        self.error(path, format!("invalid {type_name}: {reason}"));
+                        continue;
+                    }
+                }
+                for required in registration.requires {
+                    if !e.has_component(required) {
+                        self.error(
+                            component_path(i, type_name, &[]),
+                            format!("{type_name} needs a {required} component"),
+                        );
+                    }
+                }
+            }
+
+            if let Some(sprite) = e.component::<Sprite>() {
+                let (w, h) = sprite.dimensions;
+                if !(w >= 0.0 && h >= 0.0) {
+                    self.error(
+                        component_path(i, Sprite::TYPE_NAME, &["dimensions"]),
+                        format!("dimensions must not be negative, got ({w}, {h})"),
+                    );
+                }
+                self.check_color(i, Sprite::TYPE_NAME, sprite.color);
+            }
+            if let Some(text) = e.component::<Text>() {
+                if text.size.is_nan() || text.size <= 0.0 {
+                    self.error(
+                        component_path(i, Text::TYPE_NAME, &["size"]),
+                        format!("size must be positive, got {}", text.size),
+                    );
+                }
+                self.check_color(i, Text::TYPE_NAME, text.color);
+            }
+
+            let Some(asset_exists) = asset_exists else {
+                continue;
+            };
+            if let Some(mesh) = e.component::<Mesh>().filter(|m| !asset_exists(&m.file)) {
+                self.error(
+                    component_path(i, Mesh::TYPE_NAME, &["file"]),
+                    format!("unknown asset \"{}\"", mesh.file),
+                );
+            }
+            if let Some(prefab) = e.prefab.as_ref().filter(|p| !asset_exists(&p.path)) {
+                self.error(
+                    entity_path(i, &["prefab", "path"]),
+                    format!("unknown prefab \"{}\"", prefab.path),
+                );
+            }
+        }
+    }
+
+    /// Every channel of a component's `color` within 0..1.
+    fn check_color(&mut self, i: usize, type_name: &str, color: (f32, f32, f32, f32)) {
+        let (r, g, b, a) = color;
+        for (k, c) in [r, g, b, a].into_iter().enumerate() {
+            if !(0.0..=1.0).contains(&c) {
+                let mut path = component_path(i, type_name, &["color"]);
+                path.push(PathSegment::Index(k));
+                self.error(
+                    path,
+                    format!("colour component must be within 0..1, got {c}"),
+                );
+            }
+        }
+    }
+
+    /// Fields of a component value that its type doesn't have (absent after `normalize`).
+    fn check_unknown_fields(
+        &mut self,
+        value: &SceneValue,
+        normalized: &SceneValue,
+        path: Vec<PathSegment>,
+    ) {
+        // Struct names are optional (`Sprite(color: ..)`) and `implicit_some` leaves out `Some`
+        let (value, normalized) = match (value, normalized) {
+            (SceneValue::Named(_, value), SceneValue::Named(_, normalized)) => {
+                (&**value, &**normalized)
+            }
+            (SceneValue::Named(_, value), normalized) => (&**value, normalized),
+            (value, SceneValue::Option(Some(normalized))) => match value {
+                SceneValue::Option(Some(value)) => (&**value, &**normalized),
+                value => (value, &**normalized),
+            },
+            pair => pair,
+        };
+        match (value, normalized) {
+            (
+                SceneValue::Seq(values) | SceneValue::Tuple(values),
+                SceneValue::Seq(normalized) | SceneValue::Tuple(normalized),
+            ) => {
+                for (k, (value, normalized)) in values.iter().zip(normalized).enumerate() {
+                    let mut item = path.clone();
+                    item.push(PathSegment::Index(k));
+                    self.check_unknown_fields(value, normalized, item);
+                }
+            }
+            (
+                SceneValue::Struct(_) | SceneValue::Map(_),
+                SceneValue::Struct(_) | SceneValue::Map(_),
+            ) => {
+                let fields: Vec<(&str, &SceneValue)> = match value {
+                    SceneValue::Struct(fields) => {
+                        fields.iter().map(|(name, v)| (name.as_str(), v)).collect()
+                    }
+                    SceneValue::Map(entries) => entries
+                        .iter()
+                        .filter_map(|(key, v)| match key {
+                            SceneValue::String(name) => Some((name.as_str(), v)),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                for (name, value) in fields {
+                    let mut item = path.clone();
+                    item.push(field(name));
+                    match normalized.field(name) {
+                        Some(normalized) => self.check_unknown_fields(value, normalized, item),
+                        None => {
+                            self.report(Severity::Warning, item, "unknown field is ignored".into())
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_hierarchy(&mut self, scene: &Scene) {
+        let mut first_index = std::collections::HashMap::new();
+        let mut structural_errors = false;
+        for (i, e) in scene.entities.iter().enumerate() {
+            if let Some(first) = first_index.insert(e.id, i) {
+                first_index.insert(e.id, first);
+                structural_errors = true;
+                self.error(
+                    entity_path(i, &["id"]),
+                    format!("duplicate id {} (also used by entities[{first}])", e.id),
+                );
+            }
+        }
+        for (i, e) in scene.entities.iter().enumerate() {
+            if let Some(parent) = e.parent.filter(|p| !first_index.contains_key(p)) {
+                structural_errors = true;
+                self.error(
+                    entity_path(i, &["parent"]),
+                    format!("unknown parent {parent}"),
+                );
+            }
+        }
+        // Cycle detection needs unique ids and resolvable parents
+        if structural_errors {
+            return;
+        }
+        if let Err(SceneError::ParentCycle(cycle)) = scene.validate_hierarchy() {
+            let i = first_index[&cycle[0]];
+            let ids: Vec<String> = cycle.iter().map(EntityUid::to_string).collect();
+            self.error(
+                entity_path(i, &["parent"]),
+                format!("parent cycle {}", ids.join(" -> ")),
+            );
+        }
+    }
+
+    fn check_unused_fields(&mut self, scene: &Scene) {
+        let Some(source) = self.source else {
+            return;
+        };
+        let mut unused = Vec::new();
+        for (name, position) in source.fields(&[]) {
+            if !SCENE_FIELDS.contains(&name) {
+                unused.push((name.to_string(), position));
+            }
+        }
+        for i in 0..scene.entities.len() {
+            for (name, position) in source.fields(&entity_path(i, &[])) {
+                if !ENTITY_FIELDS.contains(&name) {
+                    unused.push((format!("entities[{i}].{name}"), position));
+                }
+            }
+            for (name, position) in source.fields(&entity_path(i, &["prefab"])) {
+                if !PREFAB_FIELDS.contains(&name) {
+                    unused.push((format!("entities[{i}].prefab.{name}"), position));
+                }
+            }
+        }
+        for (path, position) in unused {
+            self.out.push(Diagnostic {
+                severity: Severity::Warning,
+                path,
+                message: "unknown field is ignored".into(),
+                position: Some(position),
+            });
+        }
+    }
+}
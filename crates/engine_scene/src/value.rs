@@ -0,0 +1,693 @@
+/**
+ * path: /crates/engine_scene/src/value.rs
+ * description: Generic values of scene data (component data, override values).
+ * Unlike `ron::Value`, a `SceneValue` keeps everything the RON text spells out: field names, enum
+ * variants, struct names and tuples vs lists, so a component reads back into its type unchanged.
+ */
+use ron::error::{
+    Position as RonPosition,
+    SpannedError,
+};
+use ron::value::{
+    Float,
+    Map,
+    Number,
+};
+use ron::Value;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::fmt::Write;
+
+use crate::locate::{
+    position_at,
+    Scanner,
+};
+
+/// One RON value as written.
+/// Its serde form is this enum itself (binary scenes, patches); RON text is read with `parse`
+/// and written with `to_ron` or `SceneWriter`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SceneValue {
+    /// `()`
+    Unit,
+    Bool(bool),
+    Char(char),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    /// `None`, `Some(..)`
+    Option(Option<Box<SceneValue>>),
+    /// `[a, b]`
+    Seq(Vec<SceneValue>),
+    /// `(a, b)`
+    Tuple(Vec<SceneValue>),
+    /// `{key: value}`
+    Map(Vec<(SceneValue, SceneValue)>),
+    /// `(field: value, ..)`
+    Struct(Vec<(String, SceneValue)>),
+    /// `Name`, `Name(a, b)` or `Name(field: ..)`: an enum variant or a named struct; the value is
+    /// `Unit`, a `Tuple` or a `Struct`.
+    Named(String, Box<SceneValue>),
+}
+
+/// Text layout for `SceneValue::write`.
+/// - `indent`: per nesting level; `None` writes one line without spaces (`(a:1,b:[2])`)
+/// - `implicit_some`: leave out `Some(..)`, for `#![enable(implicit_some)]` documents
+pub(crate) struct Layout<'a> {
+    pub indent: Option<&'a str>,
+    pub implicit_some: bool,
+}
+
+impl Layout<'_> {
+    const COMPACT: Layout<'static> = Layout {
+        indent: None,
+        implicit_some: false,
+    };
+
+    fn newline(&self, out: &mut String, level: usize) {
+        if let Some(indent) = self.indent {
+            out.push('\n');
+            for _ in 0..level {
+                out.push_str(indent);
+            }
+        }
+    }
+
+    fn separator(&self) -> &'static str {
+        match self.indent {
+            Some(_) => ", ",
+            None => ",",
+        }
+    }
+
+    fn colon(&self) -> &'static str {
+        match self.indent {
+            Some(_) => ": ",
+            None => ":",
+        }
+    }
+
+    /// `open`, one entry per line (comma after each), `close`; `open close` when empty.
+    fn block<T>(
+        &self,
+        out: &mut String,
+        level: usize,
+        (open, close): (char, char),
+        entries: &[T],
+        mut entry: impl FnMut(&mut String, &T, usize) -> Result<(), ron::Error>,
+    ) -> Result<(), ron::Error> {
+        out.push(open);
+        for (k, e) in entries.iter().enumerate() {
+            if k > 0 && self.indent.is_none() {
+                out.push(',');
+            }
+            self.newline(out, level + 1);
+            entry(out, e, level + 1)?;
+            if self.indent.is_some() {
+                out.push(',');
+            }
+        }
+        if !entries.is_empty() {
+            self.newline(out, level);
+        }
+        out.push(close);
+        Ok(())
+    }
+}
+
+impl SceneValue {
+    /// Parse one RON value (a whole document; `#![..]` attributes at the start are skipped).
+    pub fn parse(text: &str) -> Result<SceneValue, SpannedError> {
+        let mut parser = Parser {
+            text,
+            scanner: Scanner {
+                src: text.as_bytes(),
+                i: 0,
+            },
+        };
+        parser.scanner.skip_attributes();
+        let value = parser.value()?;
+        parser.scanner.skip_trivia();
+        if parser.scanner.i < text.len() {
+            return Err(parser.error("unexpected text after the value"));
+        }
+        Ok(value)
+    }
+
+    /// Compact RON, e.g. `(kind:Ranged,range:Some(4.5))`. Fails only for a `Struct` field or
+    /// `Named` name that isn't an identifier.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        let mut out = String::new();
+        self.write(&mut out, &Layout::COMPACT, 0)?;
+        Ok(out)
+    }
+
+    pub(crate) fn write(
+        &self,
+        out: &mut String,
+        layout: &Layout,
+        level: usize,
+    ) -> Result<(), ron::Error> {
+        match self {
+            SceneValue::Unit => out.push_str("()"),
+            SceneValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            SceneValue::Char(c) => {
+                out.push('\'');
+                if matches!(c, '\\' | '\'') {
+                    out.push('\\');
+                }
+                out.push(*c);
+                out.push('\'');
+            }
+            SceneValue::Integer(i) => {
+                let _ = write!(out, "{i}");
+            }
+            SceneValue::Float(f) => {
+                let _ = write!(out, "{f}");
+                if f.fract() == 0.0 {
+                    out.push_str(".0");
+                }
+            }
+            SceneValue::String(s) => {
+                out.push('"');
+                out.extend(s.chars().flat_map(char::escape_debug));
+                out.push('"');
+            }
+            SceneValue::Option(None) => out.push_str("None"),
+            SceneValue::Option(Some(inner))
+                if layout.implicit_some && !matches!(**inner, SceneValue::Option(_)) =>
+            {
+                inner.write(out, layout, level)?
+            }
+            SceneValue::Option(Some(inner)) => {
+                out.push_str("Some(");
+                inner.write(out, layout, level)?;
+                out.push(')');
+            }
+            SceneValue::Seq(items) => {
+                layout.block(out, level, ('[', ']'), items, |out, v, l| {
+                    v.write(out, layout, l)
+                })?
+            }
+            SceneValue::Tuple(items) => {
+                out.push('(');
+                for (k, item) in items.iter().enumerate() {
+                    if k > 0 {
+                        out.push_str(layout.separator());
+                    }
+                    item.write(out, layout, level)?;
+                }
+                out.push(')');
+            }
+            SceneValue::Map(entries) => {
+                layout.block(out, level, ('{', '}'), entries, |out, (k, v), l| {
+                    k.write(out, layout, l)?;
+                    out.push_str(layout.colon());
+                    v.write(out, layout, l)
+                })?
+            }
+            SceneValue::Struct(fields) => {
+                layout.block(out, level, ('(', ')'), fields, |out, (name, v), l| {
+                    write_identifier(out, name)?;
+                    out.push_str(layout.colon());
+                    v.write(out, layout, l)
+                })?
+            }
+            SceneValue::Named(name, inner) => {
+                write_identifier(out, name)?;
+                match &**inner {
+                    SceneValue::Unit => {}
+                    inner @ (SceneValue::Tuple(_) | SceneValue::Struct(_)) => {
+                        inner.write(out, layout, level)?
+                    }
+                    inner => {
+                        out.push('(');
+                        inner.write(out, layout, level)?;
+                        out.push(')');
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Field `name` of a struct, named struct or map with string keys.
+    pub fn field(&self, name: &str) -> Option<&SceneValue> {
+        match self {
+            SceneValue::Struct(fields) => fields.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            SceneValue::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| matches!(k, SceneValue::String(k) if k == name))
+                .map(|(_, v)| v),
+            SceneValue::Named(_, inner) => inner.field(name),
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut SceneValue> {
+        match self {
+            SceneValue::Struct(fields) => {
+                fields.iter_mut().find(|(k, _)| k == name).map(|(_, v)| v)
+            }
+            SceneValue::Map(entries) => entries
+                .iter_mut()
+                .find(|(k, _)| matches!(k, SceneValue::String(k) if k == name))
+                .map(|(_, v)| v),
+            SceneValue::Named(_, inner) => inner.field_mut(name),
+            _ => None,
+        }
+    }
+}
+
+fn write_identifier(out: &mut String, name: &str) -> Result<(), ron::Error> {
+    if !is_identifier(name) {
+        return Err(ron::Error::InvalidIdentifier(name.to_string()));
+    }
+    out.push_str(name);
+    Ok(())
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Values parsed as `ron::Value` (migrated documents): maps with identifier keys become structs
+/// and lists of 2 to 4 numbers (vectors, colours) tuples, as they were most likely written.
+impl From<Value> for SceneValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Bool(b) => SceneValue::Bool(b),
+            Value::Char(c) => SceneValue::Char(c),
+            Value::Number(Number::Integer(i)) => SceneValue::Integer(i.into()),
+            Value::Number(Number::Float(f)) => SceneValue::Float(f.get()),
+            Value::String(s) => SceneValue::String(s),
+            Value::Unit => SceneValue::Unit,
+            Value::Option(inner) => SceneValue::Option(inner.map(|v| Box::new((*v).into()))),
+            Value::Seq(items)
+                if (2..=4).contains(&items.len())
+                    && items.iter().all(|v| matches!(v, Value::Number(_))) =>
+            {
+                SceneValue::Tuple(items.into_iter().map(Into::into).collect())
+            }
+            Value::Seq(items) => SceneValue::Seq(items.into_iter().map(Into::into).collect()),
+            Value::Map(map)
+                if map
+                    .keys()
+                    .all(|k| matches!(k, Value::String(k) if is_identifier(k))) =>
+            {
+                SceneValue::Struct(
+                    map.into_iter()
+                        .map(|(k, v)| match k {
+                            Value::String(k) => (k, v.into()),
+                            _ => unreachable!("checked above"),
+                        })
+                        .collect(),
+                )
+            }
+            Value::Map(map) => {
+                SceneValue::Map(map.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+            }
+        }
+    }
+}
+
+/// Lossy: `ron::Value` has no names, so structs become maps and enum variants their contents.
+impl From<&SceneValue> for Value {
+    fn from(value: &SceneValue) -> Self {
+        match value {
+            SceneValue::Unit => Value::Unit,
+            SceneValue::Bool(b) => Value::Bool(*b),
+            SceneValue::Char(c) => Value::Char(*c),
+            SceneValue::Integer(i) => match i64::try_from(*i) {
+                Ok(i) => Value::Number(Number::Integer(i)),
+                Err(_) => Value::Number(Number::Float(Float::new(*i as f64))),
+            },
+            SceneValue::Float(f) => Value::Number(Number::Float(Float::new(*f))),
+            SceneValue::String(s) => Value::String(s.clone()),
+            SceneValue::Option(inner) => {
+                Value::Option(inner.as_deref().map(|v| Box::new(v.into())))
+            }
+            SceneValue::Seq(items) | SceneValue::Tuple(items) => {
+                Value::Seq(items.iter().map(Into::into).collect())
+            }
+            SceneValue::Map(entries) => {
+                let mut map = Map::new();
+                for (k, v) in entries {
+                    map.insert(k.into(), v.into());
+                }
+                Value::Map(map)
+            }
+            SceneValue::Struct(fields) => {
+                let mut map = Map::new();
+                for (k, v) in fields {
+                    map.insert(Value::String(k.clone()), v.into());
+                }
+                Value::Map(map)
+            }
+            SceneValue::Named(_, inner) => (&**inner).into(),
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    scanner: Scanner<'a>,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> SpannedError {
+        self.error_at(self.scanner.i, message)
+    }
+
+    fn error_at(&self, offset: usize, message: impl Into<String>) -> SpannedError {
+        let position = position_at(self.text, offset);
+        SpannedError {
+            code: ron::Error::Message(message.into()),
+            position: RonPosition {
+                line: position.line,
+                col: position.column,
+            },
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), SpannedError> {
+        match self.scanner.eat(c) {
+            true => Ok(()),
+            false => Err(self.error(format!("expected `{}`", c as char))),
+        }
+    }
+
+    fn value(&mut self) -> Result<SceneValue, SpannedError> {
+        self.scanner.skip_trivia();
+        match self.scanner.peek() {
+            None => Err(self.error("unexpected end of text")),
+            Some(b'(') => {
+                self.scanner.i += 1;
+                self.parens()
+            }
+            Some(b'[') => {
+                self.scanner.i += 1;
+                self.items(b']').map(SceneValue::Seq)
+            }
+            Some(b'{') => {
+                self.scanner.i += 1;
+                self.map()
+            }
+            Some(b'"') => self.string().map(SceneValue::String),
+            Some(b'\'') => self.char_literal(),
+            Some(b'r') if self.scanner.at_raw_string() => match self.scanner.raw_string() {
+                Some(contents) => Ok(SceneValue::String(self.text[contents].to_string())),
+                None => Err(self.error("unterminated raw string")),
+            },
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => self.identified(),
+            Some(_) => self.number(),
+        }
+    }
+
+    /// `true`/`false`, `None`, `Some(..)`, `inf`/`NaN` or a `Named` value.
+    fn identified(&mut self) -> Result<SceneValue, SpannedError> {
+        let start = self.scanner.i;
+        let Some(name) = self.scanner.ident() else {
+            return Err(self.error("expected an identifier"));
+        };
+        match name.as_str() {
+            "true" => return Ok(SceneValue::Bool(true)),
+            "false" => return Ok(SceneValue::Bool(false)),
+            "None" => return Ok(SceneValue::Option(None)),
+            "inf" | "NaN" => {
+                self.scanner.i = start;
+                return self.number();
+            }
+            _ => {}
+        }
+        let before = self.scanner.i;
+        if !self.scanner.eat(b'(') {
+            self.scanner.i = before;
+            return Ok(SceneValue::Named(name, Box::new(SceneValue::Unit)));
+        }
+        if name == "Some" {
+            let inner = self.value()?;
+            self.scanner.eat(b',');
+            self.expect(b')')?;
+            return Ok(SceneValue::Option(Some(Box::new(inner))));
+        }
+        Ok(SceneValue::Named(name, Box::new(self.parens()?)))
+    }
+
+    /// After `(`: `()`, a `(field: ..)` struct or an `(a, b)` tuple.
+    fn parens(&mut self) -> Result<SceneValue, SpannedError> {
+        if self.scanner.eat(b')') {
+            return Ok(SceneValue::Unit);
+        }
+        let start = self.scanner.i;
+        let is_struct = self.scanner.ident().is_some() && self.scanner.eat(b':');
+        self.scanner.i = start;
+        if !is_struct {
+            return self.items(b')').map(SceneValue::Tuple);
+        }
+        let mut fields = Vec::new();
+        loop {
+            if self.scanner.eat(b')') {
+                return Ok(SceneValue::Struct(fields));
+            }
+            let Some(name) = self.scanner.ident() else {
+                return Err(self.error("expected a field name"));
+            };
+            self.expect(b':')?;
+            fields.push((name, self.value()?));
+            if !self.scanner.eat(b',') {
+                self.expect(b')')?;
+                return Ok(SceneValue::Struct(fields));
+            }
+        }
+    }
+
+    fn items(&mut self, close: u8) -> Result<Vec<SceneValue>, SpannedError> {
+        let mut items = Vec::new();
+        loop {
+            if self.scanner.eat(close) {
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            if !self.scanner.eat(b',') {
+                self.expect(close)?;
+                return Ok(items);
+            }
+        }
+    }
+
+    /// After `{`.
+    fn map(&mut self) -> Result<SceneValue, SpannedError> {
+        let mut entries = Vec::new();
+        loop {
+            if self.scanner.eat(b'}') {
+                return Ok(SceneValue::Map(entries));
+            }
+            let key = self.value()?;
+            self.expect(b':')?;
+            entries.push((key, self.value()?));
+            if !self.scanner.eat(b',') {
+                self.expect(b'}')?;
+                return Ok(SceneValue::Map(entries));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SpannedError> {
+        let start = self.scanner.i;
+        self.scanner.i += 1;
+        let mut out = String::new();
+        loop {
+            match self.scanner.peek() {
+                None => return Err(self.error_at(start, "unterminated string")),
+                Some(b'"') => {
+                    self.scanner.i += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.scanner.i += 1;
+                    out.push(self.escape()?);
+                }
+                Some(_) => {
+                    let run = self.scanner.i;
+                    while self.scanner.peek().is_some_and(|c| c != b'"' && c != b'\\') {
+                        self.scanner.i += 1;
+                    }
+                    out.push_str(&self.text[run..self.scanner.i]);
+                }
+            }
+        }
+    }
+
+    fn char_literal(&mut self) -> Result<SceneValue, SpannedError> {
+        self.scanner.i += 1;
+        let c = match self.scanner.peek() {
+            Some(b'\\') => {
+                self.scanner.i += 1;
+                self.escape()?
+            }
+            _ => {
+                let Some(c) = self.text[self.scanner.i..].chars().next() else {
+                    return Err(self.error("unterminated char"));
+                };
+                self.scanner.i += c.len_utf8();
+                c
+            }
+        };
+        if self.scanner.peek() != Some(b'\'') {
+            return Err(self.error("expected `'`"));
+        }
+        self.scanner.i += 1;
+        Ok(SceneValue::Char(c))
+    }
+
+    /// After `\`: the escapes `ron` reads.
+    fn escape(&mut self) -> Result<char, SpannedError> {
+        let start = self.scanner.i;
+        let Some(c) = self.scanner.peek() else {
+            return Err(self.error("unterminated escape"));
+        };
+        self.scanner.i += 1;
+        let hex = |digits: &str| {
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+        };
+        let escaped = match c {
+            b'\'' => Some('\''),
+            b'"' => Some('"'),
+            b'\\' => Some('\\'),
+            b'n' => Some('\n'),
+            b'r' => Some('\r'),
+            b't' => Some('\t'),
+            b'0' => Some('\0'),
+            b'x' => {
+                let digits = self
+                    .text
+                    .get(self.scanner.i..self.scanner.i + 2)
+                    .unwrap_or("");
+                self.scanner.i += digits.len();
+                hex(digits).filter(char::is_ascii)
+            }
+            b'u' => {
+                let rest = &self.text[self.scanner.i..];
+                let digits = rest
+                    .strip_prefix('{')
+                    .and_then(|r| r.split_once('}'))
+                    .map(|(digits, _)| digits)
+                    .filter(|d| (1..=6).contains(&d.len()));
+                self.scanner.i += digits.map_or(0, |d| d.len() + 2);
+                digits.and_then(hex)
+            }
+            _ => None,
+        };
+        escaped.ok_or_else(|| self.error_at(start - 1, "invalid escape"))
+    }
+
+    /// Integers (also `0x`, `0o`, `0b`) and floats (also `inf`, `NaN`); `_` separators allowed.
+    fn number(&mut self) -> Result<SceneValue, SpannedError> {
+        let start = self.scanner.i;
+        while self.scanner.peek().is_some_and(|c| {
+            !matches!(c, b',' | b')' | b']' | b'}' | b':' | b'/') && !c.is_ascii_whitespace()
+        }) {
+            self.scanner.i += 1;
+        }
+        let token = &self.text[start..self.scanner.i];
+        if token.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        let clean = token.replace('_', "");
+        let (negative, digits) = match clean.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, clean.strip_prefix('+').unwrap_or(&clean)),
+        };
+        let radix = match digits.get(..2) {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+        let value = match radix {
+            Some(radix) => i128::from_str_radix(&digits[2..], radix)
+                .ok()
+                .map(|v| SceneValue::Integer(if negative { -v } else { v })),
+            None if digits.contains(['.', 'e', 'E']) || matches!(digits, "inf" | "NaN") => {
+                clean.parse().ok().map(SceneValue::Float)
+            }
+            None => clean.parse().ok().map(SceneValue::Integer),
+        };
+        value.ok_or_else(|| self.error_at(start, format!("invalid number `{token}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(text: &str) -> String {
+        SceneValue::parse(text).unwrap().to_ron().unwrap()
+    }
+
+    #[test]
+    fn keeps_names_and_shapes() {
+        let value =
+            SceneValue::parse("(a: Left, b: Some(Pair(1, -2)), c: [(1.5, 2.0)], d: ())").unwrap();
+        assert_eq!(
+            value.field("a"),
+            Some(&SceneValue::Named(
+                "Left".into(),
+                Box::new(SceneValue::Unit)
+            ))
+        );
+        assert_eq!(
+            round_trip("(a: Left, b: Some(Pair(1, -2)), c: [(1.5, 2.0)], d: ())"),
+            "(a:Left,b:Some(Pair(1,-2)),c:[(1.5,2.0)],d:())"
+        );
+        assert_eq!(round_trip("Named(x: {\"k\": 'c'})"), "Named(x:{\"k\":'c'})");
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(round_trip("0x1F"), "31");
+        assert_eq!(round_trip("1_000"), "1000");
+        assert_eq!(round_trip("-3.0e2"), "-300.0");
+        assert_eq!(round_trip("inf"), "inf");
+        assert_eq!(round_trip("r#\"a \"raw\" one\"#"), r#""a \"raw\" one""#);
+        assert_eq!(round_trip(r#""tab\t \u{e9} \x41""#), r#""tab\t é A""#);
+        assert_eq!(round_trip(r"'\''"), r"'\''");
+        assert_eq!(round_trip("[true, None] // comment"), "[true,None]");
+    }
+
+    #[test]
+    fn pretty_layout() {
+        let value = SceneValue::parse("(a: [1], b: Some((x: 1.0)), c: {})").unwrap();
+        let mut out = String::new();
+        let layout = Layout {
+            indent: Some("  "),
+            implicit_some: true,
+        };
+        value.write(&mut out, &layout, 0).unwrap();
+        assert_eq!(
+            out,
+            "(\n  a: [\n    1,\n  ],\n  b: (\n    x: 1.0,\n  ),\n  c: {},\n)"
+        );
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        let error = SceneValue::parse("(\n  a: 1,\n  b: @,\n)").unwrap_err();
+        assert_eq!((error.position.line, error.position.col), (3, 6));
+        assert!(SceneValue::parse("(a: 1) x").is_err());
+        assert!(
+            SceneValue::Struct(vec![("not a name".into(), SceneValue::Unit)])
+                .to_ron()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,233 @@
+/**
+ * path: /crates/engine_scene/src/writer.rs
+ * description: Writing scenes as RON files.
+ * Output is pretty-printed and deterministic (entities in scene order, components and map keys
+ * sorted), so saved scenes diff cleanly. Given the text a scene was loaded from, entities that
+ * didn't change are copied from it verbatim, keeping their comments and layout.
+ */
+use std::collections::HashMap;
+
+use crate::reader::read_scene;
+use crate::value::Layout;
+use crate::{
+    Entity,
+    EntityUid,
+    PathSegment,
+    Scene,
+    SceneError,
+    SceneValue,
+    SourceMap,
+};
+
+/// Scene-to-RON writer.
+/// - `indent`: indentation per level (4 spaces by default)
+/// - `implicit_some`: write `#![enable(implicit_some)]` and leave out `Some(..)` (on by default)
+/// - `original`: text the scene was loaded from (see `preserve`)
+///
+/// ```ignore
+/// let text = SceneWriter::new().preserve(&original_text).write(&scene)?;
+/// ```
+pub struct SceneWriter<'a> {
+    indent: String,
+    implicit_some: bool,
+    original: Option<&'a str>,
+}
+
+impl Default for SceneWriter<'_> {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_string(),
+            implicit_some: true,
+            original: None,
+        }
+    }
+}
+
+impl<'a> SceneWriter<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    pub fn implicit_some(mut self, implicit_some: bool) -> Self {
+        self.implicit_some = implicit_some;
+        self
+    }
+
+    /// Copy entities that are unchanged (same id and data) from `original`, the text the scene
+    /// was loaded from, with the comments above and after them. Ignored if `original` isn't a
+    /// scene of the same version. `implicit_some` stays on if `original` enables it.
+    pub fn preserve(mut self, original: &'a str) -> Self {
+        self.original = Some(original);
+        self
+    }
+
+    /// The scene as RON text, ending with a newline.
+    pub fn write(&self, scene: &Scene) -> Result<String, SceneError> {
+        let original = self.original.and_then(|text| {
+            let parsed = read_scene(&SceneValue::parse(text).ok()?).ok()?;
+            (parsed.version == scene.version).then_some((text, parsed))
+        });
+        let implicit_some = self.implicit_some
+            || original
+                .as_ref()
+                .is_some_and(|(text, _)| enables_implicit_some(text));
+
+        let mut out = String::new();
+        if implicit_some {
+            out.push_str("#![enable(implicit_some)]\n");
+        }
+        let layout = Layout {
+            indent: Some(&self.indent),
+            implicit_some,
+        };
+        document(scene)
+            .write(&mut out, &layout, 0)
+            .map_err(SceneError::Write)?;
+        if let Some((text, parsed)) = original {
+            out = splice_unchanged(&out, scene, text, &parsed);
+        }
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+/// The scene as a document tree, fields in format order; empty optional fields are left out.
+fn document(scene: &Scene) -> SceneValue {
+    let uid = |id: EntityUid| match id.as_small() {
+        Some(id) => SceneValue::Integer(id.into()),
+        None => SceneValue::String(id.to_string()),
+    };
+    let some = |value| SceneValue::Option(Some(Box::new(value)));
+    let field = |name: &str, value| (name.to_string(), value);
+
+    let entities = scene.entities.iter().map(|e| {
+        let mut fields = vec![
+            field("id", uid(e.id)),
+            field("name", SceneValue::String(e.name.clone())),
+        ];
+        if let Some(parent) = e.parent {
+            fields.push(field("parent", some(uid(parent))));
+        }
+        if let Some(prefab) = &e.prefab {
+            let mut prefab_fields = vec![field("path", SceneValue::String(prefab.path.clone()))];
+            if !prefab.overrides.is_empty() {
+                let overrides = prefab.overrides.iter().map(|o| {
+                    SceneValue::Struct(vec![
+                        field(
+                            "entity",
+                            SceneValue::Seq(o.entity.iter().copied().map(uid).collect()),
+                        ),
+                        field("field", SceneValue::String(o.field.clone())),
+                        field("value", o.value.clone()),
+                    ])
+                });
+                prefab_fields.push(field("overrides", SceneValue::Seq(overrides.collect())));
+            }
+            fields.push(field("prefab", some(SceneValue::Struct(prefab_fields))));
+        }
+        if !e.components.is_empty() {
+            let components = e
+                .components
+                .iter()
+                .map(|(name, value)| (SceneValue::String(name.clone()), value.clone()));
+            fields.push(field("components", SceneValue::Map(components.collect())));
+        }
+        SceneValue::Struct(fields)
+    });
+    SceneValue::Struct(vec![
+        field("version", SceneValue::Integer(scene.version.into())),
+        field("name", SceneValue::String(scene.name.clone())),
+        field("entities", SceneValue::Seq(entities.collect())),
+    ])
+}
+
+fn enables_implicit_some(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("#!["))
+        .any(|line| line.contains("implicit_some"))
+}
+
+/// Replace the generated text of every entity that is the same in `original` with its original
+/// text, plus the comment lines above it and a comment after it on the same line. Comments
+/// before the whole document are kept too.
+fn splice_unchanged(generated: &str, scene: &Scene, text: &str, original: &Scene) -> String {
+    let before: HashMap<EntityUid, (usize, &Entity)> = original
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.id, (i, e)))
+        .collect();
+    let source = SourceMap::new(text);
+    let target = SourceMap::new(generated);
+    let entity_path = |i| [PathSegment::Field("entities".into()), PathSegment::Index(i)];
+
+    // (position, length replaced, text), applied back to front
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    for (i, entity) in scene.entities.iter().enumerate() {
+        let Some((j, _)) = before.get(&entity.id).filter(|(_, e)| *e == entity) else {
+            continue;
+        };
+        let (Some((_, to)), Some((lead, from))) =
+            (target.span(&entity_path(i)), source.span(&entity_path(*j)))
+        else {
+            continue;
+        };
+        let line_end = generated[to.end..]
+            .find('\n')
+            .map_or(generated.len(), |n| to.end + n);
+        if let Some(comment) = trailing_comment(&text[from.end..]) {
+            edits.push((line_end, 0, format!(" {comment}")));
+        }
+        edits.push((to.start, to.len(), text[from.clone()].to_string()));
+        let above = comment_lines(&text[lead..from.start]);
+        if !above.is_empty() {
+            let line_start = generated[..to.start].rfind('\n').map_or(0, |n| n + 1);
+            edits.push((line_start, 0, above.to_string()));
+        }
+    }
+    // Comments before the root (and around `#![..]` attributes, which the generated text has)
+    if let Some((_, from)) = source.span(&[]) {
+        let header: String = text[..from.start]
+            .split_inclusive('\n')
+            .filter(|line| !line.trim_start().starts_with("#!["))
+            .collect();
+        if !header.trim().is_empty() {
+            edits.push((0, 0, header.trim_start_matches(['\n', '\r']).to_string()));
+        }
+    }
+
+    let mut out = generated.to_string();
+    edits.sort_by_key(|(position, _, _)| std::cmp::Reverse(*position));
+    for (position, len, replacement) in edits {
+        out.replace_range(position..position + len, &replacement);
+    }
+    out
+}
+
+/// Whole lines of `trivia` (the whitespace/comments before a list item) after the line of the
+/// previous item, if any of them holds a comment.
+fn comment_lines(trivia: &str) -> &str {
+    let Some(first_newline) = trivia.find('\n') else {
+        return "";
+    };
+    let rest = &trivia[first_newline + 1..];
+    let lines = &rest[..rest.rfind('\n').map_or(0, |n| n + 1)];
+    if lines.trim().is_empty() {
+        ""
+    } else {
+        lines
+    }
+}
+
+/// `// ..` following a list item on its line (after its comma).
+fn trailing_comment(after: &str) -> Option<&str> {
+    let line = after.split('\n').next()?.trim_start();
+    let rest = line.strip_prefix(',').unwrap_or(line).trim_start();
+    rest.starts_with("//").then(|| rest.trim_end())
+}
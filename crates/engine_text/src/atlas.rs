@@ -0,0 +1,299 @@
+/**
+ * path: /crates/engine_text/src/atlas.rs
+ * description: Glyph atlas: glyphs rasterized on first use and packed into one RGBA8 texture
+ * (white, coverage in alpha) on shelves. The renderer uploads what changed each frame.
+ */
+use std::collections::HashMap;
+
+use ab_glyph::{
+    point,
+    Font as _,
+    Glyph,
+    GlyphId,
+};
+use bevy_ecs::prelude::Resource;
+use glam::{
+    UVec2,
+    Vec2,
+};
+
+use crate::font::{
+    FontId,
+    Fonts,
+};
+use crate::layout::TextLayout;
+
+/// Side of a new atlas, in pixels.
+pub const ATLAS_INITIAL_SIZE: u32 = 512;
+
+/// The atlas doubles up to this size; glyphs that don't fit then aren't drawn.
+pub const ATLAS_MAX_SIZE: u32 = 4096;
+
+/// Free pixels around each glyph, so linear filtering doesn't pick up its neighbours.
+const PADDING: u32 = 1;
+
+/// Sizes are rasterized in quarter pixels.
+const SIZE_STEPS: f32 = 4.0;
+
+/// A rasterized glyph: its pixels in the atlas and where they go relative to the glyph origin
+/// (on the baseline, y down).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    pub position: UVec2,
+    pub size: UVec2,
+    pub offset: Vec2,
+}
+
+/// Part of the atlas to upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtlasUpdate {
+    /// The atlas was created, grew or was cleared: upload all of it (at its new size).
+    Full,
+    /// Pixels in this rect changed.
+    Region { position: UVec2, size: UVec2 },
+}
+
+/// One textured quad of a glyph, in pixels from the top-left of the text box (y down).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphQuad {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: FontId,
+    glyph: u16,
+    size: u32,
+}
+
+/// A row of glyphs; each new glyph goes right of the last one.
+#[derive(Debug, Clone, Copy)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    x: u32,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct GlyphAtlas {
+    size: u32,
+    pixels: Vec<u8>,
+    shelves: Vec<Shelf>,
+    /// `None` for glyphs without pixels (spaces) or that didn't fit.
+    glyphs: HashMap<GlyphKey, Option<AtlasGlyph>>,
+    fonts_generation: u64,
+    update: Option<AtlasUpdate>,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        let size = ATLAS_INITIAL_SIZE;
+        Self {
+            size,
+            pixels: vec![0; (size * size * 4) as usize],
+            shelves: Vec::new(),
+            glyphs: HashMap::new(),
+            fonts_generation: 0,
+            update: Some(AtlasUpdate::Full),
+        }
+    }
+}
+
+impl GlyphAtlas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Side of the (square) atlas in pixels.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Tightly packed RGBA8 rows from the top.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Pixels of a rect, tightly packed, for uploading an `AtlasUpdate::Region`.
+    pub fn region_pixels(&self, position: UVec2, size: UVec2) -> Vec<u8> {
+        let mut out = Vec::with_capacity((size.x * size.y * 4) as usize);
+        for y in position.y..position.y + size.y {
+            let start = ((y * self.size + position.x) * 4) as usize;
+            out.extend_from_slice(&self.pixels[start..start + (size.x * 4) as usize]);
+        }
+        out
+    }
+
+    /// What changed since the last call.
+    pub fn take_update(&mut self) -> Option<AtlasUpdate> {
+        self.update.take()
+    }
+
+    /// Drop every glyph (e.g. when a font was replaced).
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+        self.shelves.clear();
+        self.glyphs.clear();
+        self.update = Some(AtlasUpdate::Full);
+    }
+
+    /// A glyph at `size` pixels, rasterized and packed on first use. `None` if it has no
+    /// pixels or the atlas is full.
+    pub fn glyph(
+        &mut self,
+        fonts: &Fonts,
+        font: FontId,
+        glyph: u16,
+        size: f32,
+    ) -> Option<AtlasGlyph> {
+        // Glyphs of a replaced font are stale
+        if fonts.generation() != self.fonts_generation {
+            self.fonts_generation = fonts.generation();
+            self.clear();
+        }
+        let key = GlyphKey {
+            font,
+            glyph,
+            size: (size * SIZE_STEPS).round() as u32,
+        };
+        if let Some(entry) = self.glyphs.get(&key) {
+            return *entry;
+        }
+        let entry = self.rasterize(fonts, key);
+        self.glyphs.insert(key, entry);
+        entry
+    }
+
+    fn rasterize(&mut self, fonts: &Fonts, key: GlyphKey) -> Option<AtlasGlyph> {
+        let font = fonts.get(key.font)?;
+        let outlined = font.arc().outline_glyph(Glyph {
+            id: GlyphId(key.glyph),
+            scale: font.px_scale(key.size as f32 / SIZE_STEPS),
+            position: point(0.0, 0.0),
+        })?;
+        let bounds = outlined.px_bounds();
+        let size = UVec2::new(bounds.width() as u32, bounds.height() as u32);
+        if size.x == 0 || size.y == 0 {
+            return None;
+        }
+        let position = self.allocate(size)?;
+        outlined.draw(|x, y, coverage| {
+            let i = (((position.y + y) * self.size + position.x + x) * 4) as usize;
+            self.pixels[i..i + 4].copy_from_slice(&[
+                255,
+                255,
+                255,
+                (coverage.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ]);
+        });
+        self.mark(position, size);
+        Some(AtlasGlyph {
+            position,
+            size,
+            offset: Vec2::new(bounds.min.x, bounds.min.y),
+        })
+    }
+
+    /// Space for a `size` rect: on the first shelf it fits (that isn't much taller), else on
+    /// a new shelf, growing the atlas if needed.
+    fn allocate(&mut self, size: UVec2) -> Option<UVec2> {
+        let padded = size + PADDING;
+        loop {
+            let fits = self.shelves.iter_mut().find(|shelf| {
+                shelf.height >= padded.y
+                    && shelf.height <= padded.y + padded.y / 2
+                    && shelf.x + padded.x <= self.size
+            });
+            if let Some(shelf) = fits {
+                let position = UVec2::new(shelf.x, shelf.y);
+                shelf.x += padded.x;
+                return Some(position);
+            }
+            let top = self.shelves.last().map_or(PADDING, |s| s.y + s.height);
+            if top + padded.y <= self.size && PADDING + padded.x <= self.size {
+                self.shelves.push(Shelf {
+                    y: top,
+                    height: padded.y,
+                    x: PADDING + padded.x,
+                });
+                return Some(UVec2::new(PADDING, top));
+            }
+            if self.size >= ATLAS_MAX_SIZE {
+                return None;
+            }
+            self.grow();
+        }
+    }
+
+    /// Double the size, keeping glyphs where they are. Shelves are extended to the new width.
+    fn grow(&mut self) {
+        let old = self.size;
+        self.size *= 2;
+        let mut pixels = vec![0; (self.size * self.size * 4) as usize];
+        for y in 0..old {
+            let from = (y * old * 4) as usize;
+            let to = (y * self.size * 4) as usize;
+            pixels[to..to + (old * 4) as usize]
+                .copy_from_slice(&self.pixels[from..from + (old * 4) as usize]);
+        }
+        self.pixels = pixels;
+        self.update = Some(AtlasUpdate::Full);
+    }
+
+    fn mark(&mut self, position: UVec2, size: UVec2) {
+        self.update = match self.update {
+            Some(AtlasUpdate::Full) => Some(AtlasUpdate::Full),
+            Some(AtlasUpdate::Region {
+                position: old_position,
+                size: old_size,
+            }) => {
+                let min = old_position.min(position);
+                let max = (old_position + old_size).max(position + size);
+                Some(AtlasUpdate::Region {
+                    position: min,
+                    size: max - min,
+                })
+            }
+            None => Some(AtlasUpdate::Region { position, size }),
+        };
+    }
+}
+
+impl TextLayout {
+    /// Quads of the glyphs rasterized at `scale` pixels per unit of the layout (the
+    /// devicePixelRatio for UI text). Glyph origins are snapped to whole pixels so text stays
+    /// sharp. If the atlas grows meanwhile, UVs of quads made before are wrong; callers that
+    /// batch several texts should check `GlyphAtlas::size` and build them again.
+    pub fn glyph_quads(&self, fonts: &Fonts, atlas: &mut GlyphAtlas, scale: f32) -> Vec<GlyphQuad> {
+        let mut quads = Vec::with_capacity(self.glyphs.len());
+        let mut start = atlas.size();
+        let mut index = 0;
+        while let Some(glyph) = self.glyphs.get(index) {
+            index += 1;
+            let Some(entry) = atlas.glyph(fonts, glyph.font, glyph.glyph, glyph.size * scale)
+            else {
+                continue;
+            };
+            if atlas.size() != start {
+                // Grew: start over with the new size
+                start = atlas.size();
+                quads.clear();
+                index = 0;
+                continue;
+            }
+            let origin = (glyph.position * scale).round();
+            let min = origin + entry.offset;
+            let texel = Vec2::splat(1.0 / atlas.size() as f32);
+            quads.push(GlyphQuad {
+                min,
+                max: min + entry.size.as_vec2(),
+                uv_min: entry.position.as_vec2() * texel,
+                uv_max: (entry.position + entry.size).as_vec2() * texel,
+            });
+        }
+        quads
+    }
+}
@@ -0,0 +1,233 @@
+/**
+ * path: /crates/engine_text/src/font.rs
+ * description: Loaded TTF/OTF fonts and their metrics. Sizes are font sizes as in CSS: the em
+ * (not the ascent-to-descent height) is `size` units tall. Kerning comes from the OpenType
+ * `kern` feature (GPOS pair adjustments), or the legacy `kern` table.
+ */
+use std::sync::Arc;
+
+use ab_glyph::{
+    Font as _,
+    FontArc,
+    GlyphId,
+    PxScale,
+    ScaleFont,
+};
+use bevy_ecs::prelude::Resource;
+use thiserror::Error;
+use ttf_parser::gpos::{
+    PairAdjustment,
+    PositioningSubtable,
+};
+use ttf_parser::opentype_layout::LayoutTable;
+use ttf_parser::Tag;
+
+#[derive(Error, Debug)]
+pub enum FontError {
+    #[error("font {name}: {reason}")]
+    Invalid { name: String, reason: String },
+}
+
+/// A parsed font; cheap to clone.
+#[derive(Clone)]
+pub struct Font {
+    name: String,
+    font: FontArc,
+    /// GPOS lookups of the `kern` feature.
+    kern_lookups: Arc<[u16]>,
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Font").field("name", &self.name).finish()
+    }
+}
+
+impl Font {
+    /// Parse a TrueType/OpenType file; `name` is what styles and `Text` components refer to.
+    pub fn from_bytes(name: impl Into<String>, bytes: Vec<u8>) -> Result<Font, FontError> {
+        let name = name.into();
+        let font = FontArc::try_from_vec(bytes).map_err(|e| FontError::Invalid {
+            name: name.clone(),
+            reason: e.to_string(),
+        })?;
+        let kern = Tag::from_bytes(b"kern");
+        let mut kern_lookups: Vec<u16> = ttf_parser::Face::parse(font.font_data(), 0)
+            .ok()
+            .and_then(|face| face.tables().gpos)
+            .map(|gpos| {
+                gpos.features
+                    .into_iter()
+                    .filter(|feature| feature.tag == kern)
+                    .flat_map(|feature| feature.lookup_indices)
+                    .collect()
+            })
+            .unwrap_or_default();
+        kern_lookups.sort_unstable();
+        kern_lookups.dedup();
+        Ok(Font {
+            name,
+            font,
+            kern_lookups: kern_lookups.into(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Baseline to the top of the tallest glyphs.
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.font.as_scaled(self.px_scale(size)).ascent()
+    }
+
+    /// Distance between baselines.
+    pub fn line_height(&self, size: f32) -> f32 {
+        let scaled = self.font.as_scaled(self.px_scale(size));
+        scaled.height() + scaled.line_gap()
+    }
+
+    pub(crate) fn glyph_id(&self, c: char) -> GlyphId {
+        self.font.glyph_id(c)
+    }
+
+    pub(crate) fn h_advance(&self, glyph: GlyphId, size: f32) -> f32 {
+        self.font.as_scaled(self.px_scale(size)).h_advance(glyph)
+    }
+
+    /// Kerning lookups, parsed once per text.
+    pub(crate) fn kerning(&self) -> Kerning<'_> {
+        let gpos = match self.kern_lookups.is_empty() {
+            true => None,
+            false => ttf_parser::Face::parse(self.font.font_data(), 0)
+                .ok()
+                .and_then(|face| face.tables().gpos),
+        };
+        Kerning { font: self, gpos }
+    }
+
+    pub(crate) fn arc(&self) -> &FontArc {
+        &self.font
+    }
+
+    /// ab_glyph scales by the ascent-to-descent height; convert from the em size.
+    pub(crate) fn px_scale(&self, size: f32) -> PxScale {
+        let em = self.font.units_per_em().unwrap_or(1000.0);
+        PxScale::from(size * self.font.height_unscaled() / em)
+    }
+}
+
+pub(crate) struct Kerning<'a> {
+    font: &'a Font,
+    gpos: Option<LayoutTable<'a>>,
+}
+
+impl Kerning<'_> {
+    /// Adjustment of the space between two glyphs (usually negative, e.g. in "AV").
+    pub(crate) fn get(&self, previous: Option<GlyphId>, glyph: GlyphId, size: f32) -> f32 {
+        let Some(previous) = previous else {
+            return 0.0;
+        };
+        let scaled = self.font.font.as_scaled(self.font.px_scale(size));
+        let Some(gpos) = &self.gpos else {
+            return scaled.kern(previous, glyph);
+        };
+        let (first, second) = (
+            ttf_parser::GlyphId(previous.0),
+            ttf_parser::GlyphId(glyph.0),
+        );
+        let units = self
+            .font
+            .kern_lookups
+            .iter()
+            .filter_map(|index| gpos.lookups.get(*index))
+            .flat_map(|lookup| lookup.subtables.into_iter::<PositioningSubtable>())
+            .find_map(|subtable| match subtable {
+                PositioningSubtable::Pair(pair) => pair_advance(&pair, first, second),
+                _ => None,
+            })
+            .unwrap_or(0);
+        f32::from(units) * scaled.h_scale_factor()
+    }
+}
+
+/// Extra advance of `first` before `second`, if the subtable covers the pair.
+fn pair_advance(
+    pair: &PairAdjustment,
+    first: ttf_parser::GlyphId,
+    second: ttf_parser::GlyphId,
+) -> Option<i16> {
+    match pair {
+        PairAdjustment::Format1 { coverage, sets } => {
+            let (record, _) = sets.get(coverage.get(first)?)?.get(second)?;
+            Some(record.x_advance)
+        }
+        PairAdjustment::Format2 {
+            coverage,
+            classes,
+            matrix,
+        } => {
+            coverage.get(first)?;
+            let (record, _) = matrix.get((classes.0.get(first), classes.1.get(second)))?;
+            Some(record.x_advance)
+        }
+    }
+}
+
+/// Index of a font in `Fonts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FontId(pub u32);
+
+/// Loaded fonts by name. Text without a font (or with one that isn't loaded) uses the first
+/// one loaded.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Fonts {
+    fonts: Vec<Font>,
+    generation: u64,
+}
+
+impl Fonts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a font, replacing the one with the same name (hot reload); it keeps its `FontId`.
+    pub fn insert(&mut self, font: Font) -> FontId {
+        self.generation += 1;
+        if let Some(index) = self.fonts.iter().position(|f| f.name == font.name) {
+            self.fonts[index] = font;
+            return FontId(index as u32);
+        }
+        self.fonts.push(font);
+        FontId(self.fonts.len() as u32 - 1)
+    }
+
+    /// The font named `name`, or the default one.
+    pub fn find(&self, name: Option<&str>) -> Option<FontId> {
+        let named = name.and_then(|name| self.fonts.iter().position(|f| f.name == name));
+        match named {
+            Some(index) => Some(FontId(index as u32)),
+            None => (!self.fonts.is_empty()).then_some(FontId(0)),
+        }
+    }
+
+    pub fn get(&self, id: FontId) -> Option<&Font> {
+        self.fonts.get(id.0 as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (FontId, &Font)> {
+        self.fonts
+            .iter()
+            .enumerate()
+            .map(|(index, font)| (FontId(index as u32), font))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    /// Changes whenever a font is added or replaced, so laid-out text can be measured again.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
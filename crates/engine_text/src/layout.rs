@@ -0,0 +1,192 @@
+/**
+ * path: /crates/engine_text/src/layout.rs
+ * description: Text layout: glyphs along each line with the font's advances and kerning,
+ * greedy word wrapping and alignment. Positions are in the units of the font size, from the
+ * top-left of the text box with y down.
+ */
+use ab_glyph::GlyphId;
+use bevy_reflect::Reflect;
+use glam::Vec2;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::font::{
+    Font,
+    FontId,
+    Fonts,
+    Kerning,
+};
+
+/// Advance of one character when no font is loaded, in em; close for proportional Latin text.
+pub const FALLBACK_CHAR_WIDTH: f32 = 0.6;
+
+/// Distance between baselines when no font is loaded, in em.
+pub const FALLBACK_LINE_HEIGHT: f32 = 1.2;
+
+/// Horizontal alignment of lines within the text box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// One glyph; `position` is its origin on the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub font: FontId,
+    pub glyph: u16,
+    pub size: f32,
+    pub position: Vec2,
+}
+
+/// A laid-out text. `size` is the box it occupies: the widest line (or the wrap width when
+/// aligned) by the line count times the line height. Without fonts it only has a size.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextLayout {
+    pub size: Vec2,
+    pub glyphs: Vec<PositionedGlyph>,
+    pub line_count: usize,
+}
+
+/// Lay out `text` in the font named `font` (or the default one), wrapping at spaces to
+/// `max_width` (`None` = only at newlines; a word longer than a line gets a line of its own)
+/// and aligning lines within `max_width`, or within the widest line without one.
+pub fn layout_text(
+    fonts: &Fonts,
+    font: Option<&str>,
+    text: &str,
+    size: f32,
+    max_width: Option<f32>,
+    align: TextAlign,
+) -> TextLayout {
+    let Some((id, font)) = fonts.find(font).and_then(|id| Some((id, fonts.get(id)?))) else {
+        return fallback_layout(text, size, max_width);
+    };
+    let kerning = font.kerning();
+    let lines = break_lines(text, max_width, |run, previous| {
+        run_width(font, &kerning, size, run, previous)
+    });
+    let widest = lines.iter().map(|(_, width)| *width).fold(0.0, f32::max);
+    let box_width = match (align, max_width) {
+        (TextAlign::Left, _) | (_, None) => widest,
+        (_, Some(max_width)) => max_width.max(widest),
+    };
+    let line_height = font.line_height(size);
+    let ascent = font.ascent(size);
+    let mut glyphs = Vec::new();
+    for (index, (line, width)) in lines.iter().enumerate() {
+        let mut x = match align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (box_width - width) / 2.0,
+            TextAlign::Right => box_width - width,
+        };
+        let y = ascent + index as f32 * line_height;
+        let mut previous = None;
+        for c in line.chars() {
+            let glyph = font.glyph_id(c);
+            x += kerning.get(previous, glyph, size);
+            if !c.is_whitespace() {
+                glyphs.push(PositionedGlyph {
+                    font: id,
+                    glyph: glyph.0,
+                    size,
+                    position: Vec2::new(x, y),
+                });
+            }
+            x += font.h_advance(glyph, size);
+            previous = Some(glyph);
+        }
+    }
+    TextLayout {
+        size: Vec2::new(box_width, lines.len() as f32 * line_height),
+        glyphs,
+        line_count: lines.len(),
+    }
+}
+
+/// Size of `text` as `layout_text` would lay it out.
+pub fn measure_text(
+    fonts: &Fonts,
+    font: Option<&str>,
+    text: &str,
+    size: f32,
+    max_width: Option<f32>,
+) -> Vec2 {
+    layout_text(fonts, font, text, size, max_width, TextAlign::Left).size
+}
+
+/// Width of `run` when it follows the glyph `previous` (kerning), which is updated to its last
+/// glyph.
+fn run_width(
+    font: &Font,
+    kerning: &Kerning,
+    size: f32,
+    run: &str,
+    previous: &mut Option<GlyphId>,
+) -> f32 {
+    let mut width = 0.0;
+    for c in run.chars() {
+        let glyph = font.glyph_id(c);
+        width += kerning.get(*previous, glyph, size) + font.h_advance(glyph, size);
+        *previous = Some(glyph);
+    }
+    width
+}
+
+/// Lines of `text` and their widths. Explicit newlines always break; with a `max_width`, runs
+/// of spaces between words become one space or a line break.
+fn break_lines<G: Default + Clone>(
+    text: &str,
+    max_width: Option<f32>,
+    width: impl Fn(&str, &mut G) -> f32,
+) -> Vec<(String, f32)> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let Some(max_width) = max_width else {
+            let line_width = width(paragraph, &mut G::default());
+            lines.push((paragraph.to_string(), line_width));
+            continue;
+        };
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        // Last glyph of the line, for kerning across the next run
+        let mut last = G::default();
+        for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+            if !line.is_empty() {
+                let mut after = last.clone();
+                let added = width(" ", &mut after) + width(word, &mut after);
+                if line_width + added <= max_width {
+                    line.push(' ');
+                    line.push_str(word);
+                    line_width += added;
+                    last = after;
+                    continue;
+                }
+                lines.push((std::mem::take(&mut line), line_width));
+            }
+            last = G::default();
+            line_width = width(word, &mut last);
+            line.push_str(word);
+        }
+        lines.push((line, line_width));
+    }
+    lines
+}
+
+/// Layout with `FALLBACK_CHAR_WIDTH` metrics and no glyphs.
+fn fallback_layout(text: &str, size: f32, max_width: Option<f32>) -> TextLayout {
+    let advance = FALLBACK_CHAR_WIDTH * size;
+    let lines = break_lines(text, max_width, |run, _: &mut ()| {
+        run.chars().count() as f32 * advance
+    });
+    let widest = lines.iter().map(|(_, width)| *width).fold(0.0, f32::max);
+    TextLayout {
+        size: Vec2::new(widest, lines.len() as f32 * FALLBACK_LINE_HEIGHT * size),
+        glyphs: Vec::new(),
+        line_count: lines.len(),
+    }
+}
@@ -0,0 +1,32 @@
+/**
+ * path: /crates/engine_text/src/lib.rs
+ * description: Text for game UI, overlays and world-space labels: TTF/OTF fonts (`Fonts`),
+ * layout with kerning, wrapping and alignment (`layout_text`), and a glyph atlas the renderer
+ * uploads as one texture (`GlyphAtlas`). Independent of rendering and of the ECS schedule.
+ */
+mod atlas;
+mod font;
+mod layout;
+pub use atlas::{
+    AtlasGlyph,
+    AtlasUpdate,
+    GlyphAtlas,
+    GlyphQuad,
+    ATLAS_INITIAL_SIZE,
+    ATLAS_MAX_SIZE,
+};
+pub use font::{
+    Font,
+    FontError,
+    FontId,
+    Fonts,
+};
+pub use layout::{
+    layout_text,
+    measure_text,
+    PositionedGlyph,
+    TextAlign,
+    TextLayout,
+    FALLBACK_CHAR_WIDTH,
+    FALLBACK_LINE_HEIGHT,
+};
@@ -4,6 +4,16 @@
  */
 
 use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+pub mod packed;
+pub use packed::{
+    is_packed,
+    pack,
+    unpack,
+    PackedError,
+    PackedKind,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -13,7 +23,7 @@ pub struct CameraUniform {
 
 /// Vertex structure for 3D meshes
 #[repr(C)]
-#[derive(Clone, Copy, Pod, Zeroable)]
+#[derive(Clone, Copy, Pod, Zeroable, Serialize, Deserialize)]
 pub struct MeshVertex {
     pub position: [f32; 3],
     pub normal: [f32; 3],
@@ -48,9 +58,49 @@ pub struct Transform3D {
     pub t1: [f32; 4], // scale.x, scale.y, scale.z, pad
 }
 
-#[derive(Clone)]
+/// Vertex of screen-space UI quads; `position` in canvas pixels (origin top-left, y down).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct GuiVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4], // RGBA, multiplied with the texture
+}
+
+/// Indices drawn with one texture (0 = plain white, i.e. untextured).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuiBatch {
+    pub texture: u32,
+    pub first_index: u32,
+    pub index_count: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MeshData {
     pub vertices: Vec<MeshVertex>,
     pub indices: Vec<u32>,
 }
 
+/// Version of the cooked mesh payload (`MeshData` layout).
+pub const COOKED_MESH_VERSION: u32 = 1;
+/// File extension of cooked meshes.
+pub const MESH_BINARY_EXTENSION: &str = "mesh.bin";
+
+impl MeshData {
+    /// Cooked (binary) form, e.g. for `.mesh.bin` files.
+    pub fn to_packed(&self) -> Result<Vec<u8>, PackedError> {
+        pack(PackedKind::Mesh, COOKED_MESH_VERSION, self)
+    }
+
+    pub fn from_packed(bytes: &[u8]) -> Result<Self, PackedError> {
+        let (header, mesh) = unpack::<Self>(PackedKind::Mesh, bytes)?;
+        if header.content_version != COOKED_MESH_VERSION {
+            return Err(PackedError::Decode(format!(
+                "cooked mesh version {} is not supported (expected {COOKED_MESH_VERSION})",
+                header.content_version
+            )));
+        }
+        Ok(mesh)
+    }
+}
+
@@ -0,0 +1,166 @@
+/**
+ * path: /crates/engine_types/src/packed.rs
+ * description: Container for binary (shipping) assets.
+ * A fixed header (magic, container version, asset kind, content version, payload length, CRC32)
+ * followed by a postcard-encoded payload. Scenes and cooked meshes use it; the loaders pick it
+ * over text formats by file extension.
+ */
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+/// First bytes of every packed asset.
+pub const PACKED_MAGIC: [u8; 4] = *b"IHPK";
+/// Version of the container layout (header + payload encoding).
+pub const PACKED_FORMAT_VERSION: u16 = 1;
+/// magic (4) + format version (2) + kind (1) + reserved (1) + content version (4)
+/// + payload length (4) + CRC32 of the payload (4); integers are little-endian.
+pub const PACKED_HEADER_LEN: usize = 20;
+
+/// What a packed file contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackedKind {
+    Scene,
+    Mesh,
+}
+
+impl PackedKind {
+    fn tag(self) -> u8 {
+        match self {
+            PackedKind::Scene => 1,
+            PackedKind::Mesh => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(PackedKind::Scene),
+            2 => Some(PackedKind::Mesh),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PackedError {
+    #[error("not a packed asset (bad magic)")]
+    BadMagic,
+    #[error("packed format version {found} is not supported (expected {supported})")]
+    UnsupportedVersion { found: u16, supported: u16 },
+    #[error("unknown packed asset kind {0}")]
+    UnknownKind(u8),
+    #[error("expected a packed {expected:?}, found {found:?}")]
+    WrongKind {
+        expected: PackedKind,
+        found: PackedKind,
+    },
+    #[error("truncated: header says {expected} payload bytes, file has {found}")]
+    Truncated { expected: usize, found: usize },
+    #[error("checksum mismatch (expected {expected:08x}, computed {found:08x})")]
+    Checksum { expected: u32, found: u32 },
+    #[error("encode: {0}")]
+    Encode(String),
+    #[error("decode: {0}")]
+    Decode(String),
+}
+
+/// Header of a packed asset.
+/// - `content_version`: version of the payload's own schema (e.g. the scene format version)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedHeader {
+    pub kind: PackedKind,
+    pub content_version: u32,
+    pub payload_len: u32,
+    pub checksum: u32,
+}
+
+/// True if `bytes` start with the packed magic.
+pub fn is_packed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&PACKED_MAGIC)
+}
+
+/// Encode `value` with a header.
+pub fn pack<T: Serialize>(
+    kind: PackedKind,
+    content_version: u32,
+    value: &T,
+) -> Result<Vec<u8>, PackedError> {
+    let payload = postcard::to_stdvec(value).map_err(|e| PackedError::Encode(e.to_string()))?;
+    let payload_len = u32::try_from(payload.len())
+        .map_err(|_| PackedError::Encode("payload over 4 GiB".into()))?;
+
+    let mut out = Vec::with_capacity(PACKED_HEADER_LEN + payload.len());
+    out.extend_from_slice(&PACKED_MAGIC);
+    out.extend_from_slice(&PACKED_FORMAT_VERSION.to_le_bytes());
+    out.push(kind.tag());
+    out.push(0);
+    out.extend_from_slice(&content_version.to_le_bytes());
+    out.extend_from_slice(&payload_len.to_le_bytes());
+    out.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// Check the header (magic, version, length, checksum) without decoding the payload.
+pub fn read_header(bytes: &[u8]) -> Result<PackedHeader, PackedError> {
+    if !is_packed(bytes) {
+        return Err(PackedError::BadMagic);
+    }
+    if bytes.len() < PACKED_HEADER_LEN {
+        return Err(PackedError::Truncated {
+            expected: PACKED_HEADER_LEN,
+            found: bytes.len(),
+        });
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+    let u32_at =
+        |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+    let version = u16_at(4);
+    if version != PACKED_FORMAT_VERSION {
+        return Err(PackedError::UnsupportedVersion {
+            found: version,
+            supported: PACKED_FORMAT_VERSION,
+        });
+    }
+    let kind = PackedKind::from_tag(bytes[6]).ok_or(PackedError::UnknownKind(bytes[6]))?;
+    let header = PackedHeader {
+        kind,
+        content_version: u32_at(8),
+        payload_len: u32_at(12),
+        checksum: u32_at(16),
+    };
+
+    let payload = &bytes[PACKED_HEADER_LEN..];
+    if payload.len() != header.payload_len as usize {
+        return Err(PackedError::Truncated {
+            expected: header.payload_len as usize,
+            found: payload.len(),
+        });
+    }
+    let found = crc32fast::hash(payload);
+    if found != header.checksum {
+        return Err(PackedError::Checksum {
+            expected: header.checksum,
+            found,
+        });
+    }
+    Ok(header)
+}
+
+/// Verify and decode a packed asset of the given kind; returns the header with the value.
+pub fn unpack<T: DeserializeOwned>(
+    kind: PackedKind,
+    bytes: &[u8],
+) -> Result<(PackedHeader, T), PackedError> {
+    let header = read_header(bytes)?;
+    if header.kind != kind {
+        return Err(PackedError::WrongKind {
+            expected: kind,
+            found: header.kind,
+        });
+    }
+    let value = postcard::from_bytes(&bytes[PACKED_HEADER_LEN..])
+        .map_err(|e| PackedError::Decode(e.to_string()))?;
+    Ok((header, value))
+}
@@ -0,0 +1,204 @@
+/**
+ * path: /crates/engine_ui/src/binding.rs
+ * description: Binding nodes to fields of reflected resources. A bound text node shows the
+ * value; a bound toggle or slider follows it and writes the user's changes back, so game code
+ * needs no per-frame glue.
+ */
+use bevy_ecs::world::World;
+use engine_ecs::reflect::Reflect;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::interaction::{
+    UiEvent,
+    UiEvents,
+};
+use crate::node::{
+    UiNodeKind,
+    UiValue,
+};
+use crate::tree::{
+    UiId,
+    UiTree,
+};
+
+/// A resource field a node displays.
+/// - `resource`: registered type name (`"Score"` or the full path); see
+///   `EngineApp::register_type`
+/// - `path`: field path inside it (`"value"`, `"stats.hp"`; empty for the whole resource)
+/// - `format`: text with `{}` (or `{:.2}` for two decimals) where the value goes; only used
+///   by text nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiBinding {
+    pub resource: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+impl UiBinding {
+    pub fn new(resource: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            resource: resource.into(),
+            path: path.into(),
+            format: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+}
+
+/// Show the current values of bound resource fields. Only nodes whose value changed are
+/// touched, so an unchanged UI isn't laid out again.
+pub fn update_ui_bindings(world: &mut World) {
+    let bound: Vec<(UiId, UiBinding)> = {
+        let tree = world.resource::<UiTree>();
+        tree.descendants(tree.root())
+            .into_iter()
+            .filter_map(|id| Some((id, tree.node(id)?.bind.clone()?)))
+            .collect()
+    };
+    let mut updates = Vec::new();
+    for (id, binding) in bound {
+        let Ok(value) = engine_ecs::get_resource_path(world, &binding.resource, &binding.path)
+        else {
+            continue;
+        };
+        let kind = world
+            .resource::<UiTree>()
+            .node(id)
+            .map(|node| node.kind.clone());
+        let Some(mut kind) = kind else {
+            continue;
+        };
+        let changed = match &mut kind {
+            UiNodeKind::Text(text) => {
+                let shown = format_value(world, value.as_ref(), binding.format.as_deref());
+                let changed = *text != shown;
+                *text = shown;
+                changed
+            }
+            // Toggles take bools, sliders numbers
+            widget => {
+                let new = match value.downcast_ref::<bool>() {
+                    Some(on) => Some(UiValue::Bool(*on)),
+                    None => as_number(value.as_ref()).map(|n| UiValue::Number(n as f32)),
+                };
+                new.is_some_and(|new| widget.set_value(new))
+            }
+        };
+        if changed {
+            updates.push((id, kind));
+        }
+    }
+    let mut tree = world.resource_mut::<UiTree>();
+    for (id, kind) in updates {
+        if let Some(node) = tree.node_mut(id) {
+            node.kind = kind;
+        }
+    }
+}
+
+/// Write this frame's widget changes (`UiEvent::Changed`) to the fields bound to them,
+/// converted to the field's type.
+pub fn apply_ui_changes(world: &mut World) {
+    let changes: Vec<(UiBinding, UiValue)> = {
+        let tree = world.resource::<UiTree>();
+        world
+            .resource::<UiEvents>()
+            .iter()
+            .filter_map(|event| match *event {
+                UiEvent::Changed { id, value } => Some((tree.node(id)?.bind.clone()?, value)),
+                _ => None,
+            })
+            .collect()
+    };
+    for (binding, value) in changes {
+        let Ok(current) = engine_ecs::get_resource_path(world, &binding.resource, &binding.path)
+        else {
+            continue;
+        };
+        let new: Option<Box<dyn Reflect>> = match value {
+            UiValue::Bool(on) => current
+                .is::<bool>()
+                .then(|| Box::new(on) as Box<dyn Reflect>),
+            UiValue::Number(number) => number_like(current.as_ref(), number as f64),
+        };
+        if let Some(new) = new {
+            let _ = engine_ecs::set_resource_path(world, &binding.resource, &binding.path, &*new);
+        }
+    }
+}
+
+/// `format` with its placeholder replaced by the value; plain values are shown as text,
+/// others as RON.
+fn format_value(world: &World, value: &dyn Reflect, format: Option<&str>) -> String {
+    let format = format.unwrap_or("{}");
+    let (Some(start), Some(end)) = (format.find('{'), format.find('}')) else {
+        return format.to_string();
+    };
+    if end < start {
+        return format.to_string();
+    }
+    let precision = format[start + 1..end]
+        .strip_prefix(":.")
+        .and_then(|digits| digits.parse::<usize>().ok());
+    let shown = if let Some(text) = value.downcast_ref::<String>() {
+        text.clone()
+    } else if let Some(on) = value.downcast_ref::<bool>() {
+        on.to_string()
+    } else if let Some(number) = as_number(value) {
+        match precision {
+            Some(precision) => format!("{number:.precision$}"),
+            None if value.is::<f32>() => (number as f32).to_string(),
+            None => number.to_string(),
+        }
+    } else {
+        engine_ecs::to_ron(world, value).unwrap_or_default()
+    };
+    format!("{}{shown}{}", &format[..start], &format[end + 1..])
+}
+
+macro_rules! numbers {
+    (floats: $($float:ty),*; integers: $($integer:ty),*) => {
+        fn as_number(value: &dyn Reflect) -> Option<f64> {
+            $(
+                if let Some(number) = value.downcast_ref::<$float>() {
+                    return Some(*number as f64);
+                }
+            )*
+            $(
+                if let Some(number) = value.downcast_ref::<$integer>() {
+                    return Some(*number as f64);
+                }
+            )*
+            None
+        }
+
+        /// `number` as a value of the same type as `current` (integers rounded).
+        fn number_like(current: &dyn Reflect, number: f64) -> Option<Box<dyn Reflect>> {
+            $(
+                if current.is::<$float>() {
+                    return Some(Box::new(number as $float));
+                }
+            )*
+            $(
+                if current.is::<$integer>() {
+                    return Some(Box::new(number.round() as $integer));
+                }
+            )*
+            None
+        }
+    };
+}
+
+numbers!(
+    floats: f32, f64;
+    integers: i8, i16, i32, i64, isize, u8, u16, u32, u64, usize
+);
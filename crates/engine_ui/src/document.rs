@@ -0,0 +1,329 @@
+/**
+ * path: /crates/engine_ui/src/document.rs
+ * description: UI screens authored as `.ui.ron` documents: theme variables, style rules with
+ * selectors and the node hierarchy. `UiTree::spawn_document` builds one; `update_document`
+ * applies an edited version in place (hot reload).
+ */
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use thiserror::Error;
+
+use crate::binding::UiBinding;
+use crate::node::{
+    UiNode,
+    UiNodeKind,
+};
+use crate::selector::{
+    Selector,
+    SelectorError,
+    Specificity,
+    StyledNode,
+};
+use crate::style::{
+    Color,
+    UiStyle,
+    Val,
+};
+use crate::tree::{
+    UiId,
+    UiTree,
+};
+
+/// File extension of UI documents.
+pub const UI_DOCUMENT_EXTENSION: &str = "ui.ron";
+
+#[derive(Error, Debug)]
+pub enum UiDocumentError {
+    #[error("parse: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("{line}:{column}: unknown theme variable `${name}`")]
+    UnknownVariable {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+/// Named values styles refer to as `$name`, e.g. `background: $panel`. Names are shared by
+/// all three maps.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub colors: BTreeMap<String, Color>,
+    pub lengths: BTreeMap<String, Val>,
+    pub numbers: BTreeMap<String, f32>,
+}
+
+impl Theme {
+    /// RON text of a variable's value.
+    fn value(&self, name: &str) -> Option<String> {
+        let text = if let Some(color) = self.colors.get(name) {
+            ron::to_string(color)
+        } else if let Some(length) = self.lengths.get(name) {
+            ron::to_string(length)
+        } else {
+            ron::to_string(self.numbers.get(name)?)
+        };
+        text.ok()
+    }
+}
+
+/// Style rules in document order. Where rules conflict the more specific selector wins, then
+/// the later rule; a node's own `style` wins over all of them.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "Vec<(String, UiStyle)>")]
+pub struct StyleSheet {
+    rules: Vec<(Selector, UiStyle)>,
+}
+
+impl TryFrom<Vec<(String, UiStyle)>> for StyleSheet {
+    type Error = SelectorError;
+
+    fn try_from(rules: Vec<(String, UiStyle)>) -> Result<Self, Self::Error> {
+        let rules = rules
+            .into_iter()
+            .map(|(selector, style)| Ok((Selector::parse(&selector)?, style)))
+            .collect::<Result<_, SelectorError>>()?;
+        Ok(StyleSheet { rules })
+    }
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, selector: Selector, style: UiStyle) {
+        self.rules.push((selector, style));
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = (&Selector, &UiStyle)> {
+        self.rules.iter().map(|(selector, style)| (selector, style))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Merged style of the rules matching `node` (without its own style).
+    pub fn style_for(&self, node: StyledNode, ancestors: &[StyledNode]) -> UiStyle {
+        let mut matching: Vec<(Specificity, usize)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (selector, _))| Some((selector.matches(node, ancestors)?, i)))
+            .collect();
+        matching.sort();
+        let mut style = UiStyle::default();
+        for (_, i) in matching {
+            style.merge(&self.rules[i].1);
+        }
+        style
+    }
+}
+
+/// A node of a document and its children; see `UiNode` for the fields they share.
+/// - `class`: classes for style rules
+/// - `style`: inline style, over the rules
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UiElement {
+    pub kind: UiNodeKind,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub class: Vec<String>,
+    #[serde(default)]
+    pub style: UiStyle,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub tab_index: Option<i32>,
+    #[serde(default)]
+    pub bind: Option<UiBinding>,
+    #[serde(default)]
+    pub children: Vec<UiElement>,
+}
+
+impl UiElement {
+    pub fn to_node(&self) -> UiNode {
+        UiNode {
+            kind: self.kind.clone(),
+            name: self.name.clone(),
+            classes: self.class.clone(),
+            style: self.style.clone(),
+            disabled: self.disabled,
+            tab_index: self.tab_index,
+            bind: self.bind.clone(),
+        }
+    }
+}
+
+/// Contents of a `.ui.ron` file. `Option` fields may be written without `Some(..)`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct UiDocument {
+    #[serde(default)]
+    pub theme: Theme,
+    #[serde(default)]
+    pub styles: StyleSheet,
+    pub root: UiElement,
+}
+
+/// Only the theme, to resolve variables before the whole document is parsed.
+#[derive(Deserialize)]
+#[serde(rename = "UiDocument")]
+struct ThemeSection {
+    #[serde(default)]
+    theme: Theme,
+}
+
+impl UiDocument {
+    pub fn from_ron_str(text: &str) -> Result<UiDocument, UiDocumentError> {
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        // Variables aren't valid RON; any value will do for skipping past them
+        let placeholder = substitute_variables(text, |_| Some("()".to_string()))?;
+        let theme = options.from_str::<ThemeSection>(&placeholder)?.theme;
+        let resolved = substitute_variables(text, |name| theme.value(name))?;
+        Ok(options.from_str(&resolved)?)
+    }
+}
+
+impl UiTree {
+    /// Build `document` as the last child of `parent`; its style rules apply below its root.
+    pub fn spawn_document(&mut self, parent: UiId, document: &UiDocument) -> UiId {
+        let root = self.spawn_element(parent, &document.root);
+        self.set_style_sheet(root, document.styles.clone());
+        root
+    }
+
+    /// Apply an edited `document` to the one spawned at `root`. Nodes are matched to elements
+    /// by name, unnamed ones by kind and order, and updated in place; they keep their `UiId`
+    /// (and so focus, hover and other state) and widgets their value. Other nodes are spawned
+    /// or removed.
+    pub fn update_document(&mut self, root: UiId, document: &UiDocument) {
+        self.update_element(root, &document.root);
+        self.set_style_sheet(root, document.styles.clone());
+    }
+
+    fn spawn_element(&mut self, parent: UiId, element: &UiElement) -> UiId {
+        let id = self.spawn(parent, element.to_node());
+        for child in &element.children {
+            self.spawn_element(id, child);
+        }
+        id
+    }
+
+    fn update_element(&mut self, id: UiId, element: &UiElement) {
+        if let Some(node) = self.node_mut(id) {
+            let value = node.kind.value();
+            *node = element.to_node();
+            if let Some(value) = value {
+                node.kind.set_value(value);
+            }
+        }
+        let mut unmatched = self.children(id);
+        let mut order = Vec::with_capacity(element.children.len());
+        for child in &element.children {
+            let matched = unmatched.iter().position(|existing| {
+                self.node(*existing).is_some_and(|node| match &child.name {
+                    Some(name) => node.name.as_ref() == Some(name),
+                    None => node.name.is_none() && node.kind.name() == child.kind.name(),
+                })
+            });
+            let child_id = match matched {
+                Some(index) => {
+                    let existing = unmatched.remove(index);
+                    self.update_element(existing, child);
+                    existing
+                }
+                None => self.spawn_element(id, child),
+            };
+            order.push(child_id);
+        }
+        for stale in unmatched {
+            self.remove(stale);
+        }
+        for (index, child) in order.into_iter().enumerate() {
+            if self.children(id).get(index) != Some(&child) {
+                self.move_node(child, id, index);
+            }
+        }
+    }
+}
+
+/// Replace every `$name` outside of strings and comments with `value(name)`.
+fn substitute_variables(
+    text: &str,
+    value: impl Fn(&str) -> Option<String>,
+) -> Result<String, UiDocumentError> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    // Line number and byte offset of its start, for errors
+    let mut line = (1, 0);
+    while let Some((i, c)) = chars.next() {
+        out.push(c);
+        match (c, chars.peek().map(|(_, next)| *next)) {
+            ('\n', _) => line = (line.0 + 1, i + 1),
+            ('"', _) => {
+                let mut escaped = false;
+                copy_until(&mut chars, &mut out, &mut line, |c| {
+                    let end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    end
+                });
+            }
+            ('/', Some('/')) => copy_until(&mut chars, &mut out, &mut line, |c| c == '\n'),
+            ('/', Some('*')) => {
+                chars.next();
+                out.push('*');
+                let mut star = false;
+                copy_until(&mut chars, &mut out, &mut line, |c| {
+                    let end = star && c == '/';
+                    star = c == '*';
+                    end
+                });
+            }
+            ('$', _) => {
+                out.pop();
+                let mut name = String::new();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                let Some(replacement) = value(&name) else {
+                    return Err(UiDocumentError::UnknownVariable {
+                        name,
+                        line: line.0,
+                        column: text[line.1..i].chars().count() + 1,
+                    });
+                };
+                out.push_str(&replacement);
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Copy characters up to and including the one `end` accepts.
+fn copy_until(
+    chars: &mut Peekable<CharIndices>,
+    out: &mut String,
+    line: &mut (usize, usize),
+    mut end: impl FnMut(char) -> bool,
+) {
+    for (i, c) in chars.by_ref() {
+        out.push(c);
+        if c == '\n' {
+            *line = (line.0 + 1, i + 1);
+        }
+        if end(c) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,309 @@
+/**
+ * path: /crates/engine_ui/src/draw.rs
+ * description: Turning the laid-out tree into coloured/textured quads for `GUIPipeline`.
+ * Quads are in physical canvas pixels with edges on whole pixels, in drawing order (parents
+ * before children). Text is drawn from the glyph atlas.
+ */
+use engine_text::{
+    layout_text,
+    Fonts,
+    GlyphAtlas,
+};
+use engine_types::{
+    GuiBatch,
+    GuiVertex,
+};
+use glam::Vec2;
+
+use crate::image::ImageDraw;
+use crate::node::UiNodeKind;
+use crate::style::Color;
+use crate::tree::{
+    UiRect,
+    UiTree,
+    GLYPH_ATLAS_TEXTURE,
+    WHITE_TEXTURE,
+};
+
+/// One quad; `uv_min`/`uv_max` select the part of `texture` drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiQuad {
+    pub rect: UiRect,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    pub color: Color,
+    pub texture: u32,
+}
+
+impl UiQuad {
+    pub fn solid(rect: UiRect, color: Color) -> Self {
+        Self {
+            rect,
+            uv_min: Vec2::ZERO,
+            uv_max: Vec2::ONE,
+            color,
+            texture: WHITE_TEXTURE,
+        }
+    }
+
+    pub fn textured(rect: UiRect, texture: u32, color: Color) -> Self {
+        Self {
+            texture,
+            ..Self::solid(rect, color)
+        }
+    }
+
+    /// The part inside `clip`, with the UVs cut to match; `None` if nothing is inside.
+    pub fn clipped(&self, clip: UiRect) -> Option<UiQuad> {
+        let rect = self.rect.intersect(&clip);
+        if rect.size.x <= 0.0 || rect.size.y <= 0.0 {
+            return None;
+        }
+        let uv = |p: Vec2| {
+            self.uv_min + (p - self.rect.position) / self.rect.size * (self.uv_max - self.uv_min)
+        };
+        Some(UiQuad {
+            rect,
+            uv_min: uv(rect.position),
+            uv_max: uv(rect.max()),
+            ..*self
+        })
+    }
+}
+
+/// Vertex/index data plus one batch per run of quads sharing a texture.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiGeometry {
+    pub vertices: Vec<GuiVertex>,
+    pub indices: Vec<u32>,
+    pub batches: Vec<GuiBatch>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiDrawList {
+    pub quads: Vec<UiQuad>,
+}
+
+impl UiDrawList {
+    pub fn push(&mut self, quad: UiQuad) {
+        // Fully transparent or empty quads draw nothing
+        if quad.color.3 > 0.0 && quad.rect.size.x > 0.0 && quad.rect.size.y > 0.0 {
+            self.quads.push(quad);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quads.is_empty()
+    }
+
+    /// Clip the quads from `start` on, dropping those outside `clip`.
+    fn clip_from(&mut self, start: usize, clip: UiRect) {
+        let clipped: Vec<UiQuad> = self
+            .quads
+            .drain(start..)
+            .filter_map(|quad| quad.clipped(clip))
+            .collect();
+        self.quads.extend(clipped);
+    }
+
+    pub fn geometry(&self) -> UiGeometry {
+        let mut geometry = UiGeometry::default();
+        for quad in &self.quads {
+            let first = geometry.vertices.len() as u32;
+            let (min, max) = (quad.rect.position, quad.rect.max());
+            let color = [quad.color.0, quad.color.1, quad.color.2, quad.color.3];
+            let corners = [
+                (
+                    Vec2::new(min.x, min.y),
+                    Vec2::new(quad.uv_min.x, quad.uv_min.y),
+                ),
+                (
+                    Vec2::new(max.x, min.y),
+                    Vec2::new(quad.uv_max.x, quad.uv_min.y),
+                ),
+                (
+                    Vec2::new(max.x, max.y),
+                    Vec2::new(quad.uv_max.x, quad.uv_max.y),
+                ),
+                (
+                    Vec2::new(min.x, max.y),
+                    Vec2::new(quad.uv_min.x, quad.uv_max.y),
+                ),
+            ];
+            geometry
+                .vertices
+                .extend(corners.iter().map(|(position, uv)| GuiVertex {
+                    position: position.to_array(),
+                    uv: uv.to_array(),
+                    color,
+                }));
+            let index_start = geometry.indices.len() as u32;
+            geometry
+                .indices
+                .extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+            match geometry.batches.last_mut() {
+                Some(batch) if batch.texture == quad.texture => batch.index_count += 6,
+                _ => geometry.batches.push(GuiBatch {
+                    texture: quad.texture,
+                    first_index: index_start,
+                    index_count: 6,
+                }),
+            }
+        }
+        geometry
+    }
+}
+
+impl UiTree {
+    /// Quads of every visible node as of the last `layout`: background, border, then the
+    /// text, image or widget, cut to the padding boxes of `Hidden` and `Scroll` ancestors.
+    /// Images are drawn once their size is known (`set_texture_size`). Glyphs are rasterized
+    /// into `atlas` at the devicePixelRatio; its changes must be uploaded as
+    /// `GLYPH_ATLAS_TEXTURE` before drawing.
+    pub fn draw_list(&self, fonts: &Fonts, atlas: &mut GlyphAtlas) -> UiDrawList {
+        loop {
+            let size = atlas.size();
+            let list = self.build_draw_list(fonts, atlas);
+            // The atlas grew, so earlier glyphs' UVs are off
+            if atlas.size() == size {
+                return list;
+            }
+        }
+    }
+
+    fn build_draw_list(&self, fonts: &Fonts, atlas: &mut GlyphAtlas) -> UiDrawList {
+        let scale = self.scale_factor();
+        let mut list = UiDrawList::default();
+        for id in self.descendants(self.root()) {
+            let (Some(node), Some(style), Some(layout)) =
+                (self.node(id), self.style(id), self.layout_of(id))
+            else {
+                continue;
+            };
+            let start = list.quads.len();
+            match (&style.background_image, style.background) {
+                (Some(path), tint) => {
+                    let tint = tint.unwrap_or((1.0, 1.0, 1.0, 1.0));
+                    if let Some(image) = ImageDraw::new(self, path, style, tint) {
+                        image.push(&mut list, layout.rect, scale);
+                    }
+                }
+                (None, Some(background)) => {
+                    list.push(UiQuad::solid(layout.padding_box.snap(scale), background));
+                }
+                (None, None) => {}
+            }
+            if let Some(color) = style.border_color {
+                for edge in border_edges(layout.rect, layout.padding_box) {
+                    list.push(UiQuad::solid(edge.snap(scale), color));
+                }
+            }
+            match node.kind {
+                UiNodeKind::Text(ref text) => {
+                    let text = layout_text(
+                        fonts,
+                        style.font.as_deref(),
+                        text,
+                        style.font_size(),
+                        Some(layout.content.size.x),
+                        style.text_align(),
+                    );
+                    let origin = (layout.content.position * scale).round();
+                    for glyph in text.glyph_quads(fonts, atlas, scale) {
+                        list.push(UiQuad {
+                            rect: UiRect::new(origin + glyph.min, glyph.max - glyph.min),
+                            uv_min: glyph.uv_min,
+                            uv_max: glyph.uv_max,
+                            color: style.color(),
+                            texture: GLYPH_ATLAS_TEXTURE,
+                        });
+                    }
+                }
+                UiNodeKind::Image(ref path) => {
+                    if let Some(image) = ImageDraw::new(self, path, style, style.color()) {
+                        image.push(&mut list, layout.content, scale);
+                    }
+                }
+                UiNodeKind::Toggle(on) => {
+                    draw_toggle(&mut list, layout.content, on, style.color(), scale)
+                }
+                UiNodeKind::Slider {
+                    value, min, max, ..
+                } => {
+                    let fraction = if max > min {
+                        ((value - min) / (max - min)).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    draw_slider(&mut list, layout.content, fraction, style.color(), scale);
+                }
+                _ => {}
+            }
+            if let Some(clip) = layout.clip {
+                list.clip_from(start, clip.snap(scale));
+            }
+        }
+        list
+    }
+}
+
+/// An outlined box in the text colour, filled when on.
+fn draw_toggle(list: &mut UiDrawList, rect: UiRect, on: bool, color: Color, scale: f32) {
+    let line = (rect.size.min_element() / 8.0).max(1.0);
+    let inner = UiRect::new(rect.position + line, rect.size - 2.0 * line);
+    for edge in border_edges(rect, inner) {
+        list.push(UiQuad::solid(edge.snap(scale), color));
+    }
+    if on {
+        let mark = UiRect::new(inner.position + line, inner.size - 2.0 * line);
+        list.push(UiQuad::solid(mark.snap(scale), color));
+    }
+}
+
+/// A track in the text colour, filled up to the value, and a square thumb there.
+fn draw_slider(list: &mut UiDrawList, rect: UiRect, fraction: f32, color: Color, scale: f32) {
+    let track_height = (rect.size.y / 4.0).max(1.0);
+    let track = UiRect::new(
+        Vec2::new(
+            rect.position.x,
+            rect.position.y + (rect.size.y - track_height) / 2.0,
+        ),
+        Vec2::new(rect.size.x, track_height),
+    );
+    let faded = (color.0, color.1, color.2, color.3 * 0.35);
+    list.push(UiQuad::solid(track.snap(scale), faded));
+    let filled = UiRect::new(
+        track.position,
+        Vec2::new(track.size.x * fraction, track.size.y),
+    );
+    list.push(UiQuad::solid(filled.snap(scale), color));
+    let thumb_size = rect.size.y.min(rect.size.x);
+    let thumb_x = rect.position.x + (rect.size.x - thumb_size) * fraction;
+    let thumb = UiRect::new(Vec2::new(thumb_x, rect.position.y), Vec2::splat(thumb_size));
+    list.push(UiQuad::solid(thumb.snap(scale), color));
+}
+
+/// The four border strips between the border box and the padding box (top and bottom span
+/// the full width).
+fn border_edges(outer: UiRect, inner: UiRect) -> [UiRect; 4] {
+    let (outer_min, outer_max) = (outer.position, outer.max());
+    let (inner_min, inner_max) = (inner.position, inner.max());
+    [
+        UiRect::new(
+            outer_min,
+            Vec2::new(outer.size.x, inner_min.y - outer_min.y),
+        ),
+        UiRect::new(
+            Vec2::new(outer_min.x, inner_max.y),
+            Vec2::new(outer.size.x, outer_max.y - inner_max.y),
+        ),
+        UiRect::new(
+            Vec2::new(outer_min.x, inner_min.y),
+            Vec2::new(inner_min.x - outer_min.x, inner.size.y),
+        ),
+        UiRect::new(
+            Vec2::new(inner_max.x, inner_min.y),
+            Vec2::new(outer_max.x - inner_max.x, inner.size.y),
+        ),
+    ]
+}
@@ -0,0 +1,194 @@
+/**
+ * path: /crates/engine_ui/src/image.rs
+ * description: Image quads: a region of a texture stretched, tiled or 9-sliced into a box. The
+ * pieces are cut in physical pixels with whole-pixel edges, so slices and tiles meet without
+ * gaps or seams at any devicePixelRatio.
+ */
+use glam::Vec2;
+
+use crate::draw::{
+    UiDrawList,
+    UiQuad,
+};
+use crate::style::{
+    Color,
+    ImageMode,
+    ImageRegion,
+    ImageSlice,
+    UiStyle,
+};
+use crate::tree::{
+    UiRect,
+    UiTree,
+};
+
+/// Beyond this many copies along an axis (a tiny tile in a large box) the image is stretched.
+const MAX_TILES: usize = 256;
+
+/// A loaded image with the style properties that say how to draw it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ImageDraw {
+    texture: u32,
+    texture_size: Vec2,
+    region: Option<ImageRegion>,
+    slice: Option<ImageSlice>,
+    mode: ImageMode,
+    color: Color,
+}
+
+/// A piece along one axis: where it goes (physical pixels) and the image pixels it shows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Span {
+    dest: (f32, f32),
+    source: (f32, f32),
+}
+
+impl ImageDraw {
+    /// The image at `path` drawn as `style` says; `None` until the renderer loaded it.
+    pub(crate) fn new(tree: &UiTree, path: &str, style: &UiStyle, color: Color) -> Option<Self> {
+        let texture = tree.texture(path)?;
+        let texture_size = tree
+            .texture_size(texture)
+            .filter(|size| size.x > 0.0 && size.y > 0.0)?;
+        Some(Self {
+            texture,
+            texture_size,
+            region: style.image_region,
+            slice: style.image_slice,
+            mode: style.image_mode.unwrap_or_default(),
+            color,
+        })
+    }
+
+    /// Quads filling `rect` (logical pixels) at `scale` physical pixels per logical pixel.
+    pub(crate) fn push(&self, list: &mut UiDrawList, rect: UiRect, scale: f32) {
+        let rect = rect.snap(scale);
+        let region = self.region.unwrap_or(ImageRegion {
+            x: 0.0,
+            y: 0.0,
+            width: self.texture_size.x,
+            height: self.texture_size.y,
+        });
+        let source_min = Vec2::new(region.x, region.y);
+        let source_max = source_min + Vec2::new(region.width, region.height).max(Vec2::ZERO);
+        // Keep linear filtering from blending in the pixels around an atlas region
+        let (lowest, highest) = match self.region {
+            Some(_) => {
+                let center = (source_min + source_max) / 2.0;
+                (
+                    (source_min + 0.5).min(center),
+                    (source_max - 0.5).max(center),
+                )
+            }
+            None => (source_min, source_max),
+        };
+        let slice = self.slice.unwrap_or(ImageSlice::all(0.0));
+        let columns = self.spans(
+            (rect.position.x, rect.max().x),
+            (source_min.x, source_max.x),
+            (slice.left, slice.right),
+            scale,
+        );
+        let rows = self.spans(
+            (rect.position.y, rect.max().y),
+            (source_min.y, source_max.y),
+            (slice.top, slice.bottom),
+            scale,
+        );
+        let uv = |x: f32, y: f32| Vec2::new(x, y).clamp(lowest, highest) / self.texture_size;
+        for row in &rows {
+            for column in &columns {
+                let min = Vec2::new(column.dest.0, row.dest.0);
+                let max = Vec2::new(column.dest.1, row.dest.1);
+                list.push(UiQuad {
+                    rect: UiRect::new(min, max - min),
+                    uv_min: uv(column.source.0, row.source.0),
+                    uv_max: uv(column.source.1, row.source.1),
+                    color: self.color,
+                    texture: self.texture,
+                });
+            }
+        }
+    }
+
+    /// Pieces along one axis: with a slice the two fixed ends (`insets`, image pixels) and the
+    /// middle, else the whole image; the middle is stretched or repeated per `mode`.
+    fn spans(
+        &self,
+        dest: (f32, f32),
+        source: (f32, f32),
+        insets: (f32, f32),
+        scale: f32,
+    ) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let Some(slice) = self.slice else {
+            let tile = (source.1 - source.0) * scale;
+            repeat(&mut spans, dest, source, tile, self.mode);
+            return spans;
+        };
+        let pixels = slice.scale.max(0.0) * scale;
+        let source_length = source.1 - source.0;
+        let start_inset = insets.0.clamp(0.0, source_length);
+        let end_inset = insets.1.clamp(0.0, source_length - start_inset);
+        let length = dest.1 - dest.0;
+        let (mut start, mut end) = ((start_inset * pixels).round(), (end_inset * pixels).round());
+        // Corners larger than the box shrink together
+        if start + end > length {
+            let fit = length / (start + end);
+            start = (start * fit).floor();
+            end = (end * fit).floor();
+        }
+        let middle = (source.0 + start_inset, source.1 - end_inset);
+        spans.push(Span {
+            dest: (dest.0, dest.0 + start),
+            source: (source.0, middle.0),
+        });
+        repeat(
+            &mut spans,
+            (dest.0 + start, dest.1 - end),
+            middle,
+            (middle.1 - middle.0) * pixels,
+            self.mode,
+        );
+        spans.push(Span {
+            dest: (dest.1 - end, dest.1),
+            source: (middle.1, source.1),
+        });
+        spans
+    }
+}
+
+/// Fill `dest` with `source`, stretched or as copies `tile` pixels long. Copies are cut at
+/// whole pixels; a `Tile` copy cut off at the end shows the matching part of the source.
+fn repeat(spans: &mut Vec<Span>, dest: (f32, f32), source: (f32, f32), tile: f32, mode: ImageMode) {
+    let length = dest.1 - dest.0;
+    if length <= 0.0 {
+        return;
+    }
+    // Copies under a pixel long can't be drawn
+    let mode = match tile.is_nan() || tile < 1.0 {
+        true => ImageMode::Stretch,
+        false => mode,
+    };
+    let (count, step) = match mode {
+        ImageMode::Stretch => (1, length),
+        ImageMode::Tile => ((length / tile).ceil() as usize, tile),
+        ImageMode::Round => {
+            let count = ((length / tile).round() as usize).max(1);
+            (count, length / count as f32)
+        }
+    };
+    let (count, step) = match count <= MAX_TILES {
+        true => (count, step),
+        false => (1, length),
+    };
+    for index in 0..count {
+        let start = dest.0 + index as f32 * step;
+        let end = (start + step).min(dest.1);
+        let shown = (end - start) / step;
+        spans.push(Span {
+            dest: (start.round(), end.round()),
+            source: (source.0, source.0 + (source.1 - source.0) * shown),
+        });
+    }
+}
@@ -0,0 +1,402 @@
+/**
+ * path: /crates/engine_ui/src/interaction.rs
+ * description: Pointer, keyboard and gamepad interaction with UI widgets.
+ * `update_ui_interaction` hit-tests the pointer against the last layout, tracks the hovered,
+ * pressed and focused nodes in the `UiTree` (selectors see them as `:hover`, `:pressed`,
+ * `:focus`) and writes this frame's `UiEvents`. The wheel and pointer drags scroll `Scroll`
+ * nodes.
+ */
+use bevy_ecs::prelude::{
+    Local,
+    Res,
+    ResMut,
+    Resource,
+};
+use engine_input::{
+    ButtonInput,
+    Cursor,
+    GamepadButton,
+    Gamepads,
+    KeyCode,
+    MouseButton,
+    MouseWheel,
+    Touches,
+    PIXELS_PER_LINE,
+};
+use glam::Vec2;
+
+use crate::node::{
+    UiNodeKind,
+    UiValue,
+};
+use crate::tree::{
+    UiId,
+    UiTree,
+};
+
+/// Pointer movement (logical pixels) before a press scrolls the content under it instead of
+/// clicking.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Interaction with a node this frame. A press released over the same node is a `Click`, as
+/// is Enter/Space (gamepad South) on the focused node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UiEvent {
+    HoverStart(UiId),
+    HoverEnd(UiId),
+    Press(UiId),
+    /// Sent to the pressed node, wherever the pointer is now.
+    Release(UiId),
+    Click(UiId),
+    /// A toggle or slider changed through the UI (not through `node_mut` or a binding).
+    Changed {
+        id: UiId,
+        value: UiValue,
+    },
+    Focus(UiId),
+    Blur(UiId),
+}
+
+impl UiEvent {
+    pub fn id(&self) -> UiId {
+        match *self {
+            UiEvent::HoverStart(id)
+            | UiEvent::HoverEnd(id)
+            | UiEvent::Press(id)
+            | UiEvent::Release(id)
+            | UiEvent::Click(id)
+            | UiEvent::Changed { id, .. }
+            | UiEvent::Focus(id)
+            | UiEvent::Blur(id) => id,
+        }
+    }
+}
+
+/// This frame's `UiEvent`s, in the order they happened. Not a bevy `Events` queue: the list is
+/// replaced every `PreUpdate`, so read it in the same frame, after `UiSystems` (in `PreUpdate`)
+/// or in `Update`/`PostUpdate`. `FixedUpdate` may run zero or several times a frame and then
+/// misses or repeats events.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiEvents {
+    events: Vec<UiEvent>,
+}
+
+impl UiEvents {
+    pub fn iter(&self) -> impl Iterator<Item = &UiEvent> {
+        self.events.iter()
+    }
+
+    /// Events of one node this frame.
+    pub fn events_for(&self, id: UiId) -> impl Iterator<Item = &UiEvent> {
+        self.events.iter().filter(move |e| e.id() == id)
+    }
+
+    pub fn clicked(&self, id: UiId) -> bool {
+        self.events_for(id).any(|e| matches!(e, UiEvent::Click(_)))
+    }
+
+    /// New value if the widget changed this frame.
+    pub fn changed(&self, id: UiId) -> Option<UiValue> {
+        self.events_for(id).find_map(|e| match *e {
+            UiEvent::Changed { value, .. } => Some(value),
+            _ => None,
+        })
+    }
+
+    pub fn push(&mut self, event: UiEvent) {
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+impl UiTree {
+    /// Nodes Tab moves through: enabled, visible interactive nodes without a negative
+    /// `tab_index`, by `tab_index` and then tree order.
+    pub fn focus_order(&self) -> Vec<UiId> {
+        let mut order: Vec<(i32, UiId)> = self
+            .descendants(self.root())
+            .into_iter()
+            .filter(|id| self.layout_of(*id).is_some() && !self.is_disabled(*id))
+            .filter_map(|id| {
+                let node = self.node(id)?;
+                let index = node.tab_index.unwrap_or(0);
+                (node.kind.is_interactive() && index >= 0).then_some((index, id))
+            })
+            .collect();
+        order.sort_by_key(|(index, _)| *index);
+        order.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// The node after the focused one in `focus_order` (before it if `reverse`), wrapping
+    /// around; the first (last) one if nothing is focused.
+    pub fn next_focus(&self, reverse: bool) -> Option<UiId> {
+        let order = self.focus_order();
+        let current = self
+            .focused()
+            .and_then(|id| order.iter().position(|o| *o == id));
+        let index = match (current, reverse) {
+            (None, false) => 0,
+            (None, true) => order.len().checked_sub(1)?,
+            (Some(i), false) => (i + 1) % order.len(),
+            (Some(i), true) => (i + order.len() - 1) % order.len(),
+        };
+        order.get(index).copied()
+    }
+
+    /// The focusable node nearest to the focused one in `direction` (y down), preferring
+    /// nodes in line with it; the first in `focus_order` if nothing is focused.
+    pub fn focus_in_direction(&self, direction: Vec2) -> Option<UiId> {
+        let order = self.focus_order();
+        let center = |id: UiId| {
+            self.layout_of(id)
+                .map(|l| l.rect.position + l.rect.size / 2.0)
+        };
+        let Some(from) = self.focused().and_then(center) else {
+            return order.first().copied();
+        };
+        let direction = direction.normalize_or_zero();
+        order
+            .into_iter()
+            .filter(|id| Some(*id) != self.focused())
+            .filter_map(|id| {
+                let offset = center(id)? - from;
+                let along = offset.dot(direction);
+                let across = (offset - direction * along).length();
+                (along > 0.0).then_some((along + 2.0 * across, id))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, id)| id)
+    }
+}
+
+/// A press that may turn into scrolling the node under it (and its `Scroll` ancestors).
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollDrag {
+    node: UiId,
+    start: Vec2,
+    last: Vec2,
+    scrolling: bool,
+}
+
+/// Hit-test the pointer, update hover/press/focus, widget values and scroll offsets from the
+/// pointer, wheel, keys and gamepads, and write this frame's `UiEvents`.
+#[allow(clippy::too_many_arguments)]
+pub fn update_ui_interaction(
+    cursor: Res<Cursor>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    wheel: Res<MouseWheel>,
+    touches: Res<Touches>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    mut tree: ResMut<UiTree>,
+    mut events: ResMut<UiEvents>,
+    mut drag: Local<Option<ScrollDrag>>,
+) {
+    let tree = &mut *tree;
+    let events = &mut *events;
+    events.clear();
+
+    // The focused node may have been disabled or hidden since
+    if let Some(id) = tree.focused() {
+        if tree.is_disabled(id) || tree.layout_of(id).is_none() {
+            set_focus(tree, events, None);
+        }
+    }
+
+    // Pointer: the first touch stands in for the left button on touch screens
+    let scale = tree.scale_factor();
+    let position = cursor
+        .position
+        .or_else(|| {
+            touches
+                .iter()
+                .chain(touches.just_ended())
+                .next()
+                .map(|t| t.position)
+        })
+        .map(|p| p / scale);
+    let hit = position.and_then(|p| tree.interactive_at(p));
+    let previous = tree.hovered();
+    if previous != hit {
+        if let Some(id) = previous {
+            events.push(UiEvent::HoverEnd(id));
+        }
+        if let Some(id) = hit {
+            events.push(UiEvent::HoverStart(id));
+        }
+        tree.set_hovered(hit);
+    }
+
+    let down = mouse.pressed(MouseButton::Left) || !touches.is_empty();
+    let just_pressed = mouse.just_pressed(MouseButton::Left)
+        || (!touches.is_empty() && touches.just_started().count() == touches.len());
+    if just_pressed && tree.pressed().is_none() {
+        if let Some(id) = hit {
+            events.push(UiEvent::Press(id));
+            tree.set_pressed(Some(id));
+        }
+        // Pressing elsewhere clears the focus
+        set_focus(tree, events, hit);
+        // Sliders take drags themselves
+        let slider = hit
+            .and_then(|id| tree.node(id))
+            .is_some_and(|node| matches!(node.kind, UiNodeKind::Slider { .. }));
+        *drag = position
+            .filter(|_| !slider)
+            .and_then(|p| Some((tree.node_at(p)?, p)))
+            .map(|(node, p)| ScrollDrag {
+                node,
+                start: p,
+                last: p,
+                scrolling: false,
+            });
+    }
+
+    // Dragging scrolls; once it does, the press no longer clicks
+    if let (Some(scroll), Some(position)) = (drag.as_mut(), position.filter(|_| down)) {
+        if scroll.scrolling || position.distance(scroll.start) >= DRAG_THRESHOLD {
+            if tree.scroll(scroll.node, scroll.last - position) && !scroll.scrolling {
+                scroll.scrolling = true;
+                if let Some(id) = tree.pressed() {
+                    events.push(UiEvent::Release(id));
+                    tree.set_pressed(None);
+                }
+            }
+            scroll.last = position;
+        }
+    }
+    if !down {
+        *drag = None;
+    }
+    if wheel.delta != Vec2::ZERO {
+        if let Some(id) = position.and_then(|p| tree.node_at(p)) {
+            tree.scroll(id, wheel.delta * PIXELS_PER_LINE);
+        }
+    }
+
+    if let Some(id) = tree.pressed() {
+        if let Some(position) = position {
+            drag_slider(tree, events, id, position);
+        }
+        if !down {
+            events.push(UiEvent::Release(id));
+            if hit == Some(id) {
+                activate(tree, events, id);
+            }
+            tree.set_pressed(None);
+        }
+    }
+
+    // Keyboard and gamepad focus navigation
+    let gamepad = |button: GamepadButton| gamepads.iter().any(|(_, g)| g.just_pressed(button));
+    if keys.just_pressed(KeyCode::Tab) {
+        let reverse = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let next = tree.next_focus(reverse);
+        set_focus(tree, events, next);
+    }
+    let directions = [
+        (KeyCode::ArrowUp, GamepadButton::DPadUp, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, GamepadButton::DPadDown, Vec2::Y),
+        (KeyCode::ArrowLeft, GamepadButton::DPadLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, GamepadButton::DPadRight, Vec2::X),
+    ];
+    for (key, button, direction) in directions {
+        if !keys.just_pressed(key) && !gamepad(button) {
+            continue;
+        }
+        // A focused slider takes left/right
+        if let Some(id) = tree.focused() {
+            if direction.y == 0.0 && step_slider(tree, events, id, direction.x) {
+                continue;
+            }
+        }
+        if let Some(next) = tree.focus_in_direction(direction) {
+            set_focus(tree, events, Some(next));
+        }
+    }
+    let confirm = keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space])
+        || gamepad(GamepadButton::South);
+    if let (true, Some(id)) = (confirm, tree.focused()) {
+        activate(tree, events, id);
+    }
+}
+
+fn set_focus(tree: &mut UiTree, events: &mut UiEvents, id: Option<UiId>) {
+    let previous = tree.focused();
+    if previous == id {
+        return;
+    }
+    if let Some(previous) = previous {
+        events.push(UiEvent::Blur(previous));
+    }
+    if let Some(id) = id {
+        events.push(UiEvent::Focus(id));
+        tree.scroll_into_view(id);
+    }
+    tree.set_focus(id);
+}
+
+/// Click a node; a toggle flips first.
+fn activate(tree: &mut UiTree, events: &mut UiEvents, id: UiId) {
+    if let Some(UiValue::Bool(on)) = tree.node(id).and_then(|n| n.kind.value()) {
+        change(tree, events, id, UiValue::Bool(!on));
+    }
+    events.push(UiEvent::Click(id));
+}
+
+/// Set a widget's value, sending `Changed` if it did.
+fn change(tree: &mut UiTree, events: &mut UiEvents, id: UiId, value: UiValue) {
+    let Some(mut kind) = tree.node(id).map(|n| n.kind.clone()) else {
+        return;
+    };
+    if !kind.set_value(value) {
+        return;
+    }
+    if let Some(value) = kind.value() {
+        events.push(UiEvent::Changed { id, value });
+    }
+    if let Some(node) = tree.node_mut(id) {
+        node.kind = kind;
+    }
+}
+
+/// Move a pressed slider's value to the pointer.
+fn drag_slider(tree: &mut UiTree, events: &mut UiEvents, id: UiId, position: Vec2) {
+    let (Some(node), Some(layout)) = (tree.node(id), tree.layout_of(id)) else {
+        return;
+    };
+    let UiNodeKind::Slider { min, max, .. } = node.kind else {
+        return;
+    };
+    let content = layout.content;
+    if content.size.x <= 0.0 {
+        return;
+    }
+    let fraction = ((position.x - content.position.x) / content.size.x).clamp(0.0, 1.0);
+    change(
+        tree,
+        events,
+        id,
+        UiValue::Number(min + fraction * (max - min)),
+    );
+}
+
+/// Move a slider one step (a tenth of its range if continuous) in `sign`'s direction; returns
+/// whether the node is a slider.
+fn step_slider(tree: &mut UiTree, events: &mut UiEvents, id: UiId, sign: f32) -> bool {
+    let Some(UiNodeKind::Slider {
+        value,
+        min,
+        max,
+        step,
+    }) = tree.node(id).map(|n| n.kind.clone())
+    else {
+        return false;
+    };
+    let step = if step > 0.0 { step } else { (max - min) / 10.0 };
+    change(tree, events, id, UiValue::Number(value + sign * step));
+    true
+}
@@ -1,5 +1,129 @@
-// /**
-//  * path: /crates/engine_ui/src/lib.rs
-//  * description: Placeholder for engine UI components.
-//  */
-// Retained-mode UI placeholder using taffy for layout.
+/**
+ * path: /crates/engine_ui/src/lib.rs
+ * description: Retained-mode game UI: a tree of containers, text, images and buttons styled
+ * with flexbox/grid properties and laid out by taffy, built in code or from `.ui.ron` documents.
+ * Widgets react to the pointer, keys and gamepads (`UiEvents`) and can show resource values.
+ * Text is measured and drawn with the fonts in `engine_text::Fonts`. The renderer draws
+ * `UiTree::draw_list` with `GUIPipeline` on top of the scene.
+ */
+use bevy_ecs::prelude::{
+    IntoSystemConfigs,
+    Res,
+    ResMut,
+};
+use bevy_ecs::schedule::SystemSet;
+use engine_core::{
+    EngineApp,
+    Plugin,
+    PostUpdate,
+    PreUpdate,
+};
+use engine_input::InputSystems;
+use engine_text::Fonts;
+
+mod binding;
+mod document;
+mod draw;
+mod image;
+mod interaction;
+mod node;
+mod selector;
+mod style;
+mod tree;
+pub use binding::{
+    apply_ui_changes,
+    update_ui_bindings,
+    UiBinding,
+};
+pub use document::{
+    StyleSheet,
+    Theme,
+    UiDocument,
+    UiDocumentError,
+    UiElement,
+    UI_DOCUMENT_EXTENSION,
+};
+pub use draw::{
+    UiDrawList,
+    UiGeometry,
+    UiQuad,
+};
+pub use interaction::{
+    update_ui_interaction,
+    UiEvent,
+    UiEvents,
+};
+pub use node::{
+    UiNode,
+    UiNodeKind,
+    UiNodeState,
+    UiValue,
+};
+pub use selector::{
+    Selector,
+    SelectorError,
+    Specificity,
+    StyledNode,
+};
+pub use style::{
+    AlignContent,
+    AlignItems,
+    Color,
+    Display,
+    Edges,
+    FlexDirection,
+    FlexWrap,
+    GridLine,
+    GridPlacement,
+    GridTrack,
+    ImageMode,
+    ImageRegion,
+    ImageSlice,
+    Overflow,
+    PositionType,
+    UiStyle,
+    Val,
+    DEFAULT_FONT_SIZE,
+};
+pub use tree::{
+    UiId,
+    UiLayout,
+    UiRect,
+    UiTree,
+    GLYPH_ATLAS_TEXTURE,
+    WHITE_TEXTURE,
+};
+
+/// `UiTree`, `UiEvents`, `Fonts`, `update_ui_interaction` and `apply_ui_changes` in `PreUpdate`
+/// after `InputSystems`, and `update_ui_bindings` and `layout_ui` in `PostUpdate`, after gameplay
+/// changed the tree and the bound resources.
+pub struct UiPlugin;
+
+/// The UI systems; systems reading `UiEvents` in `PreUpdate` or layouts in `PostUpdate` should
+/// run after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UiSystems;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut EngineApp) {
+        app.init_resource::<UiTree>()
+            .init_resource::<UiEvents>()
+            .init_resource::<Fonts>()
+            .add_systems(
+                PreUpdate,
+                (update_ui_interaction, apply_ui_changes)
+                    .chain()
+                    .in_set(UiSystems)
+                    .after(InputSystems),
+            )
+            .add_systems(
+                PostUpdate,
+                (update_ui_bindings, layout_ui).chain().in_set(UiSystems),
+            );
+    }
+}
+
+/// Lay out the tree if it changed (or the canvas or the fonts did).
+pub fn layout_ui(mut tree: ResMut<UiTree>, fonts: Res<Fonts>) {
+    tree.layout(&fonts);
+}
@@ -0,0 +1,215 @@
+/**
+ * path: /crates/engine_ui/src/node.rs
+ * description: What a UI node is: its kind (and value, for widgets), optional name and style.
+ */
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::binding::UiBinding;
+use crate::style::UiStyle;
+
+/// - `Container`: lays out its children, draws its background and border
+/// - `Text`: sized to its (wrapped) text
+/// - `Image`: image asset path (PNG, relative to the served root like scene assets); sized to
+///   the image unless the style sets a size
+/// - `Button`: a container that is meant to be pressed; its children are the label
+/// - `Toggle`: a check box; pressing it flips the value
+/// - `Slider`: a horizontal value in `min..=max`, snapped to multiples of `step` from `min`
+///   (0 = continuous); set by pressing or dragging it, or with left/right while focused
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UiNodeKind {
+    Container,
+    Text(String),
+    Image(String),
+    Button,
+    Toggle(bool),
+    Slider {
+        value: f32,
+        #[serde(default)]
+        min: f32,
+        #[serde(default = "default_slider_max")]
+        max: f32,
+        #[serde(default)]
+        step: f32,
+    },
+}
+
+fn default_slider_max() -> f32 {
+    1.0
+}
+
+/// Value of a `Toggle` or `Slider`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UiValue {
+    Bool(bool),
+    Number(f32),
+}
+
+impl UiNodeKind {
+    /// Kind names, as used by selectors.
+    pub const NAMES: [&'static str; 6] =
+        ["Container", "Text", "Image", "Button", "Toggle", "Slider"];
+
+    /// Name used by selectors (`Button`, `.menu > Text`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            UiNodeKind::Container => "Container",
+            UiNodeKind::Text(_) => "Text",
+            UiNodeKind::Image(_) => "Image",
+            UiNodeKind::Button => "Button",
+            UiNodeKind::Toggle(_) => "Toggle",
+            UiNodeKind::Slider { .. } => "Slider",
+        }
+    }
+
+    /// Whether the pointer and focus navigation can target it.
+    pub fn is_interactive(&self) -> bool {
+        matches!(
+            self,
+            UiNodeKind::Button | UiNodeKind::Toggle(_) | UiNodeKind::Slider { .. }
+        )
+    }
+
+    pub fn value(&self) -> Option<UiValue> {
+        match *self {
+            UiNodeKind::Toggle(on) => Some(UiValue::Bool(on)),
+            UiNodeKind::Slider { value, .. } => Some(UiValue::Number(value)),
+            _ => None,
+        }
+    }
+
+    /// Set a widget's value (a slider's clamped and snapped); returns whether it changed.
+    pub fn set_value(&mut self, new: UiValue) -> bool {
+        match (self, new) {
+            (UiNodeKind::Toggle(on), UiValue::Bool(new)) if *on != new => {
+                *on = new;
+                true
+            }
+            (
+                UiNodeKind::Slider {
+                    value,
+                    min,
+                    max,
+                    step,
+                },
+                UiValue::Number(new),
+            ) => {
+                let mut new = new.clamp(*min, max.max(*min));
+                if *step > 0.0 {
+                    new = (*min + ((new - *min) / *step).round() * *step).min(*max);
+                }
+                let changed = *value != new;
+                *value = new;
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Interaction state of a node, for selectors (`:hover`, `:pressed`, `:focus`, `:disabled`).
+/// `disabled` is also set below a disabled node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UiNodeState {
+    pub hovered: bool,
+    pub pressed: bool,
+    pub focused: bool,
+    pub disabled: bool,
+}
+
+/// - `classes`: for selectors of document styles
+/// - `style`: the node's own style; it wins over document styles
+/// - `disabled`: the node and everything below it ignore the pointer and can't be focused
+/// - `tab_index`: position in the Tab order (lower first, then tree order; default 0);
+///   negative leaves the node out of it
+/// - `bind`: a resource value shown by a text node, or kept in sync with a widget's value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiNode {
+    pub kind: UiNodeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    #[serde(default)]
+    pub style: UiStyle,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_index: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<UiBinding>,
+}
+
+impl UiNode {
+    pub fn new(kind: UiNodeKind) -> Self {
+        Self {
+            kind,
+            name: None,
+            classes: Vec::new(),
+            style: UiStyle::default(),
+            disabled: false,
+            tab_index: None,
+            bind: None,
+        }
+    }
+
+    pub fn container() -> Self {
+        Self::new(UiNodeKind::Container)
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(UiNodeKind::Text(text.into()))
+    }
+
+    pub fn image(path: impl Into<String>) -> Self {
+        Self::new(UiNodeKind::Image(path.into()))
+    }
+
+    pub fn button() -> Self {
+        Self::new(UiNodeKind::Button)
+    }
+
+    pub fn toggle(on: bool) -> Self {
+        Self::new(UiNodeKind::Toggle(on))
+    }
+
+    pub fn slider(value: f32, min: f32, max: f32, step: f32) -> Self {
+        Self::new(UiNodeKind::Slider {
+            value,
+            min,
+            max,
+            step,
+        })
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    pub fn with_style(mut self, style: UiStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_binding(mut self, bind: UiBinding) -> Self {
+        self.bind = Some(bind);
+        self
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+}
@@ -0,0 +1,225 @@
+/**
+ * path: /crates/engine_ui/src/selector.rs
+ * description: Minimal CSS-like selectors for applying document styles to nodes.
+ *   Button              node kind (Container, Text, Image, Button)
+ *   .primary            class
+ *   #play               node name
+ *   Button:hover        state: `:hover`, `:pressed`, `:focus`, `:disabled`, `:checked` (toggle on)
+ *   Button.primary      all of them on one node
+ *   .menu Button        descendant; `.menu > Button` for a direct child
+ *   .title, .subtitle   either selector
+ */
+use thiserror::Error;
+
+use crate::node::{
+    UiNode,
+    UiNodeKind,
+    UiNodeState,
+};
+
+/// A node and its interaction state, as selectors see it.
+pub type StyledNode<'a> = (&'a UiNode, UiNodeState);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PseudoClass {
+    Hover,
+    Pressed,
+    Focus,
+    Disabled,
+    Checked,
+}
+
+impl PseudoClass {
+    fn parse(name: &str) -> Option<PseudoClass> {
+        Some(match name {
+            "hover" => PseudoClass::Hover,
+            "pressed" => PseudoClass::Pressed,
+            "focus" => PseudoClass::Focus,
+            "disabled" => PseudoClass::Disabled,
+            "checked" => PseudoClass::Checked,
+            _ => return None,
+        })
+    }
+
+    fn matches(self, (node, state): StyledNode) -> bool {
+        match self {
+            PseudoClass::Hover => state.hovered,
+            PseudoClass::Pressed => state.pressed,
+            PseudoClass::Focus => state.focused,
+            PseudoClass::Disabled => state.disabled,
+            PseudoClass::Checked => node.kind == UiNodeKind::Toggle(true),
+        }
+    }
+}
+
+/// One node's part of a selector; empty fields match anything.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Compound {
+    kind: Option<String>,
+    name: Option<String>,
+    classes: Vec<String>,
+    states: Vec<PseudoClass>,
+}
+
+impl Compound {
+    fn matches(&self, (node, state): StyledNode) -> bool {
+        self.kind.as_deref().is_none_or(|k| k == node.kind.name())
+            && self
+                .name
+                .as_deref()
+                .is_none_or(|n| node.name.as_deref() == Some(n))
+            && self.classes.iter().all(|c| node.has_class(c))
+            && self.states.iter().all(|s| s.matches((node, state)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// A parsed selector list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    source: String,
+    /// Alternatives (comma-separated); each is compounds from the outermost ancestor to the
+    /// node, with the combinator before every compound but the first.
+    alternatives: Vec<Vec<(Combinator, Compound)>>,
+}
+
+/// (names, classes and states, kinds) of the most specific matching alternative; compared like
+/// CSS specificity.
+pub type Specificity = (u32, u32, u32);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("selector `{selector}`: {message}")]
+pub struct SelectorError {
+    pub selector: String,
+    pub message: String,
+}
+
+impl Selector {
+    pub fn parse(source: &str) -> Result<Selector, SelectorError> {
+        let error = |message: String| SelectorError {
+            selector: source.to_string(),
+            message,
+        };
+        let mut alternatives = Vec::new();
+        for alternative in source.split(',') {
+            let mut parts = Vec::new();
+            let mut combinator = Combinator::Descendant;
+            for token in alternative.replace('>', " > ").split_whitespace() {
+                if token == ">" {
+                    if parts.is_empty() || combinator == Combinator::Child {
+                        return Err(error("`>` needs a selector on both sides".into()));
+                    }
+                    combinator = Combinator::Child;
+                    continue;
+                }
+                parts.push((combinator, parse_compound(token).map_err(error)?));
+                combinator = Combinator::Descendant;
+            }
+            if parts.is_empty() {
+                return Err(error("empty selector".into()));
+            }
+            if combinator == Combinator::Child {
+                return Err(error("`>` needs a selector on both sides".into()));
+            }
+            alternatives.push(parts);
+        }
+        Ok(Selector {
+            source: source.trim().to_string(),
+            alternatives,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Specificity of the most specific alternative matching `node`, whose ancestors are
+    /// `ancestors` (outermost first); `None` if none matches.
+    pub fn matches(&self, node: StyledNode, ancestors: &[StyledNode]) -> Option<Specificity> {
+        self.alternatives
+            .iter()
+            .filter(|parts| matches_parts(parts, node, ancestors))
+            .map(|parts| specificity(parts))
+            .max()
+    }
+}
+
+fn parse_compound(token: &str) -> Result<Compound, String> {
+    let mut compound = Compound::default();
+    // Split before every `.`, `#` and `:`, keeping the marker with its identifier
+    let mut rest = token;
+    let kind_end = rest.find(['.', '#', ':']).unwrap_or(rest.len());
+    if kind_end > 0 {
+        let kind = &rest[..kind_end];
+        if kind != "*" && !UiNodeKind::NAMES.contains(&kind) {
+            return Err(format!("unknown node kind `{kind}`"));
+        }
+        compound.kind = (kind != "*").then(|| kind.to_string());
+        rest = &rest[kind_end..];
+    }
+    while let Some(marker) = rest.chars().next() {
+        let end = rest[1..]
+            .find(['.', '#', ':'])
+            .map_or(rest.len(), |i| i + 1);
+        let ident = &rest[1..end];
+        if ident.is_empty()
+            || !ident
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!("bad identifier in `{token}`"));
+        }
+        match marker {
+            '.' => compound.classes.push(ident.to_string()),
+            ':' => match PseudoClass::parse(ident) {
+                Some(state) => compound.states.push(state),
+                None => return Err(format!("unknown state `:{ident}`")),
+            },
+            _ if compound.name.is_some() => return Err(format!("two names in `{token}`")),
+            _ => compound.name = Some(ident.to_string()),
+        }
+        rest = &rest[end..];
+    }
+    Ok(compound)
+}
+
+fn matches_parts(
+    parts: &[(Combinator, Compound)],
+    node: StyledNode,
+    ancestors: &[StyledNode],
+) -> bool {
+    let Some(((combinator, last), outer)) = parts.split_last() else {
+        return true;
+    };
+    if !last.matches(node) {
+        return false;
+    }
+    if outer.is_empty() {
+        return true;
+    }
+    match combinator {
+        Combinator::Child => ancestors
+            .split_last()
+            .is_some_and(|(parent, above)| matches_parts(outer, *parent, above)),
+        Combinator::Descendant => (0..ancestors.len())
+            .rev()
+            .any(|i| matches_parts(outer, ancestors[i], &ancestors[..i])),
+    }
+}
+
+fn specificity(parts: &[(Combinator, Compound)]) -> Specificity {
+    parts
+        .iter()
+        .fold((0, 0, 0), |(names, classes, kinds), (_, compound)| {
+            (
+                names + compound.name.is_some() as u32,
+                classes + (compound.classes.len() + compound.states.len()) as u32,
+                kinds + compound.kind.is_some() as u32,
+            )
+        })
+}
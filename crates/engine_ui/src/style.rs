@@ -0,0 +1,552 @@
+/**
+ * path: /crates/engine_ui/src/style.rs
+ * description: Node styles: box model, flexbox and grid properties (mapped onto taffy) plus the
+ * visual properties the draw list uses. Lengths are logical (CSS) pixels.
+ */
+use engine_text::TextAlign;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use taffy::style_helpers::{
+    auto,
+    fr,
+    length,
+    line,
+    max_content,
+    min_content,
+    percent,
+    span,
+};
+
+/// RGBA (0..1), as in scene components.
+pub type Color = (f32, f32, f32, f32);
+
+/// A length; `Percent` is 0..1 of the parent's size.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Val {
+    #[default]
+    Auto,
+    Px(f32),
+    Percent(f32),
+}
+
+/// Per-edge lengths (margin, padding, border widths, inset). `Auto` only means something for
+/// margins and insets; elsewhere it's 0.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Edges {
+    pub left: Val,
+    pub right: Val,
+    pub top: Val,
+    pub bottom: Val,
+}
+
+impl Edges {
+    pub fn all(value: Val) -> Self {
+        Self {
+            left: value,
+            right: value,
+            top: value,
+            bottom: value,
+        }
+    }
+
+    pub fn px(value: f32) -> Self {
+        Self::all(Val::Px(value))
+    }
+
+    /// `horizontal` for left/right, `vertical` for top/bottom.
+    pub fn axes(horizontal: Val, vertical: Val) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Display {
+    #[default]
+    Flex,
+    Grid,
+    Block,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PositionType {
+    #[default]
+    Relative,
+    Absolute,
+}
+
+/// What happens to children that don't fit the padding box.
+/// - `Visible`: they are drawn outside it
+/// - `Hidden`: they are clipped to it
+/// - `Scroll`: they are clipped, and the wheel or dragging scrolls them
+///   (`UiTree::scroll_offset`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    Hidden,
+    Scroll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FlexDirection {
+    #[default]
+    Row,
+    Column,
+    RowReverse,
+    ColumnReverse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FlexWrap {
+    #[default]
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+/// Distribution of lines or items along an axis (`justify_content`, `align_content`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlignContent {
+    Start,
+    End,
+    Center,
+    Stretch,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+/// Alignment of items in their line or cell (`align_items`, `align_self`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlignItems {
+    Start,
+    End,
+    Center,
+    Baseline,
+    Stretch,
+}
+
+/// Size of a grid row or column.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GridTrack {
+    Auto,
+    Px(f32),
+    Percent(f32),
+    Fr(f32),
+    MinContent,
+    MaxContent,
+}
+
+/// Grid line of one edge of an item; lines are 1-based, negative counts from the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GridPlacement {
+    #[default]
+    Auto,
+    Line(i16),
+    Span(u16),
+}
+
+/// Where an item starts and ends along one grid axis, e.g. `(start: Line(1), end: Span(2))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridLine {
+    pub start: GridPlacement,
+    pub end: GridPlacement,
+}
+
+/// Part of an image in its pixels (from the top-left), e.g. one picture of an atlas.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ImageRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// 9-slice insets in image pixels: the corners keep their size, the edges stretch (or tile)
+/// along the box and the centre fills the rest. `scale` is logical pixels per image pixel for
+/// corners, edges and tiles (e.g. 0.5 for @2x art).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSlice {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    pub scale: f32,
+}
+
+impl Default for ImageSlice {
+    fn default() -> Self {
+        Self::all(0.0)
+    }
+}
+
+impl ImageSlice {
+    pub fn all(inset: f32) -> Self {
+        Self {
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+            scale: 1.0,
+        }
+    }
+}
+
+/// How an image (or, when sliced, its edges and centre) fills its box.
+/// - `Stretch`: scaled to the box
+/// - `Tile`: repeated at its size from the top-left, cut off at the far edges
+/// - `Round`: repeated a whole number of times, each copy scaled a little to fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ImageMode {
+    #[default]
+    Stretch,
+    Tile,
+    Round,
+}
+
+/// Style of a node. Unset (`None`) properties use the CSS initial value, so a style only lists
+/// what it changes.
+/// - `color`: text colour, and the tint of images
+/// - `font_size`: logical pixels
+/// - `font`: name of a loaded font (see `engine_text::Fonts`); the default font if unset
+/// - `background_image`: image path drawn over the border box (above `background`, below the
+///   border), tinted by `background` if that is set
+/// - `image_region`, `image_slice`, `image_mode`: which part of the image an `Image` node or
+///   `background_image` shows and how it fills the box; unsliced images are sized 1 logical
+///   pixel per image pixel
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiStyle {
+    pub display: Option<Display>,
+    pub position: Option<PositionType>,
+    pub inset: Option<Edges>,
+    pub width: Option<Val>,
+    pub height: Option<Val>,
+    pub min_width: Option<Val>,
+    pub min_height: Option<Val>,
+    pub max_width: Option<Val>,
+    pub max_height: Option<Val>,
+    pub aspect_ratio: Option<f32>,
+    pub margin: Option<Edges>,
+    pub padding: Option<Edges>,
+    pub border: Option<Edges>,
+    pub overflow: Option<Overflow>,
+
+    pub flex_direction: Option<FlexDirection>,
+    pub flex_wrap: Option<FlexWrap>,
+    pub flex_grow: Option<f32>,
+    pub flex_shrink: Option<f32>,
+    pub flex_basis: Option<Val>,
+    pub justify_content: Option<AlignContent>,
+    pub align_content: Option<AlignContent>,
+    pub align_items: Option<AlignItems>,
+    pub align_self: Option<AlignItems>,
+    pub gap: Option<Val>,
+
+    pub grid_template_columns: Option<Vec<GridTrack>>,
+    pub grid_template_rows: Option<Vec<GridTrack>>,
+    pub grid_column: Option<GridLine>,
+    pub grid_row: Option<GridLine>,
+
+    pub background: Option<Color>,
+    pub background_image: Option<String>,
+    pub image_region: Option<ImageRegion>,
+    pub image_slice: Option<ImageSlice>,
+    pub image_mode: Option<ImageMode>,
+    pub border_color: Option<Color>,
+    pub color: Option<Color>,
+    pub font_size: Option<f32>,
+    pub font: Option<String>,
+    pub text_align: Option<TextAlign>,
+}
+
+/// Font size of text without a `font_size`.
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// Overwrite the fields of `$target` that are set in `$source`.
+macro_rules! merge_fields {
+    ($target:ident, $source:ident, $($field:ident),* $(,)?) => {
+        $(
+            if $source.$field.is_some() {
+                $target.$field = $source.$field.clone();
+            }
+        )*
+    };
+}
+
+impl UiStyle {
+    /// Apply `other` on top of this style (its set properties win).
+    pub fn merge(&mut self, other: &UiStyle) {
+        merge_fields!(
+            self,
+            other,
+            display,
+            position,
+            inset,
+            width,
+            height,
+            min_width,
+            min_height,
+            max_width,
+            max_height,
+            aspect_ratio,
+            margin,
+            padding,
+            border,
+            overflow,
+            flex_direction,
+            flex_wrap,
+            flex_grow,
+            flex_shrink,
+            flex_basis,
+            justify_content,
+            align_content,
+            align_items,
+            align_self,
+            gap,
+            grid_template_columns,
+            grid_template_rows,
+            grid_column,
+            grid_row,
+            background,
+            background_image,
+            image_region,
+            image_slice,
+            image_mode,
+            border_color,
+            color,
+            font_size,
+            font,
+            text_align,
+        );
+    }
+
+    /// Take the inherited properties (`color`, `font_size`, `font`, `text_align`) from the
+    /// parent where unset, as CSS does.
+    pub fn inherit(&mut self, parent: &UiStyle) {
+        self.color = self.color.or(parent.color);
+        self.font_size = self.font_size.or(parent.font_size);
+        if self.font.is_none() {
+            self.font = parent.font.clone();
+        }
+        self.text_align = self.text_align.or(parent.text_align);
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.display == Some(Display::None)
+    }
+
+    /// Whether children are clipped to the padding box (`Hidden` or `Scroll`).
+    pub fn clips(&self) -> bool {
+        matches!(self.overflow, Some(Overflow::Hidden | Overflow::Scroll))
+    }
+
+    pub fn is_scrollable(&self) -> bool {
+        self.overflow == Some(Overflow::Scroll)
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.font_size.unwrap_or(DEFAULT_FONT_SIZE)
+    }
+
+    pub fn text_align(&self) -> TextAlign {
+        self.text_align.unwrap_or_default()
+    }
+
+    pub fn color(&self) -> Color {
+        self.color.unwrap_or((1.0, 1.0, 1.0, 1.0))
+    }
+
+    /// Layout part of the style.
+    pub fn to_taffy(&self) -> taffy::Style {
+        let mut style = taffy::Style::default();
+        if let Some(display) = self.display {
+            style.display = match display {
+                Display::Flex => taffy::Display::Flex,
+                Display::Grid => taffy::Display::Grid,
+                Display::Block => taffy::Display::Block,
+                Display::None => taffy::Display::None,
+            };
+        }
+        if let Some(position) = self.position {
+            style.position = match position {
+                PositionType::Relative => taffy::Position::Relative,
+                PositionType::Absolute => taffy::Position::Absolute,
+            };
+        }
+        if let Some(inset) = self.inset {
+            style.inset = edges(inset, auto_length);
+        }
+        let sizes = [
+            (self.width, &mut style.size.width),
+            (self.height, &mut style.size.height),
+            (self.min_width, &mut style.min_size.width),
+            (self.min_height, &mut style.min_size.height),
+            (self.max_width, &mut style.max_size.width),
+            (self.max_height, &mut style.max_size.height),
+            (self.flex_basis, &mut style.flex_basis),
+        ];
+        for (value, target) in sizes {
+            if let Some(value) = value {
+                *target = dimension(value);
+            }
+        }
+        style.aspect_ratio = self.aspect_ratio;
+        if let Some(margin) = self.margin {
+            style.margin = edges(margin, auto_length);
+        }
+        if let Some(padding) = self.padding {
+            style.padding = edges(padding, length_percentage);
+        }
+        if let Some(border) = self.border {
+            style.border = edges(border, length_percentage);
+        }
+        if let Some(overflow) = self.overflow {
+            let overflow = match overflow {
+                Overflow::Visible => taffy::Overflow::Visible,
+                Overflow::Hidden => taffy::Overflow::Hidden,
+                Overflow::Scroll => taffy::Overflow::Scroll,
+            };
+            style.overflow = taffy::Point {
+                x: overflow,
+                y: overflow,
+            };
+        }
+
+        if let Some(direction) = self.flex_direction {
+            style.flex_direction = match direction {
+                FlexDirection::Row => taffy::FlexDirection::Row,
+                FlexDirection::Column => taffy::FlexDirection::Column,
+                FlexDirection::RowReverse => taffy::FlexDirection::RowReverse,
+                FlexDirection::ColumnReverse => taffy::FlexDirection::ColumnReverse,
+            };
+        }
+        if let Some(wrap) = self.flex_wrap {
+            style.flex_wrap = match wrap {
+                FlexWrap::NoWrap => taffy::FlexWrap::NoWrap,
+                FlexWrap::Wrap => taffy::FlexWrap::Wrap,
+                FlexWrap::WrapReverse => taffy::FlexWrap::WrapReverse,
+            };
+        }
+        if let Some(grow) = self.flex_grow {
+            style.flex_grow = grow;
+        }
+        if let Some(shrink) = self.flex_shrink {
+            style.flex_shrink = shrink;
+        }
+        style.justify_content = self.justify_content.map(align_content);
+        style.align_content = self.align_content.map(align_content);
+        style.align_items = self.align_items.map(align_items);
+        style.align_self = self.align_self.map(align_items);
+        if let Some(gap) = self.gap {
+            let gap = length_percentage(gap);
+            style.gap = taffy::Size {
+                width: gap,
+                height: gap,
+            };
+        }
+
+        if let Some(columns) = &self.grid_template_columns {
+            style.grid_template_columns = columns.iter().copied().map(track).collect();
+        }
+        if let Some(rows) = &self.grid_template_rows {
+            style.grid_template_rows = rows.iter().copied().map(track).collect();
+        }
+        if let Some(column) = self.grid_column {
+            style.grid_column = grid_line(column);
+        }
+        if let Some(row) = self.grid_row {
+            style.grid_row = grid_line(row);
+        }
+        style
+    }
+}
+
+fn dimension(value: Val) -> taffy::Dimension {
+    match value {
+        Val::Auto => taffy::Dimension::Auto,
+        Val::Px(px) => length(px),
+        Val::Percent(fraction) => percent(fraction),
+    }
+}
+
+fn auto_length(value: Val) -> taffy::LengthPercentageAuto {
+    match value {
+        Val::Auto => taffy::LengthPercentageAuto::Auto,
+        Val::Px(px) => length(px),
+        Val::Percent(fraction) => percent(fraction),
+    }
+}
+
+fn length_percentage(value: Val) -> taffy::LengthPercentage {
+    match value {
+        Val::Auto => length(0.0),
+        Val::Px(px) => length(px),
+        Val::Percent(fraction) => percent(fraction),
+    }
+}
+
+fn edges<T>(edges: Edges, map: impl Fn(Val) -> T) -> taffy::Rect<T> {
+    taffy::Rect {
+        left: map(edges.left),
+        right: map(edges.right),
+        top: map(edges.top),
+        bottom: map(edges.bottom),
+    }
+}
+
+fn align_content(value: AlignContent) -> taffy::AlignContent {
+    match value {
+        AlignContent::Start => taffy::AlignContent::Start,
+        AlignContent::End => taffy::AlignContent::End,
+        AlignContent::Center => taffy::AlignContent::Center,
+        AlignContent::Stretch => taffy::AlignContent::Stretch,
+        AlignContent::SpaceBetween => taffy::AlignContent::SpaceBetween,
+        AlignContent::SpaceAround => taffy::AlignContent::SpaceAround,
+        AlignContent::SpaceEvenly => taffy::AlignContent::SpaceEvenly,
+    }
+}
+
+fn align_items(value: AlignItems) -> taffy::AlignItems {
+    match value {
+        AlignItems::Start => taffy::AlignItems::Start,
+        AlignItems::End => taffy::AlignItems::End,
+        AlignItems::Center => taffy::AlignItems::Center,
+        AlignItems::Baseline => taffy::AlignItems::Baseline,
+        AlignItems::Stretch => taffy::AlignItems::Stretch,
+    }
+}
+
+fn track(value: GridTrack) -> taffy::TrackSizingFunction {
+    match value {
+        GridTrack::Auto => auto(),
+        GridTrack::Px(px) => length(px),
+        GridTrack::Percent(fraction) => percent(fraction),
+        GridTrack::Fr(flex) => fr(flex),
+        GridTrack::MinContent => min_content(),
+        GridTrack::MaxContent => max_content(),
+    }
+}
+
+fn grid_line(value: GridLine) -> taffy::Line<taffy::GridPlacement> {
+    let placement = |p: GridPlacement| match p {
+        GridPlacement::Auto => taffy::GridPlacement::Auto,
+        GridPlacement::Line(index) => line(index),
+        GridPlacement::Span(count) => span(count),
+    };
+    taffy::Line {
+        start: placement(value.start),
+        end: placement(value.end),
+    }
+}
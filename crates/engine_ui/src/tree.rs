@@ -0,0 +1,752 @@
+/**
+ * path: /crates/engine_ui/src/tree.rs
+ * description: The retained UI node tree and its layout.
+ * Nodes live in a taffy tree; any change marks the tree dirty and the next `layout` recomputes
+ * styles (style sheets, inheritance) and the layout against the canvas size, resolving absolute
+ * rects (moved by the scroll offsets of `Scroll` ancestors) for drawing and hit-testing.
+ */
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::Resource;
+use engine_text::{
+    measure_text,
+    Fonts,
+};
+use glam::Vec2;
+use taffy::prelude::{
+    AvailableSpace,
+    NodeId,
+    Size,
+    TaffyTree,
+};
+use taffy::TraversePartialTree;
+
+use crate::document::StyleSheet;
+use crate::node::{
+    UiNode,
+    UiNodeKind,
+    UiNodeState,
+};
+use crate::selector::StyledNode;
+use crate::style::{
+    UiStyle,
+    Val,
+};
+
+/// Texture id of plain white, used for untextured quads.
+pub const WHITE_TEXTURE: u32 = 0;
+
+/// Texture id of the glyph atlas (`engine_text::GlyphAtlas`), used for text.
+pub const GLYPH_ATLAS_TEXTURE: u32 = u32::MAX;
+
+/// Handle of a node in a `UiTree`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UiId(NodeId);
+
+/// Axis-aligned rect; `position` is the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UiRect {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl UiRect {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self { position, size }
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.position + self.size
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.position).all() && point.cmplt(self.max()).all()
+    }
+
+    /// The overlap of both rects (empty, at the far corner of the overlap, if there is none).
+    pub fn intersect(&self, other: &UiRect) -> UiRect {
+        let min = self.position.max(other.position);
+        let max = self.max().min(other.max());
+        UiRect::new(min, (max - min).max(Vec2::ZERO))
+    }
+
+    pub fn scale(&self, factor: f32) -> UiRect {
+        UiRect::new(self.position * factor, self.size * factor)
+    }
+
+    /// Scaled, with its edges rounded to whole pixels, so neighbouring rects share edges
+    /// without gaps or blurred seams at any devicePixelRatio.
+    pub fn snap(&self, factor: f32) -> UiRect {
+        let min = (self.position * factor).round();
+        let max = (self.max() * factor).round();
+        UiRect::new(min, max - min)
+    }
+}
+
+/// Where a node ended up, in logical pixels from the top-left of the canvas.
+/// - `rect`: border box
+/// - `padding_box`: inside the border
+/// - `content`: inside the padding (where text and images go)
+/// - `clip`: the part of the canvas the node is visible in, if ancestors clip their overflow
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UiLayout {
+    pub rect: UiRect,
+    pub padding_box: UiRect,
+    pub content: UiRect,
+    pub clip: Option<UiRect>,
+}
+
+/// A node, its computed style (style sheet rules, inherited properties, its own style) and
+/// how far its content is scrolled. Document updates replace `node` and keep the offset.
+struct NodeData {
+    node: UiNode,
+    style: UiStyle,
+    scroll: Vec2,
+}
+
+/// The game UI. Lengths are logical pixels; the canvas is `size` logical pixels large and
+/// `scale_factor` physical pixels per logical pixel (devicePixelRatio).
+#[derive(Resource)]
+pub struct UiTree {
+    taffy: TaffyTree<NodeData>,
+    root: NodeId,
+    size: Vec2,
+    scale_factor: f32,
+    layouts: HashMap<UiId, UiLayout>,
+    style_sheets: HashMap<NodeId, StyleSheet>,
+    restyle: bool,
+    dirty: bool,
+    fonts_generation: u64,
+    textures: HashMap<String, u32>,
+    texture_sizes: HashMap<u32, Vec2>,
+    new_textures: Vec<(u32, String)>,
+    hovered: Option<UiId>,
+    pressed: Option<UiId>,
+    focused: Option<UiId>,
+}
+
+impl Default for UiTree {
+    fn default() -> Self {
+        let mut taffy = TaffyTree::new();
+        let root = taffy
+            .new_leaf_with_context(
+                taffy::Style::default(),
+                NodeData {
+                    node: UiNode::container(),
+                    style: UiStyle::default(),
+                    scroll: Vec2::ZERO,
+                },
+            )
+            .expect("create UI root");
+        Self {
+            taffy,
+            root,
+            size: Vec2::ZERO,
+            scale_factor: 1.0,
+            layouts: HashMap::new(),
+            style_sheets: HashMap::new(),
+            restyle: true,
+            dirty: true,
+            fonts_generation: 0,
+            textures: HashMap::new(),
+            texture_sizes: HashMap::new(),
+            new_textures: Vec::new(),
+            hovered: None,
+            pressed: None,
+            focused: None,
+        }
+    }
+}
+
+impl UiTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Node covering the whole canvas; top-level nodes are its children.
+    pub fn root(&self) -> UiId {
+        UiId(self.root)
+    }
+
+    /// Canvas size in physical pixels and the devicePixelRatio.
+    pub fn set_viewport(&mut self, width: u32, height: u32, scale_factor: f32) {
+        let scale_factor = if scale_factor > 0.0 {
+            scale_factor
+        } else {
+            1.0
+        };
+        let size = Vec2::new(width as f32, height as f32) / scale_factor;
+        if size != self.size || scale_factor != self.scale_factor {
+            self.size = size;
+            self.scale_factor = scale_factor;
+            self.restyle = true;
+            self.dirty = true;
+        }
+    }
+
+    /// Canvas size in logical pixels.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Add `node` as the last child of `parent`.
+    pub fn spawn(&mut self, parent: UiId, node: UiNode) -> UiId {
+        let count = self.taffy.child_count(parent.0);
+        self.insert(parent, count, node)
+    }
+
+    /// Add `node` as child `index` of `parent` (clamped to the end).
+    pub fn insert(&mut self, parent: UiId, index: usize, node: UiNode) -> UiId {
+        if let UiNodeKind::Image(path) = &node.kind {
+            self.texture_id(path);
+        }
+        let data = NodeData {
+            node,
+            style: UiStyle::default(),
+            scroll: Vec2::ZERO,
+        };
+        let id = self
+            .taffy
+            .new_leaf_with_context(UiStyle::default().to_taffy(), data)
+            .expect("create UI node");
+        let index = index.min(self.taffy.child_count(parent.0));
+        self.taffy
+            .insert_child_at_index(parent.0, index, id)
+            .expect("insert UI node");
+        self.restyle = true;
+        self.dirty = true;
+        UiId(id)
+    }
+
+    /// Remove a node and everything below it. The root can't be removed.
+    pub fn remove(&mut self, id: UiId) {
+        if id.0 == self.root || self.taffy.get_node_context(id.0).is_none() {
+            return;
+        }
+        for child in self.children(id) {
+            self.remove(child);
+        }
+        // taffy doesn't invalidate the parent's cached layout on removal
+        if let Some(parent) = self.taffy.parent(id.0) {
+            let _ = self.taffy.mark_dirty(parent);
+        }
+        let _ = self.taffy.remove(id.0);
+        self.layouts.remove(&id);
+        self.style_sheets.remove(&id.0);
+        for state in [&mut self.hovered, &mut self.pressed, &mut self.focused] {
+            if *state == Some(id) {
+                *state = None;
+            }
+        }
+        self.restyle = true;
+        self.dirty = true;
+    }
+
+    /// Move `id` to child `index` of `parent` (clamped to the end); `parent` must not be below
+    /// `id`.
+    pub fn move_node(&mut self, id: UiId, parent: UiId, index: usize) {
+        if id.0 == self.root || self.taffy.get_node_context(id.0).is_none() {
+            return;
+        }
+        if let Some(old_parent) = self.taffy.parent(id.0) {
+            let _ = self.taffy.remove_child(old_parent, id.0);
+        }
+        let index = index.min(self.taffy.child_count(parent.0));
+        let _ = self.taffy.insert_child_at_index(parent.0, index, id.0);
+        self.restyle = true;
+        self.dirty = true;
+    }
+
+    /// Remove all children of `id`.
+    pub fn clear_children(&mut self, id: UiId) {
+        for child in self.children(id) {
+            self.remove(child);
+        }
+    }
+
+    pub fn node(&self, id: UiId) -> Option<&UiNode> {
+        self.taffy.get_node_context(id.0).map(|data| &data.node)
+    }
+
+    /// Mutable access; styles and layout are recomputed on the next `layout`.
+    pub fn node_mut(&mut self, id: UiId) -> Option<&mut UiNode> {
+        // The content may change its measured size
+        let _ = self.taffy.mark_dirty(id.0);
+        let data = self.taffy.get_node_context_mut(id.0)?;
+        self.restyle = true;
+        self.dirty = true;
+        Some(&mut data.node)
+    }
+
+    /// Computed style of a node as of the last `layout`.
+    pub fn style(&self, id: UiId) -> Option<&UiStyle> {
+        self.taffy.get_node_context(id.0).map(|data| &data.style)
+    }
+
+    /// Style rules for `id` and everything below it; replaces the rules of an enclosing node
+    /// there.
+    pub fn set_style_sheet(&mut self, id: UiId, sheet: StyleSheet) {
+        self.style_sheets.insert(id.0, sheet);
+        self.restyle = true;
+        self.dirty = true;
+    }
+
+    pub fn style_sheet(&self, id: UiId) -> Option<&StyleSheet> {
+        self.style_sheets.get(&id.0)
+    }
+
+    pub fn parent(&self, id: UiId) -> Option<UiId> {
+        self.taffy.parent(id.0).map(UiId)
+    }
+
+    pub fn children(&self, id: UiId) -> Vec<UiId> {
+        self.taffy
+            .children(id.0)
+            .map(|children| children.into_iter().map(UiId).collect())
+            .unwrap_or_default()
+    }
+
+    /// `id` and its descendants, parents before children, siblings in order (drawing order).
+    pub fn descendants(&self, id: UiId) -> Vec<UiId> {
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(next) = stack.pop() {
+            out.push(next);
+            stack.extend(self.children(next).into_iter().rev());
+        }
+        out
+    }
+
+    /// First node named `name`, in drawing order.
+    pub fn find(&self, name: &str) -> Option<UiId> {
+        self.descendants(self.root())
+            .into_iter()
+            .find(|id| self.node(*id).and_then(|n| n.name.as_deref()) == Some(name))
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Recompute the layout if anything changed since the last one (including the fonts text
+    /// is measured with); returns whether it did.
+    pub fn layout(&mut self, fonts: &Fonts) -> bool {
+        if fonts.generation() != self.fonts_generation {
+            self.fonts_generation = fonts.generation();
+            for id in self.descendants(self.root()) {
+                if matches!(self.node(id).map(|n| &n.kind), Some(UiNodeKind::Text(_))) {
+                    let _ = self.taffy.mark_dirty(id.0);
+                    self.dirty = true;
+                }
+            }
+        }
+        if !self.dirty {
+            return false;
+        }
+        if std::mem::take(&mut self.restyle) {
+            self.restyle_all();
+        }
+
+        let texture_sizes = &self.texture_sizes;
+        let textures = &self.textures;
+        let available = Size {
+            width: AvailableSpace::Definite(self.size.x),
+            height: AvailableSpace::Definite(self.size.y),
+        };
+        let _ = self.taffy.compute_layout_with_measure(
+            self.root,
+            available,
+            |known, available, _, node, _| {
+                let Some(NodeData { node, style, .. }) = node else {
+                    return Size::ZERO;
+                };
+                let intrinsic = match &node.kind {
+                    UiNodeKind::Text(text) => {
+                        let max_width = known.width.or(match available.width {
+                            AvailableSpace::Definite(width) => Some(width),
+                            AvailableSpace::MinContent => Some(0.0),
+                            AvailableSpace::MaxContent => None,
+                        });
+                        let size = measure_text(
+                            fonts,
+                            style.font.as_deref(),
+                            text,
+                            style.font_size(),
+                            max_width,
+                        );
+                        // Whole pixels, so the drawn text wraps the same in the rounded layout
+                        return to_size(known, size.ceil());
+                    }
+                    UiNodeKind::Image(path) => match style.image_region {
+                        Some(region) => Some(Vec2::new(region.width, region.height)),
+                        None => textures
+                            .get(path)
+                            .and_then(|id| texture_sizes.get(id))
+                            .copied(),
+                    },
+                    // Widgets default to a size relative to the font
+                    UiNodeKind::Toggle(_) => {
+                        return to_size(known, Vec2::splat(style.font_size()));
+                    }
+                    UiNodeKind::Slider { .. } => {
+                        return to_size(known, Vec2::new(10.0, 1.0) * style.font_size());
+                    }
+                    UiNodeKind::Container | UiNodeKind::Button => None,
+                };
+                let Some(intrinsic) = intrinsic.filter(|s| s.x > 0.0 && s.y > 0.0) else {
+                    return to_size(known, Vec2::ZERO);
+                };
+                // Keep the image's aspect ratio when only one side is known
+                let size = match (known.width, known.height) {
+                    (Some(width), None) => Vec2::new(width, width * intrinsic.y / intrinsic.x),
+                    (None, Some(height)) => Vec2::new(height * intrinsic.x / intrinsic.y, height),
+                    _ => intrinsic,
+                };
+                to_size(known, size)
+            },
+        );
+
+        self.layouts.clear();
+        self.collect_layouts(self.root, Vec2::ZERO, None);
+        self.dirty = false;
+        true
+    }
+
+    /// Layout of a node as of the last `layout`; `None` for hidden nodes (and their subtrees).
+    pub fn layout_of(&self, id: UiId) -> Option<&UiLayout> {
+        self.layouts.get(&id)
+    }
+
+    /// Topmost node containing a logical-pixel position, by drawing order; clipped parts of
+    /// nodes don't count.
+    pub fn node_at(&self, position: Vec2) -> Option<UiId> {
+        self.descendants(self.root()).into_iter().rev().find(|id| {
+            self.layout_of(*id).is_some_and(|l| {
+                l.rect.contains(position) && l.clip.is_none_or(|clip| clip.contains(position))
+            })
+        })
+    }
+
+    /// How far a `Scroll` node's content is scrolled, in logical pixels (down and right are
+    /// positive).
+    pub fn scroll_offset(&self, id: UiId) -> Vec2 {
+        self.taffy
+            .get_node_context(id.0)
+            .map_or(Vec2::ZERO, |data| data.scroll)
+    }
+
+    /// Scroll a node's content; the offset is clamped to the content on the next `layout` (and
+    /// reset there unless the node is `Scroll`).
+    pub fn set_scroll_offset(&mut self, id: UiId, offset: Vec2) {
+        let Some(data) = self.taffy.get_node_context_mut(id.0) else {
+            return;
+        };
+        let offset = offset.max(Vec2::ZERO);
+        if data.scroll != offset {
+            data.scroll = offset;
+            self.dirty = true;
+        }
+    }
+
+    /// Largest scroll offset of a node as of the last `layout`: how far its content (and
+    /// padding) reaches past the padding box. Zero unless it is `Scroll`.
+    pub fn max_scroll(&self, id: UiId) -> Vec2 {
+        match (self.style(id), self.taffy.layout(id.0)) {
+            (Some(style), Ok(layout)) if style.is_scrollable() => max_scroll(layout),
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Scroll `id` by `delta` if it is `Scroll`, and its `Scroll` ancestors by what it can't
+    /// take (innermost first); returns whether anything moved.
+    pub fn scroll(&mut self, id: UiId, mut delta: Vec2) -> bool {
+        let mut moved = false;
+        let mut next = Some(id);
+        while let Some(id) = next {
+            if delta == Vec2::ZERO {
+                break;
+            }
+            next = self.parent(id);
+            let before = self.scroll_offset(id);
+            let after = (before + delta).clamp(Vec2::ZERO, self.max_scroll(id));
+            if after != before {
+                self.set_scroll_offset(id, after);
+                moved = true;
+            }
+            delta -= after - before;
+        }
+        moved
+    }
+
+    /// Scroll the `Scroll` ancestors of a node just enough to show it in their padding boxes
+    /// (its top-left if it doesn't fit), as of the last `layout`.
+    pub fn scroll_into_view(&mut self, id: UiId) {
+        let Some(mut rect) = self.layout_of(id).map(|l| l.rect) else {
+            return;
+        };
+        let mut next = self.parent(id);
+        while let Some(ancestor) = next {
+            next = self.parent(ancestor);
+            let Some(view) = self.layout_of(ancestor).map(|l| l.padding_box) else {
+                continue;
+            };
+            let delta = (rect.max() - view.max())
+                .max(Vec2::ZERO)
+                .min(rect.position - view.position);
+            let before = self.scroll_offset(ancestor);
+            let after = (before + delta).clamp(Vec2::ZERO, self.max_scroll(ancestor));
+            self.set_scroll_offset(ancestor, after);
+            rect.position -= after - before;
+        }
+    }
+
+    /// Interactive node (see `UiNodeKind::is_interactive`) under a position in logical pixels:
+    /// the topmost node there or its nearest interactive ancestor. `None` if that is disabled.
+    pub fn interactive_at(&self, position: Vec2) -> Option<UiId> {
+        let mut id = self.node_at(position)?;
+        while !self.node(id)?.kind.is_interactive() {
+            id = self.parent(id)?;
+        }
+        (!self.is_disabled(id)).then_some(id)
+    }
+
+    /// Whether the node or one of its ancestors is disabled.
+    pub fn is_disabled(&self, id: UiId) -> bool {
+        let mut next = Some(id);
+        while let Some(id) = next {
+            if self.node(id).is_some_and(|node| node.disabled) {
+                return true;
+            }
+            next = self.parent(id);
+        }
+        false
+    }
+
+    /// Interactive node under the pointer.
+    pub fn hovered(&self) -> Option<UiId> {
+        self.hovered
+    }
+
+    /// Interactive node being pressed by the pointer.
+    pub fn pressed(&self) -> Option<UiId> {
+        self.pressed
+    }
+
+    /// Node receiving keyboard and gamepad input.
+    pub fn focused(&self) -> Option<UiId> {
+        self.focused
+    }
+
+    /// Focus a node (or nothing); game code can use this to pick the initial focus of a menu.
+    pub fn set_focus(&mut self, id: Option<UiId>) {
+        self.set_state(|tree| &mut tree.focused, id);
+    }
+
+    pub(crate) fn set_hovered(&mut self, id: Option<UiId>) {
+        self.set_state(|tree| &mut tree.hovered, id);
+    }
+
+    pub(crate) fn set_pressed(&mut self, id: Option<UiId>) {
+        self.set_state(|tree| &mut tree.pressed, id);
+    }
+
+    fn set_state(&mut self, state: impl Fn(&mut Self) -> &mut Option<UiId>, id: Option<UiId>) {
+        let state = state(self);
+        if *state != id {
+            *state = id;
+            // States can change styles through selectors
+            self.restyle = true;
+            self.dirty = true;
+        }
+    }
+
+    pub fn node_state(&self, id: UiId) -> UiNodeState {
+        UiNodeState {
+            hovered: self.hovered == Some(id),
+            pressed: self.pressed == Some(id),
+            focused: self.focused == Some(id),
+            disabled: self.is_disabled(id),
+        }
+    }
+
+    /// Texture id of an image path (of an image node or a `background_image`), registering it
+    /// on first use. Ids start at 1; `WHITE_TEXTURE` is 0.
+    pub fn texture_id(&mut self, path: &str) -> u32 {
+        if let Some(id) = self.textures.get(path) {
+            return *id;
+        }
+        let id = self.textures.len() as u32 + 1;
+        self.textures.insert(path.to_string(), id);
+        self.new_textures.push((id, path.to_string()));
+        id
+    }
+
+    /// Texture id of an image path that was registered (by an image node or style).
+    pub fn texture(&self, path: &str) -> Option<u32> {
+        self.textures.get(path).copied()
+    }
+
+    /// Pixel size of a texture, once the renderer loaded it (`set_texture_size`).
+    pub fn texture_size(&self, id: u32) -> Option<Vec2> {
+        self.texture_sizes.get(&id).copied()
+    }
+
+    /// Textures registered since the last call, for the renderer to load.
+    pub fn take_new_textures(&mut self) -> Vec<(u32, String)> {
+        std::mem::take(&mut self.new_textures)
+    }
+
+    /// Pixel size of a loaded texture; images using it are re-laid out at their natural size.
+    pub fn set_texture_size(&mut self, id: u32, width: u32, height: u32) {
+        self.texture_sizes
+            .insert(id, Vec2::new(width as f32, height as f32));
+        for node in self.descendants(self.root()) {
+            let uses = match self.node(node).map(|n| &n.kind) {
+                Some(UiNodeKind::Image(path)) => self.textures.get(path) == Some(&id),
+                _ => false,
+            };
+            if uses {
+                let _ = self.taffy.mark_dirty(node.0);
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Recompute every node's style top-down; taffy styles are only replaced where they
+    /// changed, so unchanged subtrees keep their cached layout.
+    fn restyle_all(&mut self) {
+        let mut styles = Vec::new();
+        let mut ancestors = Vec::new();
+        self.compute_styles(
+            self.root,
+            &UiStyle::default(),
+            None,
+            &mut ancestors,
+            &mut styles,
+        );
+        for (node, mut style) in styles {
+            if node == self.root {
+                style.width = Some(Val::Px(self.size.x));
+                style.height = Some(Val::Px(self.size.y));
+            }
+            if let Some(UiNodeKind::Image(path)) = self.node(UiId(node)).map(|n| n.kind.clone()) {
+                self.texture_id(&path);
+            }
+            if let Some(path) = &style.background_image {
+                self.texture_id(path);
+            }
+            let Some(data) = self.taffy.get_node_context_mut(node) else {
+                continue;
+            };
+            if data.style != style {
+                let taffy_style = style.to_taffy();
+                data.style = style;
+                let _ = self.taffy.set_style(node, taffy_style);
+            }
+        }
+    }
+
+    fn compute_styles<'a>(
+        &'a self,
+        node: NodeId,
+        parent: &UiStyle,
+        sheet: Option<&'a StyleSheet>,
+        ancestors: &mut Vec<StyledNode<'a>>,
+        out: &mut Vec<(NodeId, UiStyle)>,
+    ) {
+        let Some(data) = self.taffy.get_node_context(node) else {
+            return;
+        };
+        let id = UiId(node);
+        let state = UiNodeState {
+            hovered: self.hovered == Some(id),
+            pressed: self.pressed == Some(id),
+            focused: self.focused == Some(id),
+            disabled: data.node.disabled || ancestors.last().is_some_and(|(_, s)| s.disabled),
+        };
+        let sheet = self.style_sheets.get(&node).or(sheet);
+        let mut style = sheet
+            .map(|sheet| sheet.style_for((&data.node, state), ancestors))
+            .unwrap_or_default();
+        style.merge(&data.node.style);
+        style.inherit(parent);
+        ancestors.push((&data.node, state));
+        for child in self.taffy.children(node).unwrap_or_default() {
+            self.compute_styles(child, &style, sheet, ancestors, out);
+        }
+        ancestors.pop();
+        out.push((node, style));
+    }
+
+    fn collect_layouts(&mut self, node: NodeId, offset: Vec2, clip: Option<UiRect>) {
+        let Ok(layout) = self.taffy.layout(node) else {
+            return;
+        };
+        let max = max_scroll(layout);
+        let Some(data) = self.taffy.get_node_context_mut(node) else {
+            return;
+        };
+        if data.style.is_hidden() {
+            return;
+        }
+        let clips = data.style.clips();
+        if data.style.is_scrollable() {
+            data.scroll = data.scroll.min(max);
+        } else {
+            data.scroll = Vec2::ZERO;
+        }
+        let scroll = data.scroll;
+        let Ok(layout) = self.taffy.layout(node) else {
+            return;
+        };
+        let position = offset + Vec2::new(layout.location.x, layout.location.y);
+        let size = Vec2::new(layout.size.width, layout.size.height);
+        let inset = |rect: UiRect, edges: taffy::Rect<f32>| {
+            let min = rect.position + Vec2::new(edges.left, edges.top);
+            let max = rect.max() - Vec2::new(edges.right, edges.bottom);
+            UiRect::new(min, (max - min).max(Vec2::ZERO))
+        };
+        let rect = UiRect::new(position, size);
+        let padding_box = inset(rect, layout.border);
+        let content = inset(padding_box, layout.padding);
+        self.layouts.insert(
+            UiId(node),
+            UiLayout {
+                rect,
+                padding_box,
+                content,
+                clip,
+            },
+        );
+        let clip = if clips {
+            Some(clip.map_or(padding_box, |clip| clip.intersect(&padding_box)))
+        } else {
+            clip
+        };
+        for child in self.taffy.children(node).unwrap_or_default() {
+            self.collect_layouts(child, position - scroll, clip);
+        }
+    }
+}
+
+/// How far the content of a node (taffy measures it from the border box's top-left) and its
+/// end padding reach past the padding box.
+fn max_scroll(layout: &taffy::Layout) -> Vec2 {
+    let content = Vec2::new(layout.content_size.width, layout.content_size.height);
+    let end = Vec2::new(
+        layout.padding.right + layout.border.right,
+        layout.padding.bottom + layout.border.bottom,
+    );
+    (content + end - Vec2::new(layout.size.width, layout.size.height)).max(Vec2::ZERO)
+}
+
+fn to_size(known: Size<Option<f32>>, size: Vec2) -> Size<f32> {
+    Size {
+        width: known.width.unwrap_or(size.x),
+        height: known.height.unwrap_or(size.y),
+    }
+}
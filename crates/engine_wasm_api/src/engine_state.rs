@@ -0,0 +1,678 @@
+/**
+ * path: /crates/engine_wasm_api/src/engine_state.rs
+ * description: Engine state shared (Rc<RefCell<..>>) between the JS `Engine` handle,
+ * the requestAnimationFrame loop and async callbacks (fetch, hot reload).
+ */
+use bevy_ecs::prelude::{
+    Entity,
+    With,
+};
+use engine_assets::ImageData;
+use engine_core::{
+    set_mode,
+    EngineApp,
+    Mode,
+    Time,
+};
+use engine_input::{
+    ActionMap,
+    InputMap,
+    InputQueue,
+    InputReplay,
+    Viewport,
+};
+use engine_picking::{
+    PickCamera3D,
+    PickMesh,
+};
+use engine_render::{
+    BGPipeline,
+    GUIPipeline,
+    MeshPipeline,
+    QuadPipeline,
+};
+use engine_scene::{
+    apply_scene_patch,
+    despawn_scene,
+    spawn_scene,
+    ComponentRegistry,
+    Mesh,
+    PrefabLibrary,
+    Scene,
+    SceneComponent,
+    SceneInstance,
+    SceneValue,
+};
+use engine_text::{
+    AtlasUpdate,
+    Font,
+    Fonts,
+    GlyphAtlas,
+};
+use engine_types::{
+    InstanceData,
+    MeshData,
+};
+use engine_ui::{
+    UiDocument,
+    UiId,
+    UiTree,
+    GLYPH_ATLAS_TEXTURE,
+};
+use platform_web::{
+    CanvasResizer,
+    InputListener,
+    WgpuContext,
+};
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+
+use crate::helpers::{
+    world_text_geometry,
+    world_to_instances,
+};
+use crate::same_asset;
+
+pub(crate) struct EngineState {
+    pub(crate) app: EngineApp,
+    pub(crate) canvas: Option<HtmlCanvasElement>,
+    pub(crate) gfx: Option<WgpuContext>,
+    pub(crate) resizer: Option<CanvasResizer>,
+    pub(crate) input: Option<InputListener>,
+    /// Recorded input driving the app instead of the listener (`Engine.replay_input`).
+    pub(crate) replay: Option<InputReplay>,
+    pub(crate) auto_resize: bool,
+    pub(crate) max_resolution_scale: f32,
+    pub(crate) bg_pipeline: Option<BGPipeline>,
+    pub(crate) quad_pipeline: Option<QuadPipeline>,
+    pub(crate) mesh_pipeline: Option<MeshPipeline>,
+    pub(crate) gui_pipeline: Option<GUIPipeline>,
+    /// Text components, drawn with the sprite camera.
+    pub(crate) world_text_pipeline: Option<GUIPipeline>,
+    /// Glyphs of UI and world text, uploaded to both GUI pipelines.
+    pub(crate) glyph_atlas: GlyphAtlas,
+    pub(crate) mesh_data: Option<MeshData>,
+    /// The `Mesh` file `mesh_data` was loaded from.
+    pub(crate) mesh_url: Option<String>,
+    /// Authored scene (prefab links + overrides, as saved); the world holds its resolved form.
+    pub(crate) current_scene: Option<Scene>,
+    /// `current_scene` with prefabs resolved, as last spawned or patched into the world.
+    pub(crate) resolved_scene: Option<Scene>,
+    pub(crate) scene_instance: Option<SceneInstance>,
+    pub(crate) current_scene_url: Option<String>,
+    /// Where the action bindings were loaded from, for hot reload.
+    pub(crate) input_map_url: Option<String>,
+    /// Loaded UI documents (URL, root node), for hot reload.
+    pub(crate) ui_documents: Vec<(String, UiId)>,
+    /// Loaded fonts (URL, name), for hot reload.
+    pub(crate) fonts: Vec<(String, String)>,
+    pub(crate) prefabs: PrefabLibrary,
+    pub(crate) last_ts: Option<f64>,
+    pub(crate) tick_ts: f64, // only show error after x seconds and not every tick
+    pub(crate) tick_interval: f64,
+}
+
+impl EngineState {
+    /// Create pipelines for a freshly initialized graphics context and take ownership of it.
+    pub(crate) fn install_gfx(&mut self, gfx: WgpuContext) {
+        if let Some(resizer) = self.resizer.as_ref() {
+            resizer.set_max_dimension(gfx.device.limits().max_texture_dimension_2d);
+            // init_wgpu already configured the surface for the initial size
+            let _ = resizer.poll();
+        }
+
+        // Prepare empty quad pipeline
+        let quad_instances: Vec<InstanceData> = Vec::new();
+        self.quad_pipeline = Some(QuadPipeline::new(
+            &gfx.device,
+            gfx.config.format,
+            &quad_instances,
+        ));
+
+        // Prepare empty mesh pipeline
+        let mesh_data: &MeshData = &self.mesh_data.clone().unwrap();
+        self.mesh_pipeline = Some(MeshPipeline::new(
+            &gfx.device,
+            gfx.config.format,
+            &mesh_data.vertices,
+            &mesh_data.indices,
+        ));
+
+        // Game UI on top of the scene
+        self.gui_pipeline = Some(GUIPipeline::new(&gfx.device, &gfx.queue, gfx.config.format));
+        self.world_text_pipeline =
+            Some(GUIPipeline::new(&gfx.device, &gfx.queue, gfx.config.format));
+
+        self.gfx = Some(gfx);
+
+        // After creating gfx and pipelines, update camera projections
+        if let (Some(quad_pipeline), Some(gfx)) = (self.quad_pipeline.as_ref(), self.gfx.as_ref()) {
+            quad_pipeline.update_camera(&gfx.queue, gfx.config.width, gfx.config.height);
+        }
+        if let (Some(mesh_pipeline), Some(gfx)) = (self.mesh_pipeline.as_ref(), self.gfx.as_ref()) {
+            mesh_pipeline.update_camera(&gfx.queue, gfx.config.width, gfx.config.height);
+        }
+        if let (Some(gui_pipeline), Some(gfx)) = (self.gui_pipeline.as_ref(), self.gfx.as_ref()) {
+            gui_pipeline.update_camera(&gfx.queue, gfx.config.width, gfx.config.height);
+        }
+        if let Some(gfx) = self.gfx.as_ref() {
+            let scale = canvas_scale(&gfx.canvas);
+            update_viewport(&mut self.app, gfx.config.width, gfx.config.height, scale);
+        }
+
+        self.tick_ts = 15000.0;
+    }
+
+    // Reconfigure the WebGPU surface to match the current canvas size.
+    // Called automatically when auto_resize is enabled; hosts that size the canvas
+    // themselves should call it on resize. Also used when surface acquisition fails.
+    pub(crate) fn reconfigure_surface(&mut self) {
+        if let Some(gfx) = self.gfx.as_mut() {
+            let old_w = gfx.config.width;
+            let old_h = gfx.config.height;
+            let new_w = gfx.canvas.width().max(1);
+            let new_h = gfx.canvas.height().max(1);
+
+            if old_w != new_w || old_h != new_h {
+                web_sys::console::log_1(
+                    &format!("Surface resize detected: {old_w}x{old_h} -> {new_w}x{new_h}").into(),
+                );
+            }
+
+            platform_web::wgpu_init::reconfigure_surface(gfx);
+
+            // ✅ Update camera projection to match new canvas size
+            if let Some(quad_pipeline) = self.quad_pipeline.as_ref() {
+                quad_pipeline.update_camera(&gfx.queue, new_w, new_h);
+            }
+            if let Some(mesh_pipeline) = self.mesh_pipeline.as_ref() {
+                mesh_pipeline.update_camera(&gfx.queue, new_w, new_h);
+            }
+            if let Some(gui_pipeline) = self.gui_pipeline.as_ref() {
+                gui_pipeline.update_camera(&gfx.queue, new_w, new_h);
+            }
+            update_viewport(&mut self.app, new_w, new_h, canvas_scale(&gfx.canvas));
+        } else {
+            web_sys::console::warn_1(&"reconfigure_surface called but gfx is None".into());
+        }
+    }
+
+    /// One animation frame: derive dt from the RAF timestamp and tick.
+    pub(crate) fn frame(&mut self, ts_ms: f64) {
+        let dt_ms = match self.last_ts {
+            Some(last) => (ts_ms - last) as f32,
+            None => 0.0,
+        };
+        self.last_ts = Some(ts_ms);
+        self.tick(dt_ms);
+    }
+
+    pub(crate) fn tick(&mut self, _dt_ms: f32) {
+        // Pick up canvas size / devicePixelRatio changes observed since the last frame
+        if self.resizer.as_ref().and_then(|r| r.poll()).is_some() {
+            self.reconfigure_surface();
+        }
+
+        // Input received since the last frame; applied in PreUpdate (ignored while replaying)
+        if let Some(input) = self.input.as_ref() {
+            let events = input.drain();
+            if self.replay.is_none() {
+                self.app.world.resource_mut::<InputQueue>().extend(events);
+            }
+        }
+
+        self.update_app(_dt_ms / 1000.0);
+        if self.tick_ts < 0.0 {
+            self.tick_ts = 0.0;
+        }
+        self.tick_ts += _dt_ms as f64;
+        // web_sys::console::log_1(&format!("self.tick_ts: {0} _dt_ms {1}", self.tick_ts, _dt_ms).into());
+
+        let Some(gfx) = self.gfx.as_mut() else {
+            if self.tick_ts >= self.tick_interval {
+                web_sys::console::error_1(&"gfx None in tick()".into());
+            }
+            return;
+        };
+
+        // ECS sprites (world space) -> instances
+        let (_inst_count, _inst_bytes) = if self.scene_instance.is_some() {
+            let quad_instances = world_to_instances(&mut self.app.world);
+            if self.tick_ts >= self.tick_interval {
+                web_sys::console::log_1(
+                    &format!(
+                        "quad_instances: {0}",
+                        quad_instances.clone().into_iter().collect::<Vec<_>>().len()
+                    )
+                    .into(),
+                );
+            }
+
+            // Capacity + upload logging happens in pipeline (see below)
+            if let Some(quad_pipeline) = self.quad_pipeline.as_mut() {
+                quad_pipeline.ensure_capacity(&gfx.device, &quad_instances);
+                quad_pipeline.update_instances(&gfx.queue, &quad_instances);
+                (
+                    quad_instances.len() as u32,
+                    (quad_instances.len() * std::mem::size_of::<InstanceData>()) as u64,
+                )
+            } else {
+                if self.tick_ts >= self.tick_interval {
+                    web_sys::console::warn_1(&"quad_pipeline None; cannot upload instances".into());
+                }
+                (0, 0)
+            }
+        } else {
+            if self.tick_ts >= self.tick_interval {
+                web_sys::console::warn_1(&"current_scene None".into());
+            }
+            (0, 0)
+        };
+
+        // Creating pipeline if missing and mesh_data exists
+        if self.mesh_pipeline.is_none() {
+            if let Some(mesh_data) = &self.mesh_data {
+                if !mesh_data.vertices.is_empty() && !mesh_data.indices.is_empty() {
+                    web_sys::console::log_1(&"Creating MeshPipeline.".into());
+                    self.mesh_pipeline = Some(MeshPipeline::new(
+                        &gfx.device,
+                        gfx.config.format,
+                        &mesh_data.vertices,
+                        &mesh_data.indices,
+                    ));
+                }
+            }
+        }
+
+        // Update Mesh data for Rendering
+        if let (Some(mesh_pipeline), Some(mesh_data)) =
+            (self.mesh_pipeline.as_mut(), self.mesh_data.as_ref())
+        {
+            mesh_pipeline.ensure_vertex_capacity(&gfx.device, &mesh_data.vertices);
+            mesh_pipeline.ensure_index_capacity(&gfx.device, &mesh_data.indices);
+            gfx.queue.write_buffer(
+                &mesh_pipeline.vertex_buffer,
+                0,
+                bytemuck::cast_slice(&mesh_data.vertices),
+            );
+            gfx.queue.write_buffer(
+                &mesh_pipeline.index_buffer,
+                0,
+                bytemuck::cast_slice(&mesh_data.indices),
+            );
+        } else {
+            if self.tick_ts >= self.tick_interval {
+                web_sys::console::warn_1(&"mesh_pipeline None; cannot upload mesh_data".into());
+            }
+        }
+
+        // Game UI (laid out in PostUpdate) and world text -> GUI quads. Glyphs added to the
+        // atlas meanwhile are uploaded before drawing; if it grew, UVs are rebuilt.
+        let (ui_geometry, text_geometry) = loop {
+            let atlas_size = self.glyph_atlas.size();
+            let world = &mut self.app.world;
+            let ui = world
+                .resource::<UiTree>()
+                .draw_list(world.resource::<Fonts>(), &mut self.glyph_atlas)
+                .geometry();
+            let text = world_text_geometry(world, &mut self.glyph_atlas);
+            if self.glyph_atlas.size() == atlas_size {
+                break (ui, text);
+            }
+        };
+        let viewport = self.app.world.resource::<Viewport>().view_proj;
+        let atlas_update = self.glyph_atlas.take_update();
+        for (pipeline, geometry) in [
+            (self.gui_pipeline.as_mut(), &ui_geometry),
+            (self.world_text_pipeline.as_mut(), &text_geometry),
+        ] {
+            let Some(pipeline) = pipeline else {
+                continue;
+            };
+            upload_glyph_atlas(pipeline, gfx, &self.glyph_atlas, atlas_update);
+            pipeline.update(
+                &gfx.device,
+                &gfx.queue,
+                &geometry.vertices,
+                &geometry.indices,
+                &geometry.batches,
+            );
+        }
+        if let Some(world_text_pipeline) = self.world_text_pipeline.as_ref() {
+            world_text_pipeline.set_camera(&gfx.queue, viewport);
+        }
+
+        // Debug log instance count and bytes
+        // web_sys::console::log_1(&format!("inst_count = {}, inst_bytes = {}", inst_count, inst_bytes).into());
+
+        // Acquire + reconfigure path already logs; keep it
+        let frame = match gfx.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(err) => {
+                web_sys::console::warn_1(
+                    &format!("surface acquire error, reconfiguring: {err:?}").into(),
+                );
+                platform_web::wgpu_init::reconfigure_surface(gfx);
+
+                match gfx.surface.get_current_texture() {
+                    Ok(f) => f,
+                    Err(e2) => {
+                        web_sys::console::error_1(
+                            &format!("acquire failed after reconfigure: {e2:?}").into(),
+                        );
+                        return;
+                    }
+                }
+            }
+        };
+
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = gfx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("ironhold_encoder"),
+            });
+
+        // Start a render pass
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("ironhold_render_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 135.0 / 255.0,
+                            g: 206.0 / 255.0,
+                            b: 235.0 / 255.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            if let Some(bg_pipeline) = self.bg_pipeline.as_ref() {
+                bg_pipeline.draw(&mut rpass);
+            }
+            if let Some(quad_pipeline) = self.quad_pipeline.as_ref() {
+                quad_pipeline.draw(&mut rpass);
+            } else {
+                if self.tick_ts >= self.tick_interval {
+                    web_sys::console::warn_1(&"quad_pipeline None; skipping draw".into());
+                }
+            }
+            if let (Some(mesh_pipeline), Some(mesh_data)) =
+                (self.mesh_pipeline.as_ref(), &self.mesh_data)
+            {
+                if !mesh_data.vertices.is_empty() && !mesh_data.indices.is_empty() {
+                    mesh_pipeline.draw(&mut rpass);
+                }
+            } else {
+                if self.tick_ts >= self.tick_interval {
+                    web_sys::console::warn_1(&"mesh_pipeline None; skipping draw".into());
+                }
+            }
+            if let Some(world_text_pipeline) = self.world_text_pipeline.as_ref() {
+                world_text_pipeline.draw(&mut rpass);
+            }
+            if let Some(gui_pipeline) = self.gui_pipeline.as_ref() {
+                gui_pipeline.draw(&mut rpass);
+            }
+        }
+
+        gfx.queue.submit(Some(encoder.finish()));
+        frame.present();
+
+        if self.tick_ts >= self.tick_interval {
+            self.tick_ts = self.tick_ts - self.tick_interval;
+            // web_sys::console::log_1(&"tick completed".into());
+        }
+    }
+
+    /// Resolve prefab instances (all prefabs must be loaded) and replace the spawned scene.
+    pub(crate) fn apply_scene(&mut self, scene: Scene) -> Result<(), JsValue> {
+        let resolved = self.resolve(&scene)?;
+        if let Some(previous) = self.scene_instance.take() {
+            despawn_scene(&mut self.app.world, &previous);
+        }
+        let instance = spawn_scene(&mut self.app.world, &resolved)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.scene_instance = Some(instance);
+        self.resolved_scene = Some(resolved);
+        self.current_scene = Some(scene);
+        self.attach_pick_meshes();
+        Ok(())
+    }
+
+    /// Like `apply_scene`, but only applies what changed since the spawned version
+    /// (`Scene::diff`), so runtime state outside the changed fields survives (hot reload).
+    pub(crate) fn patch_scene(&mut self, scene: Scene) -> Result<(), JsValue> {
+        let resolved = self.resolve(&scene)?;
+        let (Some(instance), Some(previous)) =
+            (self.scene_instance.as_mut(), self.resolved_scene.as_ref())
+        else {
+            return self.apply_scene(scene);
+        };
+        let patch = previous.diff(&resolved);
+        apply_scene_patch(&mut self.app.world, instance, &patch)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        web_sys::console::log_1(&format!("Scene patched: {} change(s)", patch.len()).into());
+        self.resolved_scene = Some(resolved);
+        self.current_scene = Some(scene);
+        self.attach_pick_meshes();
+        Ok(())
+    }
+
+    /// The `Mesh` file of the spawned scene (the first one; a single mesh is drawn), unless it
+    /// is loaded already.
+    pub(crate) fn missing_mesh(&self) -> Option<String> {
+        let file = self
+            .resolved_scene
+            .as_ref()?
+            .entities
+            .iter()
+            .filter_map(|entity| entity.components.get(Mesh::TYPE_NAME))
+            .find_map(|mesh| match mesh.field("file") {
+                Some(SceneValue::String(file)) if !file.is_empty() => Some(file.clone()),
+                _ => None,
+            })?;
+        match self.mesh_url.as_deref() {
+            Some(loaded) if same_asset(loaded, &file) => None,
+            _ => Some(file),
+        }
+    }
+
+    /// Draw the mesh loaded from `url` and pick entities with a `Mesh` component by its shape.
+    pub(crate) fn set_mesh_data(&mut self, url: String, mesh: MeshData) {
+        self.mesh_data = Some(mesh);
+        self.mesh_url = Some(url);
+        self.attach_pick_meshes();
+    }
+
+    /// Insert (or replace) `PickMesh` on every `Mesh` entity, once a mesh is loaded.
+    fn attach_pick_meshes(&mut self) {
+        let (Some(_), Some(mesh)) = (self.mesh_url.as_ref(), self.mesh_data.as_ref()) else {
+            return;
+        };
+        let pick = PickMesh::from_mesh_data(mesh);
+        let world = &mut self.app.world;
+        let entities: Vec<Entity> = world
+            .query_filtered::<Entity, With<Mesh>>()
+            .iter(world)
+            .collect();
+        for entity in entities {
+            world.entity_mut(entity).insert(pick.clone());
+        }
+    }
+
+    fn resolve(&self, scene: &Scene) -> Result<Scene, JsValue> {
+        self.prefabs
+            .resolve(scene, self.app.world.resource::<ComponentRegistry>())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Run one frame: the next recorded frame while replaying, otherwise `dt_secs` of live time.
+    fn update_app(&mut self, dt_secs: f32) {
+        let Some(replay) = self.replay.as_mut() else {
+            self.app.update(dt_secs);
+            return;
+        };
+        match replay.step(&mut self.app) {
+            Ok(true) => return,
+            Ok(false) => web_sys::console::log_1(
+                &format!("Input replay finished after {} frames", replay.frame()).into(),
+            ),
+            Err(e) => web_sys::console::error_1(&format!("Input replay stopped: {e}").into()),
+        }
+        self.replay = None;
+        self.app.update(dt_secs);
+    }
+
+    /// Use the authored bindings loaded from `url`. Reloading the current map (hot reload) keeps
+    /// the active contexts; a different map starts with its own. The user's overrides are kept.
+    pub(crate) fn apply_input_map(&mut self, url: String, map: InputMap) {
+        let reload = self
+            .input_map_url
+            .as_deref()
+            .is_some_and(|loaded| same_asset(loaded, &url));
+        let mut actions = self.app.world.resource_mut::<ActionMap>();
+        if reload {
+            actions.set_map(map);
+            return;
+        }
+        let overrides = actions.overrides().clone();
+        *actions = ActionMap::new(map);
+        actions.set_overrides(overrides);
+        self.input_map_url = Some(url);
+    }
+
+    /// Spawn a UI document under the UI root, or update it in place if `url` is loaded already.
+    pub(crate) fn apply_ui_document(&mut self, url: String, document: &UiDocument) -> UiId {
+        let mut tree = self.app.world.resource_mut::<UiTree>();
+        if let Some((_, root)) = self.ui_documents.iter().find(|(loaded, _)| *loaded == url) {
+            tree.update_document(*root, document);
+            return *root;
+        }
+        let root = tree.root();
+        let root = tree.spawn_document(root, document);
+        self.ui_documents.push((url, root));
+        root
+    }
+
+    /// Remove a UI document's nodes.
+    pub(crate) fn remove_ui_document(&mut self, url: &str) {
+        let Some(index) = self
+            .ui_documents
+            .iter()
+            .position(|(loaded, _)| loaded == url)
+        else {
+            return;
+        };
+        let (_, root) = self.ui_documents.remove(index);
+        self.app.world.resource_mut::<UiTree>().remove(root);
+    }
+
+    /// Add a font, or replace the one loaded from `url` (hot reload). Text using it is laid
+    /// out again.
+    pub(crate) fn apply_font(&mut self, url: String, font: Font) {
+        self.fonts.retain(|(loaded, _)| *loaded != url);
+        self.fonts.push((url, font.name().to_string()));
+        self.app.world.resource_mut::<Fonts>().insert(font);
+    }
+
+    /// Image paths the UI registered since the last call, to fetch. Empty until the GUI
+    /// pipeline exists, so nothing arrives before it can be uploaded.
+    pub(crate) fn take_ui_texture_requests(&mut self) -> Vec<(u32, String)> {
+        if self.gui_pipeline.is_none() {
+            return Vec::new();
+        }
+        self.app.world.resource_mut::<UiTree>().take_new_textures()
+    }
+
+    /// Upload a fetched UI image and lay out the images using it at its size.
+    pub(crate) fn set_ui_texture(&mut self, id: u32, image: ImageData) {
+        if let (Some(gui_pipeline), Some(gfx)) = (self.gui_pipeline.as_mut(), self.gfx.as_ref()) {
+            gui_pipeline.set_texture(
+                &gfx.device,
+                &gfx.queue,
+                id,
+                image.width,
+                image.height,
+                &image.rgba,
+            );
+        }
+        self.app
+            .world
+            .resource_mut::<UiTree>()
+            .set_texture_size(id, image.width, image.height);
+    }
+
+    pub(crate) fn set_play_mode(&mut self, play: bool) {
+        set_mode(&mut self.app, if play { Mode::Play } else { Mode::Edit });
+    }
+
+    pub(crate) fn set_time_scale(&mut self, scale: f32) {
+        self.app.world.resource_mut::<Time>().time_scale = scale.max(0.0);
+    }
+
+    pub(crate) fn set_paused(&mut self, paused: bool) {
+        self.app.world.resource_mut::<Time>().paused = paused;
+    }
+}
+
+/// Upload what changed in the glyph atlas to a pipeline's `GLYPH_ATLAS_TEXTURE`.
+fn upload_glyph_atlas(
+    pipeline: &mut GUIPipeline,
+    gfx: &WgpuContext,
+    atlas: &GlyphAtlas,
+    update: Option<AtlasUpdate>,
+) {
+    match update {
+        Some(AtlasUpdate::Full) => pipeline.set_texture(
+            &gfx.device,
+            &gfx.queue,
+            GLYPH_ATLAS_TEXTURE,
+            atlas.size(),
+            atlas.size(),
+            atlas.pixels(),
+        ),
+        Some(AtlasUpdate::Region { position, size }) => pipeline.write_texture(
+            &gfx.queue,
+            GLYPH_ATLAS_TEXTURE,
+            (position.x, position.y),
+            (size.x, size.y),
+            &atlas.region_pixels(position, size),
+        ),
+        None => {}
+    }
+}
+
+/// Let input and picking map canvas pixels to the world through the sprite and mesh cameras,
+/// and lay out the UI for the new canvas size.
+fn update_viewport(app: &mut EngineApp, width: u32, height: u32, scale_factor: f32) {
+    app.insert_resource(Viewport::new(
+        glam::Vec2::new(width as f32, height as f32),
+        QuadPipeline::camera_matrix(),
+    ));
+    app.insert_resource(PickCamera3D {
+        view_proj: Some(MeshPipeline::camera_matrix(width, height)),
+    });
+    app.world
+        .resource_mut::<UiTree>()
+        .set_viewport(width, height, scale_factor);
+}
+
+/// Backing-store pixels per CSS pixel (devicePixelRatio as applied by the resizer).
+fn canvas_scale(canvas: &HtmlCanvasElement) -> f32 {
+    match canvas.client_width() {
+        css if css > 0 => canvas.width() as f32 / css as f32,
+        _ => 1.0,
+    }
+}
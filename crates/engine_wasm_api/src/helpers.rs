@@ -1,30 +1,49 @@
-
-use engine_scene::Scene;
-use engine_assets::{load_mesh_stub, MeshData};
+use bevy_ecs::prelude::World;
+use engine_assets::{
+    load_mesh_stub,
+    MeshData,
+};
+use engine_input::Viewport;
+use engine_scene::{
+    GlobalTransform2D,
+    Scene,
+    SceneComponent,
+};
+use engine_text::{
+    layout_text,
+    Fonts,
+    GlyphAtlas,
+};
 use engine_types::{
     // MeshVertex,
+    GuiBatch,
+    GuiVertex,
     InstanceData,
-    Transform2D,
     Sprite,
+    Transform2D,
 };
+use engine_ui::{
+    UiGeometry,
+    GLYPH_ATLAS_TEXTURE,
+};
+use glam::Vec2;
 
 // Helper to allow storing closures (not fully used yet)
 // struct RcCell<T>(std::rc::Rc<std::cell::RefCell<Option<T>>>);
 // impl<T> RcCell<T> { fn new(v: Option<T>) -> Self { Self(std::rc::Rc::new(std::cell::RefCell::new(v))) } }
 
-pub fn scene_to_instances(scene: &Scene) -> Vec<InstanceData> {
-    scene
-        .entities
-        .iter()
-        .filter(|e| e.transform_2d.is_some())
-        .map(|e| {
-            let transform_2d: &engine_scene::Transform2D  = e.transform_2d.as_ref().expect("Entity missing transform_2d component");
+/// Pack every sprite in the world into quad instances, using world-space transforms
+/// (`GlobalTransform2D`) so children are drawn relative to their parents.
+pub fn world_to_instances(world: &mut World) -> Vec<InstanceData> {
+    let mut query = world.query::<(&GlobalTransform2D, &engine_scene::Sprite)>();
+    query
+        .iter(world)
+        .map(|(global, sprite)| {
+            let transform_2d = global.to_transform();
 
             // Rotation is authored in degrees in RON; WGSL expects radians.
             let rot_rad = transform_2d.rotation.to_radians();
 
-            let sprite: &engine_scene::Sprite = e.sprite.as_ref().expect("Entity missing sprite component");
-
             InstanceData {
                 transform: Transform2D {
                     // t0: position.x, position.y, rotation(rad), pad
@@ -35,21 +54,11 @@ pub fn scene_to_instances(scene: &Scene) -> Vec<InstanceData> {
                         0.0,
                     ],
                     // t1: scale.x, scale.y, pad, pad
-                    t1: [
-                        transform_2d.scale.0,
-                        transform_2d.scale.1,
-                        0.0,
-                        0.0,
-                    ],
+                    t1: [transform_2d.scale.0, transform_2d.scale.1, 0.0, 0.0],
                 },
                 sprite: Sprite {
                     // s0: dimensions.x, dimensions.y, pad, pad
-                    s0: [
-                        sprite.dimensions.0,
-                        sprite.dimensions.1,
-                        0.0,
-                        0.0,
-                    ],
+                    s0: [sprite.dimensions.0, sprite.dimensions.1, 0.0, 0.0],
                     // RGBA
                     color: [
                         sprite.color.0,
@@ -63,12 +72,86 @@ pub fn scene_to_instances(scene: &Scene) -> Vec<InstanceData> {
         .collect()
 }
 
+/// Glyph quads of every `Text` component in world units (y up), centred on its
+/// `GlobalTransform2D`. Glyphs are rasterized at the size they appear on the canvas through the
+/// sprite camera, so labels stay sharp when scaled.
+pub fn world_text_geometry(world: &mut World, atlas: &mut GlyphAtlas) -> UiGeometry {
+    let mut query = world.query::<(&GlobalTransform2D, &engine_scene::Text)>();
+    let fonts = world.resource::<Fonts>();
+    let viewport = world.resource::<Viewport>();
+    // Canvas pixels per world unit, vertically
+    let pixels_per_unit = (viewport.view_proj.y_axis.y * viewport.size.y / 2.0).abs();
+    loop {
+        let atlas_size = atlas.size();
+        let mut geometry = UiGeometry::default();
+        for (global, text) in query.iter(world) {
+            let affine = global.0;
+            let scale = pixels_per_unit * affine.matrix2.y_axis.length();
+            if text.text.is_empty() || scale.is_nan() || scale <= 0.0 {
+                continue;
+            }
+            let layout = layout_text(
+                fonts,
+                text.font.as_deref(),
+                &text.text,
+                text.size,
+                text.max_width,
+                text.align,
+            );
+            let half = layout.size / 2.0;
+            // Layout pixels (y down from the top-left) -> world
+            let to_world = |p: Vec2| {
+                let local = Vec2::new(p.x / scale - half.x, half.y - p.y / scale);
+                affine.transform_point2(local).to_array()
+            };
+            let color = [text.color.0, text.color.1, text.color.2, text.color.3];
+            for quad in layout.glyph_quads(fonts, atlas, scale) {
+                let first = geometry.vertices.len() as u32;
+                let corners = [
+                    (quad.min, quad.uv_min),
+                    (
+                        Vec2::new(quad.max.x, quad.min.y),
+                        Vec2::new(quad.uv_max.x, quad.uv_min.y),
+                    ),
+                    (quad.max, quad.uv_max),
+                    (
+                        Vec2::new(quad.min.x, quad.max.y),
+                        Vec2::new(quad.uv_min.x, quad.uv_max.y),
+                    ),
+                ];
+                geometry
+                    .vertices
+                    .extend(corners.map(|(position, uv)| GuiVertex {
+                        position: to_world(position),
+                        uv: uv.to_array(),
+                        color,
+                    }));
+                geometry
+                    .indices
+                    .extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+            }
+        }
+        // The atlas grew, so earlier glyphs' UVs are off
+        if atlas.size() != atlas_size {
+            continue;
+        }
+        if !geometry.indices.is_empty() {
+            geometry.batches.push(GuiBatch {
+                texture: GLYPH_ATLAS_TEXTURE,
+                first_index: 0,
+                index_count: geometry.indices.len() as u32,
+            });
+        }
+        return geometry;
+    }
+}
+
 /// Convert a Scene into a list of MeshData for rendering.
 /// Currently uses `load_mesh_stub()` for placeholder mesh loading.
 pub fn scene_to_meshes(scene: &Scene) -> Vec<MeshData> {
     let mut meshes = Vec::new();
     for entity in &scene.entities {
-        if entity.mesh.is_some() {
+        if entity.has_component(engine_scene::Mesh::TYPE_NAME) {
             if let Some(mesh_data) = load_mesh_stub(entity) {
                 meshes.push(mesh_data);
             } else {
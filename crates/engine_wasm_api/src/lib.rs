@@ -2,33 +2,51 @@
  * path: /crates/engine_wasm_api/src/lib.rs
  * description: WASM bindings for the engine using wasm-bindgen.
  */
-use crate::js_sys::Date;
 use engine_core::{
-    set_mode,
     EngineApp,
-    Mode,
+    FixedTime,
+    Plugin,
+};
+use engine_input::{
+    ActionBinding,
+    ActionMap,
+    InputOverrides,
+    InputPlugin,
+    InputRecorder,
+    InputRecording,
+    InputReplay,
+};
+use engine_picking::PickingPlugin;
+use engine_scene::{
+    ComponentRegistry,
+    HierarchyPlugin,
+    PrefabLibrary,
+    Scene,
+    SceneFormat,
+    SceneLoader,
+    ScenePlugin,
+};
+use engine_text::GlyphAtlas;
+use engine_ui::UiPlugin;
+use platform_web::{
+    AnimationLoop,
+    CanvasResizer,
+    InputListener,
+};
+use std::cell::RefCell;
+use std::rc::{
+    Rc,
+    Weak,
 };
-use engine_scene::Scene;
-use js_sys;
-use platform_web::WgpuContext;
-// use std::cell::RefCell;
-// use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlCanvasElement;
-use engine_render::{
-    BGPipeline, 
-    QuadPipeline, 
-    MeshPipeline, 
-    GUIPipeline,
-};
 
+mod engine_state;
 mod helpers;
-use helpers::{scene_to_instances, scene_to_meshes};
-use engine_types::{
-    InstanceData,
-    MeshData,
-};
+use engine_state::EngineState;
+use engine_types::MeshData;
 
 // Build info functions
 fn build_id() -> &'static str {
@@ -47,6 +65,9 @@ pub struct EngineOptions {
     assets_base_url: Option<String>,
     enable_2d: bool,
     enable_3d: bool,
+    auto_resize: bool,
+    max_resolution_scale: f32,
+    fixed_hz: f32,
 }
 
 #[wasm_bindgen]
@@ -58,6 +79,9 @@ impl EngineOptions {
             assets_base_url: None,
             enable_2d: true,
             enable_3d: true,
+            auto_resize: true,
+            max_resolution_scale: platform_web::canvas_resize::DEFAULT_MAX_RESOLUTION_SCALE as f32,
+            fixed_hz: engine_core::DEFAULT_FIXED_HZ,
         }
     }
     pub fn canvas_id(mut self, id: String) -> Self {
@@ -76,67 +100,66 @@ impl EngineOptions {
         self.enable_3d = v;
         self
     }
+    /// Keep the canvas backing store at CSS size × devicePixelRatio (default: true).
+    pub fn auto_resize(mut self, v: bool) -> Self {
+        self.auto_resize = v;
+        self
+    }
+    /// Cap the devicePixelRatio used for the backing store (default: 2.0, <= 0 = uncapped).
+    pub fn max_resolution_scale(mut self, v: f32) -> Self {
+        self.max_resolution_scale = v;
+        self
+    }
+    /// Fixed simulation rate in steps per second (default: 60).
+    pub fn fixed_hz(mut self, v: f32) -> Self {
+        self.fixed_hz = v;
+        self
+    }
 }
 
+/// JS handle to the engine.
+/// All state lives in a shared `EngineState`; the RAF loop and async callbacks only hold
+/// weak references to it, so dropping the handle (`free()` from JS) tears everything down.
+/// Methods take `&self` so JS may call them while an async method (e.g. `mount_async`) is pending.
 #[wasm_bindgen]
 pub struct Engine {
-    app: EngineApp,
-    canvas: Option<HtmlCanvasElement>,
-    gfx: Option<WgpuContext>,
-    raf_handle: Option<
-        std::rc::Rc<std::cell::RefCell<Option<wasm_bindgen::closure::Closure<dyn FnMut(f64)>>>>,
-    >,
-    bg_pipeline: Option<BGPipeline>,
-    quad_pipeline: Option<QuadPipeline>,
-    mesh_pipeline: Option<MeshPipeline>,
-    gui_pipeline: Option<GUIPipeline>,
-    mesh_data: Option<MeshData>,
-    current_scene: Option<Scene>,
-    running: bool,
-    last_ts: f64,
-    tick_ts: f64, // only show error after x seconds and not every tick
-    tick_interval: f64,
+    state: Rc<RefCell<EngineState>>,
+    // Dropping the loop cancels the pending frame (see `stop()`)
+    raf_loop: RefCell<Option<AnimationLoop>>,
 }
 
 #[wasm_bindgen]
 impl Engine {
-    pub async fn mount_async(&mut self) -> Result<(), JsValue> {
-        let canvas = self
-            .canvas
-            .take()
-            .ok_or(JsValue::from_str("no canvas bound"))?;
-        let gfx = platform_web::wgpu_init::init_wgpu(canvas).await?;
+    pub async fn mount_async(&self) -> Result<(), JsValue> {
+        // Never hold a borrow across an await: JS may call into the engine meanwhile
+        let (canvas, auto_resize, max_resolution_scale) = {
+            let mut state = self.state.borrow_mut();
+            let canvas = state
+                .canvas
+                .take()
+                .ok_or(JsValue::from_str("no canvas bound"))?;
+            (canvas, state.auto_resize, state.max_resolution_scale)
+        };
 
-        // Prepare empty quad pipeline
-        let quad_instances: Vec<InstanceData> = Vec::new();
-        self.quad_pipeline = Some(QuadPipeline::new(&gfx.device, gfx.config.format, &quad_instances));
-
-        // Prepare empty mesh pipeline
-        let mesh_data: &MeshData = &self.mesh_data.clone().unwrap();
-        self.mesh_pipeline = Some(MeshPipeline::new(
-            &gfx.device,
-            gfx.config.format,
-            &mesh_data.vertices,
-            &mesh_data.indices,
-        ));
-        
-        self.gfx = Some(gfx);
-
-        // After creating gfx and pipelines, update camera projections
-        if let (Some(quad_pipeline), Some(gfx)) = (self.quad_pipeline.as_ref(), self.gfx.as_ref()) {
-            quad_pipeline.update_camera(&gfx.queue, gfx.config.width, gfx.config.height);
+        // Size the backing store from CSS size × DPR before the surface is configured
+        if auto_resize {
+            let resizer = CanvasResizer::attach(
+                &canvas,
+                max_resolution_scale as f64,
+                platform_web::canvas_resize::DEFAULT_MAX_DIMENSION,
+            )?;
+            self.state.borrow_mut().resizer = Some(resizer);
         }
-        if let (Some(mesh_pipeline), Some(gfx)) = (self.mesh_pipeline.as_ref(), self.gfx.as_ref()) {
-            mesh_pipeline.update_camera(&gfx.queue, gfx.config.width, gfx.config.height);
-        }
-
-        self.tick_ts = 15000.0;
+        let input = InputListener::attach(&canvas)?;
+        self.state.borrow_mut().input = Some(input);
+        let gfx = platform_web::wgpu_init::init_wgpu(canvas).await?;
+        self.state.borrow_mut().install_gfx(gfx);
 
         Ok(())
     }
 
-    pub fn mount(&mut self) -> Result<(), JsValue> {
-        if self.canvas.is_none() {
+    pub fn mount(&self) -> Result<(), JsValue> {
+        if self.state.borrow().canvas.is_none() {
             return Err(JsValue::from_str("no canvas bound"));
         }
         // TODO: configure wgpu surface for the canvas here
@@ -144,266 +167,498 @@ impl Engine {
     }
 
     // Reconfigure the WebGPU surface to match the current canvas size.
-    // This should be called on window resize or when surface acquisition fails.
-    pub fn reconfigure_surface(&mut self) {
-        if let Some(gfx) = self.gfx.as_mut() {
-            let old_w = gfx.config.width;
-            let old_h = gfx.config.height;
-            let new_w = gfx.canvas.width().max(1);
-            let new_h = gfx.canvas.height().max(1);
-
-            if old_w != new_w || old_h != new_h {
-                web_sys::console::log_1(&format!("Surface resize detected: {old_w}x{old_h} -> {new_w}x{new_h}").into());
-            }
-
-            platform_web::wgpu_init::reconfigure_surface(gfx);
-
-            // ✅ Update camera projection to match new canvas size
-            if let Some(quad_pipeline) = self.quad_pipeline.as_ref() {
-                quad_pipeline.update_camera(&gfx.queue, new_w, new_h);
-            }
-            if let Some(mesh_pipeline) = self.mesh_pipeline.as_ref() {
-                mesh_pipeline.update_camera(&gfx.queue, new_w, new_h);
-            }
-        } else {
-            web_sys::console::warn_1(&"reconfigure_surface called but gfx is None".into());
-        }
+    // Called automatically when auto_resize is enabled; hosts that size the canvas
+    // themselves should call it on resize.
+    pub fn reconfigure_surface(&self) {
+        self.state.borrow_mut().reconfigure_surface();
     }
 
     /// Start the requestAnimationFrame loop.
-    pub fn start(&mut self) -> Result<(), JsValue> {
-        if self.running {
+    pub fn start(&self) -> Result<(), JsValue> {
+        if self.raf_loop.borrow().is_some() {
             return Ok(());
         }
-        self.running = true;
-
-        let window = web_sys::window().ok_or("no window")?;
-        // Raw pointer so the closure can call back into self
-        let engine_ptr: *mut Engine = self as *mut _;
-
-        // Shared handle that will keep the closure alive.
-        use std::cell::RefCell;
-        use std::rc::Rc;
-        let f: Rc<RefCell<Option<wasm_bindgen::closure::Closure<dyn FnMut(f64)>>>> =
-            Rc::new(RefCell::new(None));
-        let f_for_closure = Rc::clone(&f);
-
-        // Install the closure
-        *f.borrow_mut() = Some(wasm_bindgen::closure::Closure::wrap(
-            Box::new(move |ts_ms: f64| {
-                // SAFETY: Engine lives as long as start()/stop() contract
-                let engine: &mut Engine = unsafe { &mut *engine_ptr };
-                if !engine.running {
-                    return; // allow graceful stop
-                }
-
-                let dt_ms = (ts_ms - engine.last_ts) as f32;
-                engine.last_ts = ts_ms;
-                engine.tick(dt_ms);
-
-                if let Some(win) = web_sys::window() {
-                    // Borrow immutably just to pass the same JS function back to RAF
-                    if let Some(cb) = f_for_closure.borrow().as_ref() {
-                        let _ = win.request_animation_frame(cb.as_ref().unchecked_ref());
-                    }
-                }
-            }) as Box<dyn FnMut(f64)>,
-        ));
-
-        // Kick off the first frame using the same handle.
-        {
-            let cb_ref = f.borrow();
-            let cb_func = cb_ref
-                .as_ref()
-                .ok_or(JsValue::from_str("RAF closure missing"))?;
-            let _ = window.request_animation_frame(cb_func.as_ref().unchecked_ref());
-        } // immutable borrow ends here
-
-        // Keep the Rc alive on self (do not move the closure out)
-        self.raf_handle = Some(f);
 
+        let weak: Weak<RefCell<EngineState>> = Rc::downgrade(&self.state);
+        let raf_loop = AnimationLoop::start(move |ts_ms: f64| {
+            let Some(state) = weak.upgrade() else {
+                return;
+            };
+            // Skip the frame instead of panicking if the state is borrowed elsewhere
+            let Ok(mut state) = state.try_borrow_mut() else {
+                return;
+            };
+            state.frame(ts_ms);
+            request_ui_textures(&weak, &mut state);
+        })?;
+
+        self.state.borrow_mut().last_ts = None;
+        *self.raf_loop.borrow_mut() = Some(raf_loop);
         Ok(())
     }
 
-    /// Stop the RAF loop gracefully.
-    pub fn stop(&mut self) -> Result<(), JsValue> {
-        self.running = false;
-        if let Some(handle) = self.raf_handle.take() {
-            // Drop the JS closure so the browser can GC it
-            *handle.borrow_mut() = None;
-        }
+    /// Stop the RAF loop. Cancels the pending frame and frees the JS closure.
+    pub fn stop(&self) -> Result<(), JsValue> {
+        self.raf_loop.borrow_mut().take();
         Ok(())
     }
 
-    /// Start the Hot Reload WebSocket and register a simple asset-changed handler.
+    /// Start the Hot Reload WebSocket. When the current scene's URL or one of its prefabs changes
+    /// on disk, it is re-fetched and only the differences are applied to the world. Input maps,
+    /// UI documents and fonts are reloaded too; UI nodes are updated in place, keeping their
+    /// state.
     #[wasm_bindgen]
-    pub fn start_hot_reload(&mut self) -> Result<(), JsValue> {
-        platform_web::start_hot_reload(|url: String| {
+    pub fn start_hot_reload(&self) -> Result<(), JsValue> {
+        let weak: Weak<RefCell<EngineState>> = Rc::downgrade(&self.state);
+        platform_web::start_hot_reload(move |url: String| {
             web_sys::console::log_1(&format!("Hot reload: asset changed at {url}").into());
-            // TODO: integrate with engine_assets::hot_reload_stub(url);
+            let Some(state) = weak.upgrade() else {
+                return;
+            };
+            let is_current_scene = state
+                .borrow()
+                .current_scene_url
+                .as_deref()
+                .is_some_and(|scene_url| same_asset(scene_url, &url));
+            if is_current_scene {
+                spawn_local(reload_scene(Rc::downgrade(&state), url));
+                return;
+            }
+            let is_input_map = state
+                .borrow()
+                .input_map_url
+                .as_deref()
+                .is_some_and(|map_url| same_asset(map_url, &url));
+            if is_input_map {
+                spawn_local(reload_input_map(Rc::downgrade(&state), url));
+                return;
+            }
+            let ui_document = state
+                .borrow()
+                .ui_documents
+                .iter()
+                .find(|(path, _)| same_asset(path, &url))
+                .map(|(path, _)| path.clone());
+            if let Some(path) = ui_document {
+                spawn_local(reload_ui_document(Rc::downgrade(&state), path));
+                return;
+            }
+            let font = state
+                .borrow()
+                .fonts
+                .iter()
+                .find(|(path, _)| same_asset(path, &url))
+                .cloned();
+            if let Some((path, name)) = font {
+                spawn_local(reload_font(Rc::downgrade(&state), path, name));
+                return;
+            }
+            let prefab = state
+                .borrow()
+                .prefabs
+                .paths()
+                .find(|path| same_asset(path, &url))
+                .map(str::to_string);
+            if let Some(path) = prefab {
+                // Dropping it makes the reload fetch the new version
+                state.borrow_mut().prefabs.remove(&path);
+                spawn_local(reload_prefab(Rc::downgrade(&state), path));
+            }
+            // TODO: integrate other asset kinds with engine_assets::hot_reload_stub(url);
         })
     }
 
-    
-    pub fn tick(&mut self, _dt_ms: f32) {
-        self.app.update();
-        if self.tick_ts < 0.0 {
-            self.tick_ts = 0.0;
-        }
-        self.tick_ts += _dt_ms as f64;
-        // web_sys::console::log_1(&format!("self.tick_ts: {0} _dt_ms {1}", self.tick_ts, _dt_ms).into());
+    pub fn tick(&self, dt_ms: f32) {
+        let mut state = self.state.borrow_mut();
+        state.tick(dt_ms);
+        request_ui_textures(&Rc::downgrade(&self.state), &mut state);
+    }
 
-        let Some(gfx) = self.gfx.as_mut() else { 
-            if self.tick_ts >= self.tick_interval {
-                web_sys::console::error_1(&"gfx None in tick()".into());
+    /// Load a RON scene. Prefabs it uses that aren't loaded yet are fetched first;
+    /// in that case the scene appears once they arrive.
+    pub fn load_scene_from_ron(&self, ron_str: &str) -> Result<(), JsValue> {
+        let scene = parse_scene(&self.state, ron_str.as_bytes(), "<inline scene>")?;
+        self.state.borrow_mut().current_scene_url = None;
+        if self.state.borrow().prefabs.missing(&scene).is_empty() {
+            return self.state.borrow_mut().apply_scene(scene);
+        }
+        let weak = Rc::downgrade(&self.state);
+        spawn_local(async move {
+            if let Err(e) = load_prefabs_and_apply(&weak, scene).await {
+                web_sys::console::error_1(&format!("Loading scene failed: {e:?}").into());
             }
-            return; 
-        };
+        });
+        Ok(())
+    }
 
-        // Scene -> instances
-        let (_inst_count, _inst_bytes) = if let Some(scene) = self.current_scene.as_ref() {
-            let quad_instances = scene_to_instances(scene);
-            if self.tick_ts >= self.tick_interval {
-                web_sys::console::log_1(&format!("quad_instances: {0}", quad_instances.clone().into_iter().collect::<Vec<_>>().len()).into());
-            }
+    /// Fetch a scene (`.scene.ron` or packed `.scene.bin`) and its prefabs and load it.
+    /// The URL is remembered for hot reload.
+    pub async fn load_scene_from_url(&self, url: String) -> Result<(), JsValue> {
+        let bytes = platform_web::fetch_bytes(&url).await?;
+        let scene = parse_scene(&self.state, &bytes, &url)?;
+        load_prefabs_and_apply(&Rc::downgrade(&self.state), scene).await?;
+        self.state.borrow_mut().current_scene_url = Some(url);
+        Ok(())
+    }
 
-            // Capacity + upload logging happens in pipeline (see below)
-            if let Some(quad_pipeline) = self.quad_pipeline.as_mut() {
-                quad_pipeline.ensure_capacity(&gfx.device, &quad_instances);
-                quad_pipeline.update_instances(&gfx.queue, &quad_instances);
-                (quad_instances.len() as u32, (quad_instances.len() * std::mem::size_of::<InstanceData>()) as u64)
-            } else {
-                if self.tick_ts >= self.tick_interval {
-                    web_sys::console::warn_1(&"quad_pipeline None; cannot upload instances".into());
-                }
-                (0, 0)
-            }
-        } else {
-            if self.tick_ts >= self.tick_interval {
-                web_sys::console::warn_1(&"current_scene None".into());
-            }
-            (0, 0)
-        };
+    /// Fetch action bindings (`.input.ron`) and use them; the URL is remembered for hot reload.
+    pub async fn load_input_map_from_url(&self, url: String) -> Result<(), JsValue> {
+        let bytes = platform_web::fetch_bytes(&url).await?;
+        let map = engine_assets::load_input_map_bytes(&url, &bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.borrow_mut().apply_input_map(url, map);
+        Ok(())
+    }
 
+    /// Fetch a UI document (`.ui.ron`) and show it over the scene. Loading the same URL again
+    /// updates the document in place; the URL is remembered for hot reload.
+    pub async fn load_ui_document_from_url(&self, url: String) -> Result<(), JsValue> {
+        let bytes = platform_web::fetch_bytes(&url).await?;
+        let document = engine_assets::load_ui_document_bytes(&url, &bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.borrow_mut().apply_ui_document(url, &document);
+        Ok(())
+    }
 
-        // Creating pipeline if missing and mesh_data exists
-        if self.mesh_pipeline.is_none() {
-            if let Some(mesh_data) = &self.mesh_data {
-                if !mesh_data.vertices.is_empty()
-                && !mesh_data.indices.is_empty()
-                {
-                    web_sys::console::log_1(&"Creating MeshPipeline.".into());
-                    self.mesh_pipeline = Some(MeshPipeline::new(
-                        &gfx.device,
-                        gfx.config.format,
-                        &mesh_data.vertices,
-                        &mesh_data.indices,
-                    ));
-                }
-            }
-        }
+    /// Fetch a TTF/OTF font for UI and world text, which refer to it by `name`; the first font
+    /// loaded is the default. The URL is remembered for hot reload.
+    pub async fn load_font_from_url(&self, url: String, name: String) -> Result<(), JsValue> {
+        let bytes = platform_web::fetch_bytes(&url).await?;
+        let font = engine_assets::load_font_bytes(&name, &url, bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.borrow_mut().apply_font(url, font);
+        Ok(())
+    }
 
-        // Update Mesh data for Rendering
-        if let (Some(mesh_pipeline), Some(mesh_data)) = (self.mesh_pipeline.as_mut(), self.mesh_data.as_ref()) {
-            mesh_pipeline.ensure_vertex_capacity(&gfx.device, &mesh_data.vertices);
-            mesh_pipeline.ensure_index_capacity(&gfx.device, &mesh_data.indices);
-            gfx.queue.write_buffer(&mesh_pipeline.vertex_buffer, 0, bytemuck::cast_slice(&mesh_data.vertices));
-            gfx.queue.write_buffer(&mesh_pipeline.index_buffer, 0, bytemuck::cast_slice(&mesh_data.indices));
+    /// Remove a UI document loaded from `url`.
+    pub fn unload_ui_document(&self, url: &str) {
+        self.state.borrow_mut().remove_ui_document(url);
+    }
+
+    /// Enable or disable an input context (e.g. "menu", "gameplay").
+    pub fn set_input_context(&self, context: &str, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+        let mut actions = state.app.world.resource_mut::<ActionMap>();
+        if enabled {
+            actions.enable_context(context);
         } else {
-            if self.tick_ts >= self.tick_interval {
-                web_sys::console::warn_1(&"mesh_pipeline None; cannot upload mesh_data".into());
-            }
+            actions.disable_context(context);
         }
+    }
 
-        // Debug log instance count and bytes
-        // web_sys::console::log_1(&format!("inst_count = {}, inst_bytes = {}", inst_count, inst_bytes).into());
-
-        // Acquire + reconfigure path already logs; keep it
-        let frame = match gfx.surface.get_current_texture() {
-            Ok(frame) => frame,
-            Err(err) => {
-                web_sys::console::warn_1(
-                    &format!("surface acquire error, reconfiguring: {err:?}").into(),
-                );
-                platform_web::wgpu_init::reconfigure_surface(gfx);
-
-                match gfx.surface.get_current_texture() {
-                    Ok(f) => f,
-                    Err(e2) => {
-                        web_sys::console::error_1(
-                            &format!("acquire failed after reconfigure: {e2:?}").into(),
-                        );
-                        return;
-                    }
-                }
-            }
+    /// Rebind an action for the user, e.g. `rebind_action("gameplay", "jump", "Button([Key(KeyK)])")`.
+    pub fn rebind_action(
+        &self,
+        context: &str,
+        action: &str,
+        binding_ron: &str,
+    ) -> Result<(), JsValue> {
+        let binding: ActionBinding =
+            ron::from_str(binding_ron).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut state = self.state.borrow_mut();
+        state
+            .app
+            .world
+            .resource_mut::<ActionMap>()
+            .rebind(context, action, binding);
+        Ok(())
+    }
+
+    /// Drop the user's binding of an action and use the authored one again.
+    pub fn reset_action_binding(&self, context: &str, action: &str) {
+        let mut state = self.state.borrow_mut();
+        state
+            .app
+            .world
+            .resource_mut::<ActionMap>()
+            .reset_binding(context, action);
+    }
+
+    /// The user's rebindings as RON, for the host to save (e.g. in localStorage).
+    pub fn input_overrides(&self) -> Result<String, JsValue> {
+        let state = self.state.borrow();
+        state
+            .app
+            .world
+            .resource::<ActionMap>()
+            .overrides()
+            .to_ron_string()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Restore rebindings saved from `input_overrides()`.
+    pub fn set_input_overrides(&self, ron_str: &str) -> Result<(), JsValue> {
+        let overrides =
+            InputOverrides::from_ron_str(ron_str).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut state = self.state.borrow_mut();
+        state
+            .app
+            .world
+            .resource_mut::<ActionMap>()
+            .set_overrides(overrides);
+        Ok(())
+    }
+
+    /// Start recording every frame's input (e.g. for a bug report); see `stop_input_recording`.
+    pub fn start_input_recording(&self) {
+        let mut state = self.state.borrow_mut();
+        state.app.world.resource_mut::<InputRecorder>().start();
+    }
+
+    /// Stop recording and return the recording as RON (`.replay.ron`), replayable with
+    /// `replay_input` or `engine_runner --replay`.
+    pub fn stop_input_recording(&self) -> Result<String, JsValue> {
+        let mut state = self.state.borrow_mut();
+        let recording = state
+            .app
+            .world
+            .resource_mut::<InputRecorder>()
+            .stop()
+            .ok_or_else(|| JsValue::from_str("not recording"))?;
+        recording
+            .to_ron_string()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Drive the engine with a recording instead of live input until it ends. Load the scene
+    /// the recording started from and enter Play mode first.
+    pub fn replay_input(&self, ron_str: &str) -> Result<(), JsValue> {
+        let recording =
+            InputRecording::from_ron_str(ron_str).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut state = self.state.borrow_mut();
+        let replay = InputReplay::start(recording, &mut state.app);
+        state.replay = Some(replay);
+        Ok(())
+    }
+
+    pub fn is_replaying_input(&self) -> bool {
+        self.state.borrow().replay.is_some()
+    }
+
+    pub fn set_play_mode(&self, play: bool) {
+        self.state.borrow_mut().set_play_mode(play);
+    }
+
+    /// Scale simulation time (1.0 = real time, 0.5 = slow motion).
+    pub fn set_time_scale(&self, scale: f32) {
+        self.state.borrow_mut().set_time_scale(scale);
+    }
+
+    /// Pause/resume simulation time; rendering continues.
+    pub fn set_paused(&self, paused: bool) {
+        self.state.borrow_mut().set_paused(paused);
+    }
+}
+
+/// Rust-side API for games compiled into the same wasm module.
+impl Engine {
+    /// Build a plugin (systems/resources) into the engine's `EngineApp`.
+    pub fn add_plugin(&self, plugin: impl Plugin) {
+        self.state.borrow_mut().app.add_plugin(plugin);
+    }
+
+    /// Run `f` with mutable access to the `EngineApp` (world, schedules).
+    pub fn with_app<R>(&self, f: impl FnOnce(&mut EngineApp) -> R) -> R {
+        f(&mut self.state.borrow_mut().app)
+    }
+}
+
+/// Compare asset URLs by path, ignoring origin and a leading slash
+/// (the dev server reports `/assets/foo.ron`, hosts may pass `assets/foo.ron`).
+pub(crate) fn same_asset(a: &str, b: &str) -> bool {
+    fn path(url: &str) -> &str {
+        let url = url.split(['?', '#']).next().unwrap_or(url);
+        let url = match url.find("://") {
+            Some(i) => url[i + 3..].find('/').map_or("", |j| &url[i + 3 + j..]),
+            None => url,
         };
+        url.trim_start_matches('/')
+    }
+    path(a) == path(b)
+}
 
-        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let mut encoder = gfx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("ironhold_encoder"),
-        });
+/// Parse and validate a scene against the app's component types; the format follows the
+/// file extension of `file`. Validation errors are returned as one message, warnings are logged.
+fn parse_scene(state: &RefCell<EngineState>, bytes: &[u8], file: &str) -> Result<Scene, JsValue> {
+    let state = state.borrow();
+    let path = file.split(['?', '#']).next().unwrap_or(file);
+    let (scene, diagnostics) = SceneLoader::new()
+        .file(file)
+        .components(state.app.world.resource::<ComponentRegistry>())
+        .load_as(SceneFormat::from_path(path), bytes)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    if !diagnostics.is_empty() {
+        web_sys::console::warn_1(&diagnostics.to_string().into());
+    }
+    Ok(scene)
+}
 
-        // Start a render pass
-        {
-            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("ironhold_render_pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    depth_slice: None,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 135.0/255.0, g: 206.0/255.0, b: 235.0/255.0, a: 1.0 }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-
-            if let Some(bg_pipeline) = self.bg_pipeline.as_ref() {
-                bg_pipeline.draw(&mut rpass);
-            }
-            if let Some(quad_pipeline) = self.quad_pipeline.as_ref() {
-                quad_pipeline.draw(&mut rpass);
-            } else {
-                if self.tick_ts >= self.tick_interval {
-                    web_sys::console::warn_1(&"quad_pipeline None; skipping draw".into());
-                }
-            }
-            if let (Some(mesh_pipeline), Some(mesh_data)) = (self.mesh_pipeline.as_ref(), &self.mesh_data ) {
-                if !mesh_data.vertices.is_empty()
-                && !mesh_data.indices.is_empty()
-                {
-                    mesh_pipeline.draw(&mut rpass);
-                }
-            } else {
-                if self.tick_ts >= self.tick_interval {
-                    web_sys::console::warn_1(&"mesh_pipeline None; skipping draw".into());
-                }
-            }
+/// Asset paths in scenes and UI (prefabs, images) are relative to the served root, like scene
+/// URLs (`assets/prefabs/enemy.scene.ron`).
+fn asset_url(path: &str) -> String {
+    if path.starts_with('/') || path.contains("://") {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    }
+}
+
+fn upgrade(state: &Weak<RefCell<EngineState>>) -> Result<Rc<RefCell<EngineState>>, JsValue> {
+    state
+        .upgrade()
+        .ok_or_else(|| JsValue::from_str("engine was freed"))
+}
+
+/// Fetch every prefab `scene` needs (including nested ones), then spawn it.
+async fn load_prefabs_and_apply(
+    state: &Weak<RefCell<EngineState>>,
+    scene: Scene,
+) -> Result<(), JsValue> {
+    load_prefabs(state, &scene).await?;
+    upgrade(state)?.borrow_mut().apply_scene(scene)?;
+    load_mesh(state).await
+}
+
+/// Fetch every prefab `scene` needs (including nested ones), then patch the spawned scene
+/// to match it (hot reload).
+async fn load_prefabs_and_patch(
+    state: &Weak<RefCell<EngineState>>,
+    scene: Scene,
+) -> Result<(), JsValue> {
+    load_prefabs(state, &scene).await?;
+    upgrade(state)?.borrow_mut().patch_scene(scene)?;
+    load_mesh(state).await
+}
+
+/// Fetch the prefabs `scene` needs that aren't loaded. No borrow is held across the fetches.
+async fn load_prefabs(state: &Weak<RefCell<EngineState>>, scene: &Scene) -> Result<(), JsValue> {
+    loop {
+        let missing = upgrade(state)?.borrow().prefabs.missing(scene);
+        if missing.is_empty() {
+            break;
         }
+        for path in missing {
+            let bytes = platform_web::fetch_bytes(&asset_url(&path)).await?;
+            let prefab = parse_scene(&*upgrade(state)?, &bytes, &path)?;
+            upgrade(state)?.borrow_mut().prefabs.insert(path, prefab);
+        }
+    }
+    Ok(())
+}
 
-        gfx.queue.submit(Some(encoder.finish()));
-        frame.present();
+/// Fetch the spawned scene's mesh if it changed, and make the `Mesh` entities pickable.
+async fn load_mesh(state: &Weak<RefCell<EngineState>>) -> Result<(), JsValue> {
+    let Some(file) = upgrade(state)?.borrow().missing_mesh() else {
+        return Ok(());
+    };
+    let bytes = platform_web::fetch_bytes(&asset_url(&file)).await?;
+    let mesh = engine_assets::load_mesh_bytes(&file, &bytes)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    upgrade(state)?.borrow_mut().set_mesh_data(file, mesh);
+    Ok(())
+}
 
-        if self.tick_ts >= self.tick_interval {
-            self.tick_ts = self.tick_ts - self.tick_interval;
-            // web_sys::console::log_1(&"tick completed".into());
+async fn reload_scene(state: Weak<RefCell<EngineState>>, url: String) {
+    let parsed = platform_web::fetch_bytes(&url)
+        .await
+        .and_then(|bytes| parse_scene(&*upgrade(&state)?, &bytes, &url));
+    let result = match parsed {
+        Ok(scene) => load_prefabs_and_patch(&state, scene).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => web_sys::console::log_1(&format!("Hot reload: scene reloaded from {url}").into()),
+        Err(e) => web_sys::console::error_1(&format!("Hot reload of {url} failed: {e:?}").into()),
+    }
+}
+
+async fn reload_input_map(state: Weak<RefCell<EngineState>>, url: String) {
+    let result = platform_web::fetch_bytes(&url).await.and_then(|bytes| {
+        engine_assets::load_input_map_bytes(&url, &bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    });
+    match (result, state.upgrade()) {
+        (Ok(map), Some(state)) => {
+            state.borrow_mut().apply_input_map(url.clone(), map);
+            web_sys::console::log_1(&format!("Hot reload: input map reloaded from {url}").into());
+        }
+        (Err(e), _) => {
+            web_sys::console::error_1(&format!("Hot reload of {url} failed: {e:?}").into())
         }
+        (Ok(_), None) => {}
     }
+}
 
+async fn reload_ui_document(state: Weak<RefCell<EngineState>>, url: String) {
+    let result = platform_web::fetch_bytes(&url).await.and_then(|bytes| {
+        engine_assets::load_ui_document_bytes(&url, &bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    });
+    match (result, state.upgrade()) {
+        (Ok(document), Some(state)) => {
+            state.borrow_mut().apply_ui_document(url.clone(), &document);
+            web_sys::console::log_1(&format!("Hot reload: UI reloaded from {url}").into());
+        }
+        (Err(e), _) => {
+            web_sys::console::error_1(&format!("Hot reload of {url} failed: {e:?}").into())
+        }
+        (Ok(_), None) => {}
+    }
+}
 
-    pub fn load_scene_from_ron(&mut self, ron_str: &str) -> Result<(), JsValue> {
-        let scene: Scene =
-            Scene::from_ron_str(ron_str).map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.current_scene = Some(scene);
-        Ok(())
+async fn reload_font(state: Weak<RefCell<EngineState>>, url: String, name: String) {
+    let result = platform_web::fetch_bytes(&url).await.and_then(|bytes| {
+        engine_assets::load_font_bytes(&name, &url, bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    });
+    match (result, state.upgrade()) {
+        (Ok(font), Some(state)) => {
+            state.borrow_mut().apply_font(url.clone(), font);
+            web_sys::console::log_1(&format!("Hot reload: font {name} reloaded from {url}").into());
+        }
+        (Err(e), _) => {
+            web_sys::console::error_1(&format!("Hot reload of {url} failed: {e:?}").into())
+        }
+        (Ok(_), None) => {}
     }
+}
 
-    pub fn set_play_mode(&mut self, play: bool) {
-        set_mode(&mut self.app, if play { Mode::Play } else { Mode::Edit });
+/// Re-apply the current scene after `path` was dropped from the prefab library.
+async fn reload_prefab(state: Weak<RefCell<EngineState>>, path: String) {
+    let Some(scene) = state
+        .upgrade()
+        .and_then(|s| s.borrow().current_scene.clone())
+    else {
+        return;
+    };
+    match load_prefabs_and_patch(&state, scene).await {
+        Ok(()) => web_sys::console::log_1(&format!("Hot reload: prefab {path} reloaded").into()),
+        Err(e) => {
+            web_sys::console::error_1(&format!("Hot reload of prefab {path} failed: {e:?}").into())
+        }
+    }
+}
+
+/// Fetch the images the UI started using; each is uploaded and laid out once it arrives.
+fn request_ui_textures(weak: &Weak<RefCell<EngineState>>, state: &mut EngineState) {
+    for (id, path) in state.take_ui_texture_requests() {
+        spawn_local(load_ui_texture(weak.clone(), id, path));
+    }
+}
+
+async fn load_ui_texture(state: Weak<RefCell<EngineState>>, id: u32, path: String) {
+    let result = platform_web::fetch_bytes(&asset_url(&path))
+        .await
+        .and_then(|bytes| {
+            engine_assets::load_image_bytes(&path, &bytes)
+                .map_err(|e| JsValue::from_str(&e.to_string()))
+        });
+    match (result, state.upgrade()) {
+        (Ok(image), Some(state)) => state.borrow_mut().set_ui_texture(id, image),
+        (Err(e), _) => {
+            web_sys::console::error_1(&format!("Loading UI image {path} failed: {e:?}").into())
+        }
+        (Ok(_), None) => {}
     }
 }
 
@@ -437,23 +692,49 @@ pub async fn init(opts: EngineOptions) -> Result<Engine, JsValue> {
         );
     }
 
-    Ok(Engine {
-        app: EngineApp::default(),
+    let mut app = EngineApp::default();
+    app.insert_resource(FixedTime::from_hz(opts.fixed_hz));
+    app.add_plugin(InputPlugin);
+    app.add_plugin(PickingPlugin);
+    app.add_plugin(HierarchyPlugin);
+    app.add_plugin(ScenePlugin);
+    app.add_plugin(UiPlugin);
+
+    let state = EngineState {
+        app,
         canvas,
         gfx: None,
-        raf_handle: None,
+        resizer: None,
+        input: None,
+        replay: None,
+        auto_resize: opts.auto_resize,
+        max_resolution_scale: opts.max_resolution_scale,
         bg_pipeline: None,
         quad_pipeline: None,
         mesh_pipeline: None,
         gui_pipeline: None,
+        world_text_pipeline: None,
+        glyph_atlas: GlyphAtlas::new(),
         mesh_data: Some(MeshData {
             vertices: Vec::new(),
             indices: Vec::new(),
         }),
-        running: false,
-        last_ts: Date::now(), // milliseconds
+        mesh_url: None,
         current_scene: None,
+        resolved_scene: None,
+        scene_instance: None,
+        current_scene_url: None,
+        input_map_url: None,
+        ui_documents: Vec::new(),
+        fonts: Vec::new(),
+        prefabs: PrefabLibrary::new(),
+        last_ts: None,
         tick_ts: 15000.0,
         tick_interval: 15000.0,
+    };
+
+    Ok(Engine {
+        state: Rc::new(RefCell::new(state)),
+        raf_loop: RefCell::new(None),
     })
-}
\ No newline at end of file
+}
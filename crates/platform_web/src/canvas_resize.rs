@@ -0,0 +1,200 @@
+/**
+ * path: /crates/platform_web/src/canvas_resize.rs
+ * description: Automatic canvas backing-store sizing from CSS size × devicePixelRatio.
+ * A ResizeObserver watches the bound canvas and records the new pixel size; the engine
+ * polls it once per frame and reconfigures the surface and cameras when it changes.
+ */
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    HtmlCanvasElement,
+    ResizeObserver,
+    ResizeObserverEntry,
+};
+
+/// Default cap on the devicePixelRatio multiplier (e.g. 3x phones render at 2x).
+pub const DEFAULT_MAX_RESOLUTION_SCALE: f64 = 2.0;
+
+/// WebGPU's guaranteed `maxTextureDimension2D`; used until the device limits are known.
+pub const DEFAULT_MAX_DIMENSION: u32 = 8192;
+
+/// Compute the canvas backing-store size for a CSS size and devicePixelRatio.
+/// - `max_resolution_scale`: upper bound for the effective pixel ratio (<= 0 means uncapped)
+/// - `max_dimension`: upper bound per axis (e.g. `Limits::max_texture_dimension_2d`)
+pub fn backing_size(
+    css_width: f64,
+    css_height: f64,
+    device_pixel_ratio: f64,
+    max_resolution_scale: f64,
+    max_dimension: u32,
+) -> (u32, u32) {
+    let dpr = if device_pixel_ratio.is_finite() && device_pixel_ratio > 0.0 {
+        device_pixel_ratio
+    } else {
+        1.0
+    };
+    let scale = if max_resolution_scale > 0.0 {
+        dpr.min(max_resolution_scale)
+    } else {
+        dpr
+    };
+    let max_dimension = max_dimension.max(1);
+    let to_pixels = |css: f64| -> u32 {
+        let px = (css.max(0.0) * scale).round();
+        (px as u32).clamp(1, max_dimension)
+    };
+    (to_pixels(css_width), to_pixels(css_height))
+}
+
+fn current_device_pixel_ratio() -> f64 {
+    web_sys::window()
+        .map(|w| w.device_pixel_ratio())
+        .unwrap_or(1.0)
+}
+
+fn apply_css_size(
+    canvas: &HtmlCanvasElement,
+    css_width: f64,
+    css_height: f64,
+    device_pixel_ratio: f64,
+    max_resolution_scale: f64,
+    max_dimension: u32,
+) -> ((u32, u32), bool) {
+    let (w, h) = backing_size(
+        css_width,
+        css_height,
+        device_pixel_ratio,
+        max_resolution_scale,
+        max_dimension,
+    );
+    let changed = canvas.width() != w || canvas.height() != h;
+    if changed {
+        canvas.set_width(w);
+        canvas.set_height(h);
+    }
+    ((w, h), changed)
+}
+
+/// Shared state between the observer callback and the engine.
+struct ResizeState {
+    max_resolution_scale: f64,
+    max_dimension: Cell<u32>,
+    last_css_size: Cell<(f64, f64)>,
+    last_dpr: Cell<f64>,
+    pending: Cell<Option<(u32, u32)>>,
+}
+
+/// Keeps a canvas' backing store in sync with its CSS size and devicePixelRatio.
+/// The canvas must get its CSS size from layout (e.g. `width: 100%`), otherwise
+/// changing `canvas.width` would feed back into the observed size.
+/// Dropping the resizer disconnects the observer.
+pub struct CanvasResizer {
+    canvas: HtmlCanvasElement,
+    observer: ResizeObserver,
+    state: Rc<ResizeState>,
+    _on_resize: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl CanvasResizer {
+    /// Fit the canvas once and start observing it for CSS size changes.
+    pub fn attach(
+        canvas: &HtmlCanvasElement,
+        max_resolution_scale: f64,
+        max_dimension: u32,
+    ) -> Result<Self, JsValue> {
+        let rect = canvas.get_bounding_client_rect();
+        let dpr = current_device_pixel_ratio();
+        let (size, changed) = apply_css_size(
+            canvas,
+            rect.width(),
+            rect.height(),
+            dpr,
+            max_resolution_scale,
+            max_dimension,
+        );
+
+        let state = Rc::new(ResizeState {
+            max_resolution_scale,
+            max_dimension: Cell::new(max_dimension),
+            last_css_size: Cell::new((rect.width(), rect.height())),
+            last_dpr: Cell::new(dpr),
+            pending: Cell::new(changed.then_some(size)),
+        });
+
+        let on_resize = {
+            let state = state.clone();
+            let canvas = canvas.clone();
+            Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+                // Only the bound canvas is observed; the last entry holds its latest size.
+                let Some(entry) = entries
+                    .iter()
+                    .last()
+                    .and_then(|e| e.dyn_into::<ResizeObserverEntry>().ok())
+                else {
+                    return;
+                };
+                let rect = entry.content_rect();
+                let dpr = current_device_pixel_ratio();
+                state.last_css_size.set((rect.width(), rect.height()));
+                state.last_dpr.set(dpr);
+                let (size, changed) = apply_css_size(
+                    &canvas,
+                    rect.width(),
+                    rect.height(),
+                    dpr,
+                    state.max_resolution_scale,
+                    state.max_dimension.get(),
+                );
+                if changed {
+                    state.pending.set(Some(size));
+                }
+            })
+        };
+
+        let observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref())?;
+        observer.observe(canvas);
+
+        Ok(Self {
+            canvas: canvas.clone(),
+            observer,
+            state,
+            _on_resize: on_resize,
+        })
+    }
+
+    /// Clamp future sizes to a device limit (e.g. once the adapter limits are known).
+    pub fn set_max_dimension(&self, max_dimension: u32) {
+        self.state.max_dimension.set(max_dimension);
+    }
+
+    /// Return the new backing-store size if it changed since the last poll.
+    /// Also catches devicePixelRatio changes (zoom, moving between monitors),
+    /// which do not trigger the ResizeObserver on their own.
+    pub fn poll(&self) -> Option<(u32, u32)> {
+        let dpr = current_device_pixel_ratio();
+        if dpr != self.state.last_dpr.get() {
+            self.state.last_dpr.set(dpr);
+            let (css_w, css_h) = self.state.last_css_size.get();
+            let (size, changed) = apply_css_size(
+                &self.canvas,
+                css_w,
+                css_h,
+                dpr,
+                self.state.max_resolution_scale,
+                self.state.max_dimension.get(),
+            );
+            if changed {
+                self.state.pending.set(Some(size));
+            }
+        }
+        self.state.pending.take()
+    }
+}
+
+impl Drop for CanvasResizer {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
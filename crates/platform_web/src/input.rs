@@ -0,0 +1,301 @@
+/**
+ * path: /crates/platform_web/src/input.rs
+ * description: Web input backend: DOM events on the bound canvas → `engine_input::InputEvent`s.
+ * Pointer events cover mouse, pen and touch; keyboard events need the canvas focused (it is
+ * made focusable and focused on click). Gamepads have no events worth trusting across
+ * browsers and are polled from `navigator.getGamepads()` when the queue is drained.
+ */
+use engine_input::{
+    GamepadAxis,
+    GamepadButton,
+    GamepadId,
+    InputEvent,
+    KeyCode,
+    MouseButton,
+    PIXELS_PER_LINE,
+};
+use glam::Vec2;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Event,
+    HtmlCanvasElement,
+    KeyboardEvent,
+    PointerEvent,
+    WheelEvent,
+};
+
+type Handler = Closure<dyn FnMut(Event)>;
+
+/// Lines per wheel "page" (`WheelEvent.DOM_DELTA_PAGE`).
+const LINES_PER_PAGE: f32 = 20.0;
+
+/// Keys whose default action scrolls the page.
+const SCROLL_KEYS: [KeyCode; 9] = [
+    KeyCode::Space,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+];
+
+/// Last polled state of a gamepad.
+#[derive(Default)]
+struct PadSnapshot {
+    buttons: Vec<bool>,
+    axes: Vec<f32>,
+}
+
+/// Listens to input on a canvas and buffers it until the next `drain`.
+/// Dropping it removes the listeners.
+pub struct InputListener {
+    canvas: HtmlCanvasElement,
+    events: Rc<RefCell<Vec<InputEvent>>>,
+    handlers: Vec<(&'static str, Handler)>,
+    pads: RefCell<BTreeMap<u32, PadSnapshot>>,
+}
+
+impl InputListener {
+    pub fn attach(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        // Focusable for keyboard events; touches must not scroll or zoom the page
+        if !canvas.has_attribute("tabindex") {
+            canvas.set_attribute("tabindex", "0")?;
+        }
+        canvas.style().set_property("touch-action", "none")?;
+
+        let events: Rc<RefCell<Vec<InputEvent>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut listener = Self {
+            canvas: canvas.clone(),
+            events: events.clone(),
+            handlers: Vec::new(),
+            pads: RefCell::new(BTreeMap::new()),
+        };
+
+        listener.on("pointerdown", {
+            let (events, canvas) = (events.clone(), canvas.clone());
+            move |e: PointerEvent| {
+                let _ = canvas.focus();
+                // Keep receiving moves/ups while dragging outside the canvas
+                let _ = canvas.set_pointer_capture(e.pointer_id());
+                let position = canvas_position(&canvas, &e);
+                let event = if e.pointer_type() == "touch" {
+                    InputEvent::TouchStart {
+                        id: e.pointer_id() as u64,
+                        position,
+                    }
+                } else {
+                    events.borrow_mut().push(InputEvent::CursorMoved(position));
+                    InputEvent::MouseDown(MouseButton::from_dom(e.button()))
+                };
+                events.borrow_mut().push(event);
+            }
+        })?;
+        listener.on("pointermove", {
+            let (events, canvas) = (events.clone(), canvas.clone());
+            move |e: PointerEvent| {
+                let position = canvas_position(&canvas, &e);
+                events.borrow_mut().push(if e.pointer_type() == "touch" {
+                    InputEvent::TouchMove {
+                        id: e.pointer_id() as u64,
+                        position,
+                    }
+                } else {
+                    InputEvent::CursorMoved(position)
+                });
+            }
+        })?;
+        for kind in ["pointerup", "pointercancel"] {
+            listener.on(kind, {
+                let (events, canvas) = (events.clone(), canvas.clone());
+                move |e: PointerEvent| {
+                    let position = canvas_position(&canvas, &e);
+                    let event = if e.pointer_type() == "touch" {
+                        InputEvent::TouchEnd {
+                            id: e.pointer_id() as u64,
+                            position,
+                        }
+                    } else if e.type_() == "pointerup" {
+                        InputEvent::MouseUp(MouseButton::from_dom(e.button()))
+                    } else {
+                        return;
+                    };
+                    events.borrow_mut().push(event);
+                }
+            })?;
+        }
+        listener.on("pointerleave", {
+            let events = events.clone();
+            move |e: PointerEvent| {
+                if e.pointer_type() != "touch" {
+                    events.borrow_mut().push(InputEvent::CursorLeft);
+                }
+            }
+        })?;
+        listener.on("wheel", {
+            let events = events.clone();
+            move |e: WheelEvent| {
+                e.prevent_default();
+                let delta = Vec2::new(e.delta_x() as f32, e.delta_y() as f32);
+                let lines = match e.delta_mode() {
+                    WheelEvent::DOM_DELTA_LINE => delta,
+                    WheelEvent::DOM_DELTA_PAGE => delta * LINES_PER_PAGE,
+                    _ => delta / PIXELS_PER_LINE,
+                };
+                events.borrow_mut().push(InputEvent::Wheel(lines));
+            }
+        })?;
+        // Right-drag instead of the context menu
+        listener.on("contextmenu", |e: Event| e.prevent_default())?;
+        for kind in ["keydown", "keyup"] {
+            listener.on(kind, {
+                let events = events.clone();
+                move |e: KeyboardEvent| {
+                    let Some(key) = KeyCode::from_code(&e.code()) else {
+                        return;
+                    };
+                    if SCROLL_KEYS.contains(&key) {
+                        e.prevent_default();
+                    }
+                    if e.type_() == "keyup" {
+                        events.borrow_mut().push(InputEvent::KeyUp(key));
+                    } else if !e.repeat() {
+                        events.borrow_mut().push(InputEvent::KeyDown(key));
+                    }
+                }
+            })?;
+        }
+        // Key-ups go elsewhere once focus moves (also fires when the tab is hidden)
+        listener.on("blur", {
+            let events = events.clone();
+            move |_: Event| events.borrow_mut().push(InputEvent::FocusLost)
+        })?;
+
+        Ok(listener)
+    }
+
+    fn on<E: JsCast + 'static>(
+        &mut self,
+        kind: &'static str,
+        mut handler: impl FnMut(E) + 'static,
+    ) -> Result<(), JsValue> {
+        let closure = Handler::new(move |e: Event| {
+            if let Ok(e) = e.dyn_into::<E>() {
+                handler(e);
+            }
+        });
+        self.canvas
+            .add_event_listener_with_callback(kind, closure.as_ref().unchecked_ref())?;
+        self.handlers.push((kind, closure));
+        Ok(())
+    }
+
+    /// Events received since the last call, followed by gamepad changes since the last poll.
+    pub fn drain(&self) -> Vec<InputEvent> {
+        let mut events = std::mem::take(&mut *self.events.borrow_mut());
+        self.poll_gamepads(&mut events);
+        events
+    }
+
+    fn poll_gamepads(&self, out: &mut Vec<InputEvent>) {
+        let Some(pads) = web_sys::window().and_then(|w| w.navigator().get_gamepads().ok()) else {
+            return;
+        };
+        let mut snapshots = self.pads.borrow_mut();
+        let mut seen = Vec::new();
+        for pad in pads.iter() {
+            let Ok(pad) = pad.dyn_into::<web_sys::Gamepad>() else {
+                continue;
+            };
+            if !pad.connected() {
+                continue;
+            }
+            let index = pad.index();
+            let id = GamepadId(index);
+            seen.push(index);
+            let snapshot = snapshots.entry(index).or_insert_with(|| {
+                out.push(InputEvent::GamepadConnected { id, name: pad.id() });
+                PadSnapshot::default()
+            });
+
+            let buttons: Vec<web_sys::GamepadButton> = pad
+                .buttons()
+                .iter()
+                .filter_map(|b| b.dyn_into().ok())
+                .collect();
+            snapshot.buttons.resize(buttons.len(), false);
+            for (i, button) in buttons.iter().enumerate() {
+                let Some(kind) = GamepadButton::from_standard_index(i) else {
+                    continue;
+                };
+                if button.pressed() != snapshot.buttons[i] {
+                    snapshot.buttons[i] = button.pressed();
+                    out.push(InputEvent::GamepadButton {
+                        id,
+                        button: kind,
+                        pressed: button.pressed(),
+                    });
+                }
+            }
+
+            // Sticks, then the analog triggers (standard buttons 6 and 7)
+            let mut axes: Vec<(GamepadAxis, f32)> = pad
+                .axes()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, v)| {
+                    Some((GamepadAxis::from_standard_index(i)?, v.as_f64()? as f32))
+                })
+                .collect();
+            for (i, axis) in [
+                (6, GamepadAxis::LeftTrigger),
+                (7, GamepadAxis::RightTrigger),
+            ] {
+                if let Some(button) = buttons.get(i) {
+                    axes.push((axis, button.value() as f32));
+                }
+            }
+            snapshot.axes.resize(axes.len(), 0.0);
+            for (i, (axis, value)) in axes.into_iter().enumerate() {
+                if value != snapshot.axes[i] {
+                    snapshot.axes[i] = value;
+                    out.push(InputEvent::GamepadAxis { id, axis, value });
+                }
+            }
+        }
+        snapshots.retain(|index, _| {
+            let connected = seen.contains(index);
+            if !connected {
+                out.push(InputEvent::GamepadDisconnected(GamepadId(*index)));
+            }
+            connected
+        });
+    }
+}
+
+impl Drop for InputListener {
+    fn drop(&mut self) {
+        for (kind, closure) in &self.handlers {
+            let _ = self
+                .canvas
+                .remove_event_listener_with_callback(kind, closure.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// Pointer position in canvas pixels (backing store), from CSS pixels.
+fn canvas_position(canvas: &HtmlCanvasElement, e: &PointerEvent) -> Vec2 {
+    let rect = canvas.get_bounding_client_rect();
+    let scale_x = canvas.width() as f64 / rect.width().max(1.0);
+    let scale_y = canvas.height() as f64 / rect.height().max(1.0);
+    Vec2::new(
+        ((e.client_x() as f64 - rect.left()) * scale_x) as f32,
+        ((e.client_y() as f64 - rect.top()) * scale_y) as f32,
+    )
+}
@@ -3,7 +3,13 @@
  * description: Web platform support for the project, including WebGPU feature detection
  * and hot-reloading capabilities.
  */
+pub mod canvas_resize;
+pub mod input;
+pub mod raf_loop;
 pub mod wgpu_init;
+pub use canvas_resize::CanvasResizer;
+pub use input::InputListener;
+pub use raf_loop::AnimationLoop;
 pub use wgpu_init::WgpuContext;
 
 use js_sys::ArrayBuffer;
@@ -11,11 +17,13 @@ use serde::Deserialize;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{
     Blob,
     Event,
     FileReader,
     MessageEvent,
+    Response,
     WebSocket,
 };
 
@@ -71,6 +79,39 @@ pub fn compute_ws_url() -> Result<String, JsValue> {
     Ok(format!("{ws_scheme}://{hostname}:{ws_port}/ws"))
 }
 
+/// Fetch a text resource (e.g. a RON scene) via `window.fetch`.
+pub async fn fetch_text(url: &str) -> Result<String, JsValue> {
+    let window = web_sys::window().ok_or(JsValue::from_str("no window"))?;
+    let resp: Response = JsFuture::from(window.fetch_with_str(url))
+        .await?
+        .dyn_into()?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!(
+            "fetch {url} failed: HTTP {}",
+            resp.status()
+        )));
+    }
+    let text = JsFuture::from(resp.text()?).await?;
+    text.as_string()
+        .ok_or_else(|| JsValue::from_str(&format!("fetch {url}: body is not text")))
+}
+
+/// Fetch a binary resource (e.g. a packed `.scene.bin`) via `window.fetch`.
+pub async fn fetch_bytes(url: &str) -> Result<Vec<u8>, JsValue> {
+    let window = web_sys::window().ok_or(JsValue::from_str("no window"))?;
+    let resp: Response = JsFuture::from(window.fetch_with_str(url))
+        .await?
+        .dyn_into()?;
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!(
+            "fetch {url} failed: HTTP {}",
+            resp.status()
+        )));
+    }
+    let buffer: ArrayBuffer = JsFuture::from(resp.array_buffer()?).await?.dyn_into()?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
 /// Start a WebSocket connection for hot reload notifications.
 pub fn start_hot_reload<F>(on_asset_changed: F) -> Result<(), JsValue>
 where
@@ -0,0 +1,86 @@
+/**
+ * path: /crates/platform_web/src/raf_loop.rs
+ * description: requestAnimationFrame loop with explicit ownership.
+ * The loop owns its JS closure; the closure only holds a weak reference back to the loop,
+ * so dropping the `AnimationLoop` cancels the pending frame and frees the closure.
+ */
+use std::cell::{
+    Cell,
+    RefCell,
+};
+use std::rc::{
+    Rc,
+    Weak,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+struct LoopInner {
+    callback: RefCell<Option<FrameCallback>>,
+    frame_id: Cell<Option<i32>>,
+}
+
+impl LoopInner {
+    fn request_frame(&self) -> Result<(), JsValue> {
+        let window = web_sys::window().ok_or("no window")?;
+        let callback = self.callback.borrow();
+        let callback = callback
+            .as_ref()
+            .ok_or(JsValue::from_str("RAF closure missing"))?;
+        let id = window.request_animation_frame(callback.as_ref().unchecked_ref())?;
+        self.frame_id.set(Some(id));
+        Ok(())
+    }
+
+    fn cancel_frame(&self) {
+        if let (Some(id), Some(window)) = (self.frame_id.take(), web_sys::window()) {
+            let _ = window.cancel_animation_frame(id);
+        }
+    }
+}
+
+/// A running requestAnimationFrame loop. Dropping it stops the loop.
+pub struct AnimationLoop {
+    inner: Rc<LoopInner>,
+}
+
+impl AnimationLoop {
+    /// Call `on_frame(timestamp_ms)` once per animation frame until the loop is dropped.
+    pub fn start<F>(mut on_frame: F) -> Result<Self, JsValue>
+    where
+        F: 'static + FnMut(f64),
+    {
+        let inner = Rc::new(LoopInner {
+            callback: RefCell::new(None),
+            frame_id: Cell::new(None),
+        });
+
+        let weak: Weak<LoopInner> = Rc::downgrade(&inner);
+        *inner.callback.borrow_mut() = Some(FrameCallback::new(move |ts_ms: f64| {
+            // The loop was dropped after this frame was scheduled
+            let Some(inner) = weak.upgrade() else {
+                return;
+            };
+            inner.frame_id.set(None);
+            on_frame(ts_ms);
+            // Only reschedule if the owning `AnimationLoop` survived the frame
+            if Rc::strong_count(&inner) < 2 {
+                return;
+            }
+            if let Err(e) = inner.request_frame() {
+                web_sys::console::error_1(&format!("requestAnimationFrame failed: {e:?}").into());
+            }
+        }));
+
+        inner.request_frame()?;
+        Ok(Self { inner })
+    }
+}
+
+impl Drop for AnimationLoop {
+    fn drop(&mut self) {
+        self.inner.cancel_frame();
+    }
+}
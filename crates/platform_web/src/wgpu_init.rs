@@ -50,8 +50,10 @@ impl rwh::HasDisplayHandle for CanvasHandle {
 }
 
 pub fn reconfigure_surface(gfx: &mut WgpuContext) {
-    let new_w = gfx.canvas.width().max(1);
-    let new_h = gfx.canvas.height().max(1);
+    // Never exceed what the device can allocate for the swapchain texture
+    let max_dim = gfx.device.limits().max_texture_dimension_2d.max(1);
+    let new_w = gfx.canvas.width().clamp(1, max_dim);
+    let new_h = gfx.canvas.height().clamp(1, max_dim);
     // Update size if changed
     if gfx.config.width != new_w || gfx.config.height != new_h {
         gfx.config.width = new_w;
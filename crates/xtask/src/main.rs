@@ -65,6 +65,28 @@ enum Cmd {
         #[arg(long, default_value = "docs/project_snapshot.txt")]
         out: String,
     },
+    /// Upgrade *.scene.ron files to the current scene format version (rewrites in place)
+    MigrateScenes {
+        /// Files or directories to scan
+        #[arg(default_value = "assets")]
+        paths: Vec<String>,
+        /// Only list outdated files; exit with an error if any are found
+        #[arg(long)]
+        check: bool,
+        /// Also rewrite files with comments inside the scene, which migrating drops
+        #[arg(long)]
+        force: bool,
+    },
+    /// Convert an assets folder for shipping: scenes to packed `.scene.bin`, glTF meshes to
+    /// cooked `.mesh.bin`, everything else copied. Paths inside scenes are rewritten to match.
+    PackAssets {
+        /// Source folder
+        #[arg(default_value = "assets")]
+        src: String,
+        /// Output folder (serve its parent as the root, like the source's)
+        #[arg(long, default_value = "dist/assets")]
+        out: String,
+    },
     /// Format the repository using nightly rustfmt
     Fmt {
         /// Run in check mode (does not modify files)
@@ -537,6 +559,232 @@ fn run_fmt(check: bool, extra: &[String]) {
     println!("Formatting completed via cargo +nightly fmt");
 }
 
+/// Rewrite outdated scenes in the current format. Comments before the scene are kept; files
+/// with comments inside it are only rewritten with `force`, as those comments are lost.
+fn migrate_scenes(paths: &[String], check: bool, force: bool) {
+    use engine_scene::{
+        scene_version,
+        Scene,
+        SceneWriter,
+        CURRENT_SCENE_VERSION,
+    };
+
+    let mut outdated = 0;
+    let mut failed = 0;
+    let files = paths.iter().flat_map(|p| WalkDir::new(p).into_iter().filter_map(Result::ok));
+    for entry in files.filter(|e| e.file_name().to_string_lossy().ends_with(".scene.ron")) {
+        let path = entry.path();
+        let result = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                let doc: ron::Value = ron::from_str(&text).map_err(|e| e.to_string())?;
+                let version = scene_version(&doc).map_err(|e| e.to_string())?;
+                if version >= CURRENT_SCENE_VERSION {
+                    return Ok(None);
+                }
+                let scene = Scene::from_ron_str(&text).map_err(|e| e.to_string())?;
+                Ok(Some((version, scene, text)))
+            });
+        match result {
+            Ok(None) => {}
+            Ok(Some((version, scene, text))) => {
+                outdated += 1;
+                let (header, body) = split_leading_comments(&text);
+                let dropped = count_comments(body);
+                if check {
+                    println!("{}: version {version} (current {CURRENT_SCENE_VERSION})", path.display());
+                } else if dropped > 0 && !force {
+                    eprintln!(
+                        "{}: not migrated: {dropped} comment(s) inside the scene would be lost (rerun with --force to drop them)",
+                        path.display()
+                    );
+                    failed += 1;
+                } else if let Err(e) = SceneWriter::new()
+                    .write(&scene)
+                    .map_err(|e| e.to_string())
+                    .and_then(|migrated| fs::write(path, format!("{header}{migrated}")).map_err(|e| e.to_string()))
+                {
+                    eprintln!("{}: {e}", path.display());
+                    failed += 1;
+                } else {
+                    println!("{}: migrated {version} -> {CURRENT_SCENE_VERSION}", path.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 || (check && outdated > 0) {
+        std::process::exit(1);
+    }
+    if outdated == 0 {
+        println!("All scenes are at version {CURRENT_SCENE_VERSION}");
+    }
+}
+
+/// Comment and blank lines before the document (without `#![..]` attributes, which the writer
+/// adds itself), and the rest of the text.
+fn split_leading_comments(text: &str) -> (String, &str) {
+    let mut header = String::new();
+    let mut rest = text;
+    while let Some(line) = rest.split_inclusive('\n').next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("#![") {
+            rest = &rest[line.len()..];
+        } else if trimmed.is_empty() || trimmed.starts_with("//") {
+            header.push_str(line);
+            rest = &rest[line.len()..];
+        } else {
+            break;
+        }
+    }
+    (header.trim_start_matches(['\n', '\r']).to_string(), rest)
+}
+
+/// `//` and `/* */` comments outside string literals.
+fn count_comments(text: &str) -> usize {
+    let mut count = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if matches!(chars.peek(), Some('/') | Some('*')) => {
+                count += 1;
+                let block = chars.next() == Some('*');
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if (block && last == '*' && c == '/') || (!block && c == '\n') {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Shipping name of a source asset path (`.scene.ron` -> `.scene.bin`, `.glb`/`.gltf` -> `.mesh.bin`).
+fn packed_asset_path(path: &str) -> Option<String> {
+    use engine_scene::SCENE_BINARY_EXTENSION;
+    use engine_types::MESH_BINARY_EXTENSION;
+
+    if let Some(stem) = path.strip_suffix(".scene.ron") {
+        return Some(format!("{stem}.{SCENE_BINARY_EXTENSION}"));
+    }
+    [".glb", ".gltf"]
+        .iter()
+        .find_map(|ext| path.strip_suffix(ext))
+        .map(|stem| format!("{stem}.{MESH_BINARY_EXTENSION}"))
+}
+
+/// Validate a RON scene, point its prefab and mesh references at the packed files and encode it.
+fn pack_scene(path: &Path) -> Result<Vec<u8>, String> {
+    use engine_scene::{
+        Mesh,
+        SceneComponent,
+        SceneLoader,
+        SceneValue,
+    };
+
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let (mut scene, diagnostics) = SceneLoader::new()
+        .file(path.display().to_string())
+        .load(&text)
+        .map_err(|e| e.to_string())?;
+    if !diagnostics.is_empty() {
+        eprintln!("{diagnostics}");
+    }
+
+    let rename = |value: &mut String| {
+        if let Some(packed) = packed_asset_path(value) {
+            *value = packed;
+        }
+    };
+    let mesh_file = format!("{}.file", Mesh::TYPE_NAME);
+    for entity in &mut scene.entities {
+        if let Some(mut mesh) = entity.component::<Mesh>() {
+            rename(&mut mesh.file);
+            entity.set_component(&mesh);
+        }
+        if let Some(prefab) = &mut entity.prefab {
+            rename(&mut prefab.path);
+            for o in prefab.overrides.iter_mut().filter(|o| o.field == mesh_file) {
+                if let SceneValue::String(file) = &mut o.value {
+                    rename(file);
+                }
+            }
+        }
+    }
+    scene.to_bytes().map_err(|e| e.to_string())
+}
+
+fn pack_assets(src: &str, out: &str) {
+    let src = Path::new(src);
+    let mut failed = 0;
+    let (mut src_bytes, mut out_bytes) = (0u64, 0u64);
+    for entry in WalkDir::new(src).into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let rel = path.strip_prefix(src).unwrap_or(path);
+        let rel_str = to_forward_slash(rel);
+        let target = Path::new(out).join(packed_asset_path(&rel_str).unwrap_or(rel_str.clone()));
+
+        let result = if rel_str.ends_with(".scene.ron") {
+            pack_scene(path)
+        } else if rel_str.ends_with(".glb") || rel_str.ends_with(".gltf") {
+            fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| engine_assets::mesh_from_gltf(&bytes).map_err(|e| e.to_string()))
+                .and_then(|mesh| mesh.to_packed().map_err(|e| e.to_string()))
+        } else {
+            fs::read(path).map_err(|e| e.to_string())
+        };
+        let written = result.and_then(|bytes| {
+            if let Some(dir) = target.parent() {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            fs::write(&target, &bytes).map_err(|e| e.to_string())?;
+            Ok(bytes.len() as u64)
+        });
+        match written {
+            Ok(size) => {
+                let original = entry.metadata().map_or(0, |m| m.len());
+                src_bytes += original;
+                out_bytes += size;
+                if packed_asset_path(&rel_str).is_some() {
+                    println!("{} -> {} ({original} -> {size} bytes)", path.display(), target.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    println!("Packed {} into {out}: {src_bytes} -> {out_bytes} bytes", src.display());
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let args = XTask::parse();
     match args.cmd {
@@ -577,6 +825,12 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Cmd::MigrateScenes { paths, check, force } => {
+            migrate_scenes(&paths, check, force);
+        }
+        Cmd::PackAssets { src, out } => {
+            pack_assets(&src, &out);
+        }
         Cmd::Fmt { check, extra } => {
             run_fmt(check, &extra);
         }